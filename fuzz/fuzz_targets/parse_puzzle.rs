@@ -0,0 +1,12 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+use sudoku_solver::prelude::*;
+
+// Any input must either be rejected or parse into a grid of digits 0-9.
+fuzz_target!(|input: &str| {
+    if let Ok(puzzle) = parse_puzzle(input) {
+        assert!(puzzle.as_flattened().iter().all(|digit| *digit <= 9));
+    }
+});
@@ -0,0 +1,20 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+use sudoku_solver::prelude::*;
+
+/// Grids searched per input, so that sparse grids do not time out the fuzzer.
+const MAX_NODES: usize = 1_000_000;
+
+// Any solution found must be a complete, valid grid that keeps every given.
+fuzz_target!(|data: [u8; 81]| {
+    let mut puzzle = [[0; 9]; 9];
+    for (index, byte) in data.into_iter().enumerate() {
+        puzzle[index / 9][index % 9] = byte % 10;
+    }
+
+    if let SolveOutcome::Solved(solution) = Solver::new().max_nodes(MAX_NODES).solve(puzzle) {
+        assert!(verify_solution(puzzle, solution));
+    }
+});
@@ -0,0 +1,55 @@
+/*
+ * C interface to sudoku-solver, built as a cdylib (libsudoku_solver.so / .dylib / .dll).
+ *
+ * Grids are passed as 81-char strings in reading order. Inputs must be NUL-terminated, and use
+ * digits 1-9 for givens and '.' or '0' for blanks; anything else is SUDOKU_INVALID_ARGUMENT.
+ * Outputs are written as 81 digits followed by a NUL, so output buffers must hold at least 82
+ * chars.
+ *
+ * There is no sudoku_generate, as the crate has no puzzle generator.
+ *
+ * Keep in sync with src/ffi.rs.
+ */
+
+#ifndef SUDOKU_SOLVER_H
+#define SUDOKU_SOLVER_H
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* The call succeeded. */
+#define SUDOKU_OK 0
+
+/* The puzzle has no solution. */
+#define SUDOKU_NO_SOLUTION 1
+
+/* An argument is null or is not an 81-char grid. */
+#define SUDOKU_INVALID_ARGUMENT -1
+
+/*
+ * Solves in81 and writes the solution to out81.
+ *
+ * Returns SUDOKU_OK, SUDOKU_NO_SOLUTION or SUDOKU_INVALID_ARGUMENT.
+ */
+int sudoku_solve(const char *in81, char *out81);
+
+/*
+ * Counts the solutions to in81, stopping once limit is reached.
+ *
+ * Returns the count, or SUDOKU_INVALID_ARGUMENT.
+ */
+int sudoku_count(const char *in81, unsigned int limit);
+
+/*
+ * Verifies whether in81 has exactly one solution.
+ *
+ * Returns 1 if it does, 0 if it does not, or SUDOKU_INVALID_ARGUMENT.
+ */
+int sudoku_has_unique_solution(const char *in81);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* SUDOKU_SOLVER_H */
@@ -0,0 +1,766 @@
+use crate::{
+    rng::Rng,
+    sudoku::{
+        GridPos, Puzzle, Solution, Uniqueness, Unit, blanks, candidates, fill_naked_singles,
+        fill_singles, is_valid_puzzle, solve_up_to, uniqueness,
+    },
+};
+
+/// How the givens of a puzzle are spread out.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ClueStats {
+    /// Total number of givens.
+    pub givens: usize,
+
+    /// Number of givens in each row, col and square, indexed as in [Unit].
+    pub per_row: [usize; 9],
+    pub per_col: [usize; 9],
+    pub per_square: [usize; 9],
+
+    /// Number of times each digit is given, indexed by digit - 1.
+    pub per_digit: [usize; 9],
+
+    /// Units without any givens.
+    pub empty_units: Vec<Unit>,
+}
+
+/// Counts the givens of a puzzle, as a quick sanity check before solving it.
+pub fn stats(puzzle: Puzzle) -> ClueStats {
+    let mut stats = ClueStats {
+        givens: 0,
+        per_row: [0; 9],
+        per_col: [0; 9],
+        per_square: [0; 9],
+        per_digit: [0; 9],
+        empty_units: Vec::new(),
+    };
+
+    for (row, digits) in puzzle.iter().enumerate() {
+        for (col, digit) in digits.iter().enumerate() {
+            if *digit == 0 {
+                continue;
+            }
+
+            stats.givens += 1;
+            stats.per_row[row] += 1;
+            stats.per_col[col] += 1;
+            stats.per_square[row / 3 * 3 + col / 3] += 1;
+            stats.per_digit[(digit - 1) as usize] += 1;
+        }
+    }
+
+    stats.empty_units = Unit::all()
+        .filter(|unit| match *unit {
+            Unit::Row(row) => stats.per_row[row] == 0,
+            Unit::Col(col) => stats.per_col[col] == 0,
+            Unit::Square(square) => stats.per_square[square] == 0,
+        })
+        .collect();
+
+    stats
+}
+
+/// A symmetry of the layout of the givens (ignoring which digits they are).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Symmetry {
+    /// Unchanged by a half turn about the center.
+    Rotational180,
+
+    /// Unchanged by a quarter turn about the center.
+    Rotational90,
+
+    /// Unchanged by reflecting left-right, across the middle col.
+    MirrorHorizontal,
+
+    /// Unchanged by reflecting top-bottom, across the middle row.
+    MirrorVertical,
+
+    /// Unchanged by reflecting across the top-left to bottom-right diagonal.
+    Diagonal,
+
+    /// Unchanged by reflecting across the top-right to bottom-left diagonal.
+    AntiDiagonal,
+}
+
+impl Symmetry {
+    pub const ALL: [Symmetry; 6] = [
+        Symmetry::Rotational180,
+        Symmetry::Rotational90,
+        Symmetry::MirrorHorizontal,
+        Symmetry::MirrorVertical,
+        Symmetry::Diagonal,
+        Symmetry::AntiDiagonal,
+    ];
+
+    /// Maps a cell to its image under this symmetry.
+    fn map(self, row: usize, col: usize) -> (usize, usize) {
+        match self {
+            Symmetry::Rotational180 => (8 - row, 8 - col),
+            Symmetry::Rotational90 => (col, 8 - row),
+            Symmetry::MirrorHorizontal => (row, 8 - col),
+            Symmetry::MirrorVertical => (8 - row, col),
+            Symmetry::Diagonal => (col, row),
+            Symmetry::AntiDiagonal => (8 - col, 8 - row),
+        }
+    }
+}
+
+/// Finds the symmetries of the layout of the givens. An empty list means the layout is
+/// asymmetric.
+pub fn detect_symmetry(puzzle: Puzzle) -> Vec<Symmetry> {
+    Symmetry::ALL
+        .into_iter()
+        .filter(|symmetry| {
+            (0..81).all(|cell| {
+                let (row, col) = (cell / 9, cell % 9);
+                let (image_row, image_col) = symmetry.map(row, col);
+
+                (puzzle[row][col] == 0) == (puzzle[image_row][image_col] == 0)
+            })
+        })
+        .collect()
+}
+
+/// Where the solutions of a puzzle differ from each other.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Ambiguity {
+    /// Number of solutions compared.
+    pub solutions: usize,
+
+    /// The digits each cell takes across the solutions, with bit (digit - 1) set for each digit.
+    pub digits: [[u16; 9]; 9],
+
+    /// Blanks that take different digits in different solutions, in reading order.
+    pub varying: Vec<GridPos>,
+
+    /// Blanks that take the same digit in every solution, in reading order.
+    pub forced: Vec<GridPos>,
+}
+
+/// Compares up to limit solutions of a puzzle, to show a setter where to add a clue. Cells that
+/// vary are the candidates for a new given.
+///
+/// If the puzzle has more than limit solutions, only the first limit are compared, so some forced
+/// cells may actually vary. A limit of 0 is taken as 1, as it takes a solution to tell whether
+/// there is any. Returns None if the puzzle has no solution.
+pub fn ambiguity(puzzle: Puzzle, limit: usize) -> Option<Ambiguity> {
+    let solutions = solve_up_to(puzzle, limit.max(1));
+    if solutions.is_empty() {
+        return None;
+    }
+
+    let mut digits = [[0u16; 9]; 9];
+    for solution in &solutions {
+        for (row, solution_digits) in solution.iter().enumerate() {
+            for (col, digit) in solution_digits.iter().enumerate() {
+                digits[row][col] |= 1 << (digit - 1);
+            }
+        }
+    }
+
+    let (varying, forced) = (0..81)
+        .map(|cell| (cell / 9, cell % 9))
+        .filter(|&(row, col)| puzzle[row][col] == 0)
+        .partition(|&(row, col): &GridPos| digits[row][col].count_ones() > 1);
+
+    Some(Ambiguity {
+        solutions: solutions.len(),
+        digits,
+        varying,
+        forced,
+    })
+}
+
+/// Where a plain backtracking search spends its effort on a puzzle.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Profile {
+    /// Number of digits placed (including rejected ones) before a solution was found.
+    pub placements: usize,
+
+    /// Number of placements in each cell that had to be undone, as a heatmap.
+    pub backtracks: [[usize; 9]; 9],
+
+    /// Number of placements of each digit that had to be undone, indexed by digit - 1.
+    pub contested_digits: [usize; 9],
+}
+
+impl Profile {
+    /// Finds the cell that caused the most backtracking, if any did.
+    pub fn hottest(&self) -> Option<GridPos> {
+        (0..81)
+            .map(|cell| (cell / 9, cell % 9))
+            .filter(|&(row, col)| self.backtracks[row][col] > 0)
+            .max_by_key(|&(row, col)| self.backtracks[row][col])
+    }
+}
+
+/// Profiles a plain backtracking search (in reading order, without propagation) on a puzzle,
+/// attributing its wasted effort to cells and digits. Useful to visualize why a puzzle is slow,
+/// and for tuning heuristics.
+///
+/// Returns None if the puzzle has no solution.
+pub fn profile(puzzle: Puzzle) -> Option<Profile> {
+    if !is_valid_puzzle(puzzle) {
+        return None;
+    }
+
+    let mut profile = Profile {
+        placements: 0,
+        backtracks: [[0; 9]; 9],
+        contested_digits: [0; 9],
+    };
+    if profile_search(puzzle, 0, &blanks(puzzle), &mut profile) {
+        Some(profile)
+    } else {
+        None
+    }
+}
+
+/// Plain backtracking search, counting into profile. Returns whether a solution was found.
+fn profile_search(
+    mut puzzle: Puzzle,
+    blank: usize,
+    blanks: &[GridPos],
+    profile: &mut Profile,
+) -> bool {
+    if blank == blanks.len() {
+        return true;
+    }
+
+    let (row, col) = blanks[blank];
+
+    for digit in 1..=9 {
+        puzzle[row][col] = digit;
+        profile.placements += 1;
+
+        if is_valid_puzzle(puzzle) && profile_search(puzzle, blank + 1, blanks, profile) {
+            return true;
+        }
+
+        profile.backtracks[row][col] += 1;
+        profile.contested_digits[(digit - 1) as usize] += 1;
+    }
+
+    false
+}
+
+/// Finds how fragile a puzzle is: for each given, in reading order, how many extra solutions
+/// appear if that given alone is removed (counting up to limit extra solutions). Givens with 0
+/// extra solutions are redundant, while the others carry the uniqueness of the puzzle.
+///
+/// Returns an empty list if the puzzle does not have exactly one solution.
+pub fn clue_sensitivity(puzzle: Puzzle, limit: usize) -> Vec<(GridPos, usize)> {
+    if solve_up_to(puzzle, 2).len() != 1 {
+        return vec![];
+    }
+
+    (0..81)
+        .map(|cell| (cell / 9, cell % 9))
+        .filter(|&(row, col)| puzzle[row][col] != 0)
+        .map(|(row, col)| {
+            let mut reduced = puzzle;
+            reduced[row][col] = 0;
+
+            let extra = solve_up_to(reduced, limit.saturating_add(1)).len() - 1;
+            ((row, col), extra)
+        })
+        .collect()
+}
+
+/// Most alternative grids looked at per set of freed digits in [unavoidable_sets].
+const UNAVOIDABLE_SEARCH_LIMIT: usize = 10_000;
+
+/// Finds the redundant givens of a puzzle: those that can be removed on their own, with the
+/// puzzle still having exactly one solution. Removing one redundant given may make others needed,
+/// so they cannot all be removed at once.
+///
+/// Returns an empty list if the puzzle does not have exactly one solution.
+pub fn redundant_clues(puzzle: Puzzle) -> Vec<GridPos> {
+    if !matches!(uniqueness(puzzle), Uniqueness::One(_)) {
+        return vec![];
+    }
+
+    (0..81)
+        .map(|cell| (cell / 9, cell % 9))
+        .filter(|&(row, col)| puzzle[row][col] != 0)
+        .filter(|&(row, col)| {
+            let mut reduced = puzzle;
+            reduced[row][col] = 0;
+            matches!(uniqueness(reduced), Uniqueness::One(_))
+        })
+        .collect()
+}
+
+/// Verifies whether a puzzle is minimal: it has exactly one solution, and every given is needed
+/// for that.
+pub fn is_minimal(puzzle: Puzzle) -> bool {
+    matches!(uniqueness(puzzle), Uniqueness::One(_)) && redundant_clues(puzzle).is_empty()
+}
+
+/// Removes givens from a puzzle, one at a time in reading order, as long as the puzzle still has
+/// exactly one solution. Gets the minimal puzzle left, and the cells whose givens were removed.
+///
+/// Returns None if the puzzle does not have exactly one solution.
+pub fn minimize(puzzle: Puzzle) -> Option<(Puzzle, Vec<GridPos>)> {
+    let givens = (0..81)
+        .map(|cell| (cell / 9, cell % 9))
+        .filter(|&(row, col)| puzzle[row][col] != 0)
+        .collect::<Vec<_>>();
+
+    minimize_in_order(puzzle, &givens)
+}
+
+/// Like [minimize], but tries removing the givens in attempts random orders as well as in reading
+/// order, and keeps the result with the fewest givens. More attempts take longer, but tend to
+/// remove more givens. The same seed always gives the same result.
+pub fn minimize_with_effort(
+    puzzle: Puzzle,
+    attempts: usize,
+    seed: u64,
+) -> Option<(Puzzle, Vec<GridPos>)> {
+    let mut best = minimize(puzzle)?;
+
+    let mut rng = Rng::new(seed);
+    let mut givens = (0..81)
+        .map(|cell| (cell / 9, cell % 9))
+        .filter(|&(row, col)| puzzle[row][col] != 0)
+        .collect::<Vec<_>>();
+    for _ in 0..attempts {
+        rng.shuffle(&mut givens);
+
+        let (minimal, mut removed) = minimize_in_order(puzzle, &givens)?;
+        if removed.len() > best.1.len() {
+            removed.sort();
+            best = (minimal, removed);
+        }
+    }
+
+    Some(best)
+}
+
+/// Removes the givens in the given order for [minimize].
+fn minimize_in_order(puzzle: Puzzle, givens: &[GridPos]) -> Option<(Puzzle, Vec<GridPos>)> {
+    if !matches!(uniqueness(puzzle), Uniqueness::One(_)) {
+        return None;
+    }
+
+    let mut minimal = puzzle;
+    let mut removed = Vec::new();
+    for &(row, col) in givens {
+        let given = minimal[row][col];
+        minimal[row][col] = 0;
+
+        if matches!(uniqueness(minimal), Uniqueness::One(_)) {
+            removed.push((row, col));
+        } else {
+            minimal[row][col] = given;
+        }
+    }
+
+    Some((minimal, removed))
+}
+
+/// Finds small unavoidable sets of a complete grid -- sets of cells of which at least one must be
+/// a given in any puzzle that has this grid as its unique solution. Sets are minimal, have at most
+/// max_size cells, and are sorted by size.
+///
+/// The sets are found by freeing up all cells holding 2 or 3 chosen digits at a time and looking
+/// for other ways to fill them in, so this finds most, but not necessarily all, small sets.
+/// Returns an empty list if the grid is not a complete, valid solution.
+pub fn unavoidable_sets(solution: Solution, max_size: usize) -> Vec<Vec<GridPos>> {
+    if !is_valid_puzzle(solution) || !blanks(solution).is_empty() {
+        return vec![];
+    }
+
+    let mut sets = Vec::new();
+    for digits in 0u16..(1 << 9) {
+        if !(2..=3).contains(&digits.count_ones()) {
+            continue;
+        }
+
+        let mut puzzle = solution;
+        for digit in puzzle.as_flattened_mut() {
+            if digits & (1 << (*digit - 1)) != 0 {
+                *digit = 0;
+            }
+        }
+
+        for other in solve_up_to(puzzle, UNAVOIDABLE_SEARCH_LIMIT) {
+            let set = (0..81)
+                .map(|cell| (cell / 9, cell % 9))
+                .filter(|&(row, col)| other[row][col] != solution[row][col])
+                .collect::<Vec<_>>();
+
+            if !set.is_empty() && set.len() <= max_size {
+                sets.push(set);
+            }
+        }
+    }
+
+    sets.sort_by(|a, b| a.len().cmp(&b.len()).then_with(|| a.cmp(b)));
+    sets.dedup();
+
+    // Drop any set that contains a smaller one, as hitting the smaller one is enough.
+    let mut minimal: Vec<Vec<GridPos>> = Vec::new();
+    for set in sets {
+        if !minimal
+            .iter()
+            .any(|smaller| smaller.iter().all(|pos| set.contains(pos)))
+        {
+            minimal.push(set);
+        }
+    }
+
+    minimal
+}
+
+/// Estimates the number of solutions to a puzzle, for grids with too many to count, e.g. to gauge
+/// how open a partially built puzzle still is.
+///
+/// Uses Knuth's estimator: each sample follows one random path down the search tree, branching on
+/// a blank with the fewest candidates, and scores the product of the numbers of candidates along
+/// the path if it ends in a solution, or 0 at a dead end. The mean of the scores is an unbiased
+/// estimate, which gets closer with more samples. The same seed always gives the same estimate.
+/// Returns 0.0 if the puzzle is invalid or samples is 0.
+pub fn estimate_solutions(puzzle: Puzzle, samples: usize, seed: u64) -> f64 {
+    if samples == 0 || !is_valid_puzzle(puzzle) {
+        return 0.0;
+    }
+
+    let mut rng = Rng::new(seed);
+    let total = (0..samples)
+        .map(|_| sample_path(puzzle, &mut rng))
+        .sum::<f64>();
+
+    total / samples as f64
+}
+
+/// Follows one random path down the search tree for [estimate_solutions], and gets its score.
+fn sample_path(mut puzzle: Puzzle, rng: &mut Rng) -> f64 {
+    let mut score = 1.0;
+    loop {
+        if fill_naked_singles(&mut puzzle).is_err() {
+            return 0.0;
+        }
+
+        let candidates = candidates(puzzle);
+        let Some((row, col)) = blanks(puzzle)
+            .into_iter()
+            .min_by_key(|&(row, col)| candidates[row][col].count_ones())
+        else {
+            return score;
+        };
+
+        let digits = (1..=9)
+            .filter(|digit| candidates[row][col] & (1 << (digit - 1)) != 0)
+            .collect::<Vec<u8>>();
+        if digits.is_empty() {
+            return 0.0;
+        }
+
+        score *= digits.len() as f64;
+        puzzle[row][col] = digits[rng.below(digits.len())];
+    }
+}
+
+/// Finds the backdoor size of a puzzle: the fewest blanks that must be guessed (filled in from
+/// the solution) before filling in naked and hidden singles finishes the puzzle. A common proxy
+/// for hardness: 0 for puzzles solvable by singles, and rarely more than 3.
+///
+/// Returns None if the puzzle does not have exactly one solution.
+pub fn backdoor_size(puzzle: Puzzle) -> Option<usize> {
+    let Uniqueness::One(solution) = uniqueness(puzzle) else {
+        return None;
+    };
+
+    let mut grid = puzzle;
+    fill_singles(&mut grid);
+    let blanks = blanks(grid);
+
+    (0..=blanks.len()).find(|&size| has_backdoor(grid, solution, &blanks, size))
+}
+
+/// Checks whether guessing size of the given blanks from the solution lets singles finish a grid
+/// that singles alone cannot.
+fn has_backdoor(grid: Puzzle, solution: Solution, blanks: &[GridPos], size: usize) -> bool {
+    if size == 0 {
+        return self::blanks(grid).is_empty();
+    }
+
+    blanks.iter().enumerate().any(|(index, &(row, col))| {
+        let mut guessed = grid;
+        guessed[row][col] = solution[row][col];
+        fill_singles(&mut guessed);
+
+        let rest = blanks[index + 1..]
+            .iter()
+            .copied()
+            .filter(|&(row, col)| guessed[row][col] == 0)
+            .collect::<Vec<_>>();
+        has_backdoor(guessed, solution, &rest, size - 1)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PUZZLE: Puzzle = [
+        [5, 3, 0, 0, 7, 0, 0, 0, 0],
+        [6, 0, 0, 1, 9, 5, 0, 0, 0],
+        [9, 8, 0, 0, 0, 0, 0, 6, 0],
+        [8, 0, 0, 0, 6, 0, 0, 0, 3],
+        [4, 0, 0, 8, 0, 3, 0, 0, 1],
+        [7, 0, 0, 0, 2, 0, 0, 0, 6],
+        [0, 6, 0, 0, 0, 0, 2, 8, 0],
+        [0, 0, 0, 4, 1, 9, 0, 0, 5],
+        [0, 0, 0, 0, 8, 0, 0, 7, 9],
+    ];
+
+    #[test]
+    fn check_stats() {
+        let stats = stats(PUZZLE);
+
+        assert_eq!(stats.givens, 30);
+        assert_eq!(stats.per_row, [3, 4, 3, 3, 4, 3, 3, 4, 3]);
+        assert_eq!(stats.per_col, [6, 3, 0, 3, 6, 3, 1, 3, 5]);
+        assert_eq!(stats.per_square, [5, 4, 1, 3, 4, 3, 1, 4, 5]);
+        assert_eq!(stats.per_digit, [3, 2, 3, 2, 3, 5, 3, 5, 4]);
+        assert_eq!(stats.per_digit.iter().sum::<usize>(), stats.givens);
+        assert_eq!(stats.empty_units, vec![Unit::Col(2)]);
+
+        assert_eq!(super::stats([[0; 9]; 9]).empty_units.len(), 27);
+    }
+
+    #[test]
+    fn check_detect_symmetry() {
+        assert_eq!(detect_symmetry(PUZZLE), vec![]);
+        assert_eq!(detect_symmetry([[0; 9]; 9]), Symmetry::ALL.to_vec());
+
+        let mut puzzle = [[0; 9]; 9];
+        puzzle[0][1] = 1;
+        puzzle[8][7] = 2;
+        assert_eq!(detect_symmetry(puzzle), vec![Symmetry::Rotational180]);
+
+        puzzle[1][0] = 3;
+        puzzle[7][8] = 4;
+        assert_eq!(
+            detect_symmetry(puzzle),
+            vec![
+                Symmetry::Rotational180,
+                Symmetry::Diagonal,
+                Symmetry::AntiDiagonal
+            ]
+        );
+
+        let mut puzzle = [[0; 9]; 9];
+        puzzle[4][0] = 5;
+        puzzle[4][8] = 6;
+        assert_eq!(
+            detect_symmetry(puzzle),
+            vec![
+                Symmetry::Rotational180,
+                Symmetry::MirrorHorizontal,
+                Symmetry::MirrorVertical
+            ]
+        );
+    }
+
+    #[test]
+    fn check_ambiguity() {
+        let unique = ambiguity(PUZZLE, 10).unwrap();
+        assert_eq!(unique.solutions, 1);
+        assert!(unique.varying.is_empty());
+        assert_eq!(unique.forced.len(), 81 - 30);
+
+        // Swapping 1 and 3 in these 4 cells of the solution gives a second solution.
+        let mut puzzle = crate::sudoku::solve_any(PUZZLE).unwrap();
+        for (row, col) in [(3, 5), (3, 8), (4, 5), (4, 8)] {
+            puzzle[row][col] = 0;
+        }
+        puzzle[1][0] = 0;
+
+        let ambiguity = ambiguity(puzzle, 10).unwrap();
+        assert_eq!(ambiguity.solutions, 2);
+        assert_eq!(ambiguity.varying, vec![(3, 5), (3, 8), (4, 5), (4, 8)]);
+        assert_eq!(ambiguity.forced, vec![(1, 0)]);
+        assert_eq!(ambiguity.digits[3][5], 0b101);
+
+        // A limit of 0 still finds a solution.
+        let first = super::ambiguity(puzzle, 0).unwrap();
+        assert_eq!(first.solutions, 1);
+        assert!(first.varying.is_empty());
+
+        let mut puzzle = PUZZLE;
+        puzzle[0][2] = 5;
+        assert_eq!(super::ambiguity(puzzle, 10), None);
+    }
+
+    #[test]
+    fn check_profile() {
+        let profile = profile(PUZZLE).unwrap();
+
+        let backtracks = profile.backtracks.as_flattened().iter().sum::<usize>();
+        assert_eq!(backtracks, profile.contested_digits.iter().sum::<usize>());
+        assert_eq!(profile.placements, backtracks + (81 - 30));
+
+        let (row, col) = profile.hottest().unwrap();
+        assert_eq!(PUZZLE[row][col], 0);
+        assert!(
+            profile
+                .backtracks
+                .as_flattened()
+                .iter()
+                .all(|count| *count <= profile.backtracks[row][col])
+        );
+
+        let solution = crate::sudoku::solve_any(PUZZLE).unwrap();
+        assert_eq!(super::profile(solution).unwrap().hottest(), None);
+
+        let mut puzzle = PUZZLE;
+        puzzle[0][2] = 5;
+        assert_eq!(super::profile(puzzle), None);
+    }
+
+    #[test]
+    fn check_unavoidable_sets() {
+        let solution = crate::sudoku::solve_any(PUZZLE).unwrap();
+        let sets = unavoidable_sets(solution, 6);
+
+        // The 1-3 rectangle in rows 3-4, cols 5 and 8.
+        assert!(sets.contains(&vec![(3, 5), (3, 8), (4, 5), (4, 8)]));
+        assert!(sets.iter().all(|set| (4..=6).contains(&set.len())));
+        assert!(sets.windows(2).all(|pair| pair[0].len() <= pair[1].len()));
+
+        // Each set really is unavoidable: blanking it allows another solution.
+        for set in &sets {
+            let mut puzzle = solution;
+            for &(row, col) in set {
+                puzzle[row][col] = 0;
+            }
+            assert_eq!(solve_up_to(puzzle, 2).len(), 2);
+        }
+
+        assert!(unavoidable_sets(PUZZLE, 6).is_empty());
+    }
+
+    #[test]
+    fn check_estimate_solutions() {
+        let solution = crate::sudoku::solve_any(PUZZLE).unwrap();
+        assert_eq!(estimate_solutions(solution, 10, 0), 1.0);
+
+        // Every path through an unavoidable rectangle guesses once between 2 digits.
+        let mut puzzle = solution;
+        for (row, col) in [(3, 5), (3, 8), (4, 5), (4, 8)] {
+            puzzle[row][col] = 0;
+        }
+        assert_eq!(estimate_solutions(puzzle, 10, 0), 2.0);
+
+        let mut puzzle = PUZZLE;
+        puzzle[0] = [0; 9];
+        puzzle[8] = [0; 9];
+        let count = crate::sudoku::count_solutions(puzzle, None) as f64;
+        let estimate = estimate_solutions(puzzle, 2000, 1);
+        assert!(
+            (estimate - count).abs() < count / 2.0,
+            "{estimate} vs {count}"
+        );
+        assert_eq!(estimate_solutions(puzzle, 2000, 1), estimate);
+
+        let mut puzzle = PUZZLE;
+        puzzle[0][2] = 5;
+        assert_eq!(estimate_solutions(puzzle, 10, 0), 0.0);
+        assert_eq!(estimate_solutions(PUZZLE, 0, 0), 0.0);
+    }
+
+    #[test]
+    fn check_backdoor_size() {
+        assert_eq!(backdoor_size(PUZZLE), Some(0));
+
+        // Arto Inkala's "world's hardest sudoku".
+        let hard = [
+            [8, 0, 0, 0, 0, 0, 0, 0, 0],
+            [0, 0, 3, 6, 0, 0, 0, 0, 0],
+            [0, 7, 0, 0, 9, 0, 2, 0, 0],
+            [0, 5, 0, 0, 0, 7, 0, 0, 0],
+            [0, 0, 0, 0, 4, 5, 7, 0, 0],
+            [0, 0, 0, 1, 0, 0, 0, 3, 0],
+            [0, 0, 1, 0, 0, 0, 0, 6, 8],
+            [0, 0, 8, 5, 0, 0, 0, 1, 0],
+            [0, 9, 0, 0, 0, 0, 4, 0, 0],
+        ];
+        let size = backdoor_size(hard).unwrap();
+        assert!(size >= 1);
+
+        // Guessing any blank of the backdoor from the solution makes it one smaller.
+        let solution = crate::sudoku::solve_any(hard).unwrap();
+        let smaller = (0..81)
+            .map(|cell| (cell / 9, cell % 9))
+            .filter(|&(row, col)| hard[row][col] == 0)
+            .filter_map(|(row, col)| {
+                let mut puzzle = hard;
+                puzzle[row][col] = solution[row][col];
+                backdoor_size(puzzle)
+            })
+            .min();
+        assert_eq!(smaller, Some(size - 1));
+
+        assert_eq!(backdoor_size([[0; 9]; 9]), None);
+        let mut puzzle = PUZZLE;
+        puzzle[0][2] = 5;
+        assert_eq!(backdoor_size(puzzle), None);
+    }
+
+    #[test]
+    fn check_redundant_clues() {
+        let redundant = redundant_clues(PUZZLE);
+        assert!(!redundant.is_empty());
+        assert!(!is_minimal(PUZZLE));
+
+        // Removing redundant givens one at a time until there are none left gives a minimal
+        // puzzle.
+        let mut puzzle = PUZZLE;
+        while let Some(&(row, col)) = redundant_clues(puzzle).first() {
+            puzzle[row][col] = 0;
+        }
+        assert!(is_minimal(puzzle));
+        assert!(crate::sudoku::has_unique_solution(puzzle));
+
+        assert!(redundant_clues([[0; 9]; 9]).is_empty());
+        assert!(!is_minimal([[0; 9]; 9]));
+    }
+
+    #[test]
+    fn check_minimize() {
+        let (minimal, removed) = minimize(PUZZLE).unwrap();
+        assert!(is_minimal(minimal));
+        assert_eq!(stats(minimal).givens + removed.len(), stats(PUZZLE).givens);
+        assert!(removed.iter().all(|&(row, col)| minimal[row][col] == 0));
+        assert!(removed.is_sorted());
+
+        let (harder, removed) = minimize_with_effort(PUZZLE, 3, 0).unwrap();
+        assert!(is_minimal(harder));
+        assert!(stats(harder).givens <= stats(minimal).givens);
+        assert_eq!(stats(harder).givens + removed.len(), stats(PUZZLE).givens);
+        assert_eq!(minimize_with_effort(PUZZLE, 3, 0), Some((harder, removed)));
+
+        assert_eq!(minimize([[0; 9]; 9]), None);
+        assert_eq!(minimize_with_effort([[0; 9]; 9], 3, 0), None);
+    }
+
+    #[test]
+    fn check_clue_sensitivity() {
+        let sensitivity = clue_sensitivity(PUZZLE, 5);
+        assert_eq!(sensitivity.len(), 30);
+        assert_eq!(sensitivity[0].0, (0, 0));
+        assert!(sensitivity.iter().all(|(_, extra)| *extra <= 5));
+
+        // Adding a given from the solution makes that given redundant.
+        let solution = crate::sudoku::solve_any(PUZZLE).unwrap();
+        let mut puzzle = PUZZLE;
+        puzzle[0][2] = solution[0][2];
+        assert!(clue_sensitivity(puzzle, 5).contains(&((0, 2), 0)));
+
+        assert!(clue_sensitivity([[0; 9]; 9], 5).is_empty());
+    }
+}
@@ -0,0 +1,214 @@
+use std::time::{Duration, Instant};
+
+use crate::{
+    io::parse_puzzle,
+    sudoku::{Backend, Puzzle, SolveOutcome, Solver},
+};
+
+/// How hard a puzzle in the benchmark corpus is for a solver.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Tier {
+    /// Solvable by singles.
+    Easy,
+
+    /// Needs guessing, but is fast with good heuristics.
+    Hard,
+
+    /// Built to defeat a particular strategy, e.g. backtracking in reading order.
+    Pathological,
+}
+
+/// A puzzle in the benchmark corpus.
+#[derive(Clone, Copy, Debug)]
+pub struct BenchPuzzle {
+    pub name: &'static str,
+    pub tier: Tier,
+
+    /// The puzzle in the single line format, with . for blanks.
+    pub line: &'static str,
+}
+
+impl BenchPuzzle {
+    pub fn puzzle(&self) -> Puzzle {
+        parse_puzzle(self.line).expect("corpus puzzles should be well formed")
+    }
+}
+
+/// The built-in benchmark corpus. Puzzles are only ever added, so that results stay comparable.
+pub static CORPUS: [BenchPuzzle; 9] = [
+    BenchPuzzle {
+        name: "classic",
+        tier: Tier::Easy,
+        line: "53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79",
+    },
+    BenchPuzzle {
+        name: "euler-1",
+        tier: Tier::Easy,
+        line: "..3.2.6..9..3.5..1..18.64....81.29..7.......8..67.82....26.95..8..2.3..9..5.1.3..",
+    },
+    BenchPuzzle {
+        name: "newspaper",
+        tier: Tier::Easy,
+        line: "2...8.3...6..7..84.3.5..2.9...1.54.8.........4.27.6...3.1..7.4.72..4..6...4.1...3",
+    },
+    BenchPuzzle {
+        name: "inkala",
+        tier: Tier::Hard,
+        line: "8..........36......7..9.2...5...7.......457.....1...3...1....68..85...1..9....4..",
+    },
+    BenchPuzzle {
+        name: "norvig-hard-1",
+        tier: Tier::Hard,
+        line: "4.....8.5.3..........7......2.....6.....8.4......1.......6.3.7.5..2.....1.4......",
+    },
+    BenchPuzzle {
+        name: "easter-monster",
+        tier: Tier::Hard,
+        line: "1.......2.9.4...5...6...7...5.9.3.......7.......85..4.7.....6...3...9.8...2.....1",
+    },
+    BenchPuzzle {
+        name: "anti-backtracking",
+        tier: Tier::Pathological,
+        line: "..............3.85..1.2.......5.7.....4...1...9.......5......73..2.1........4...9",
+    },
+    BenchPuzzle {
+        name: "norvig-hardest",
+        tier: Tier::Pathological,
+        line: ".....6....59.....82....8....45........3........6..3.54...325..6..................",
+    },
+    BenchPuzzle {
+        name: "17-clue",
+        tier: Tier::Pathological,
+        line: "...8.1..........435............7.8........1...2..3....6......75..34........2..6..",
+    },
+];
+
+/// Solve times of one backend over the puzzles of one tier.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BenchReport {
+    pub backend: Backend,
+    pub tier: Tier,
+
+    /// Number of solves that found a solution.
+    pub solved: usize,
+
+    /// Number of solves in total.
+    pub runs: usize,
+
+    pub p50: Duration,
+    pub p90: Duration,
+    pub p99: Duration,
+    pub max: Duration,
+}
+
+/// Gets the backends that find every solution, i.e. the ones worth benchmarking.
+pub fn bench_backends() -> Vec<Backend> {
+    vec![
+        Backend::Auto,
+        Backend::Backtracking,
+        Backend::Propagation,
+        Backend::Dlx,
+        #[cfg(feature = "sat")]
+        Backend::Sat,
+    ]
+}
+
+/// Solves every puzzle in [CORPUS] runs times with each backend, and reports the percentiles of
+/// the solve times per backend and tier.
+///
+/// Backtracking searches give up after timeout, and count as unsolved with the timeout as their
+/// time. Other backends always run to the end, and so does [Backend::Auto], as a timeout would
+/// keep it from picking dancing links for puzzles with few givens.
+pub fn bench(backends: &[Backend], runs: usize, timeout: Duration) -> Vec<BenchReport> {
+    let mut reports = Vec::new();
+    for &backend in backends {
+        let solver = match backend {
+            Backend::Auto => Solver::new(),
+            _ => Solver::new().backend(backend).timeout(timeout),
+        };
+
+        for tier in [Tier::Easy, Tier::Hard, Tier::Pathological] {
+            let mut times = Vec::new();
+            let mut solved = 0;
+            for entry in CORPUS.iter().filter(|entry| entry.tier == tier) {
+                let puzzle = entry.puzzle();
+                for _ in 0..runs {
+                    let start = Instant::now();
+                    if matches!(solver.solve(puzzle), SolveOutcome::Solved(_)) {
+                        solved += 1;
+                    }
+                    times.push(start.elapsed());
+                }
+            }
+
+            times.sort();
+            reports.push(BenchReport {
+                backend,
+                tier,
+                solved,
+                runs: times.len(),
+                p50: percentile(&times, 50),
+                p90: percentile(&times, 90),
+                p99: percentile(&times, 99),
+                max: times.last().copied().unwrap_or_default(),
+            });
+        }
+    }
+
+    reports
+}
+
+/// Gets the nearest-rank percentile of sorted times, or zero if there are none.
+fn percentile(times: &[Duration], percent: usize) -> Duration {
+    match times.len() {
+        0 => Duration::ZERO,
+        len => times[(len * percent).div_ceil(100).max(1) - 1],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_corpus() {
+        for entry in &CORPUS {
+            let solutions = Solver::new()
+                .backend(Backend::Dlx)
+                .count(entry.puzzle(), Some(2))
+                .unwrap();
+            match entry.name {
+                "norvig-hardest" => assert_eq!(solutions, 2),
+                _ => assert_eq!(solutions, 1, "{}", entry.name),
+            }
+        }
+    }
+
+    #[test]
+    fn check_percentile() {
+        let times = (1..=10).map(Duration::from_millis).collect::<Vec<_>>();
+        assert_eq!(percentile(&times, 50), Duration::from_millis(5));
+        assert_eq!(percentile(&times, 90), Duration::from_millis(9));
+        assert_eq!(percentile(&times, 99), Duration::from_millis(10));
+        assert_eq!(percentile(&times, 0), Duration::from_millis(1));
+        assert_eq!(percentile(&[], 50), Duration::ZERO);
+    }
+
+    #[test]
+    fn check_bench() {
+        let reports = bench(&[Backend::Dlx], 2, Duration::from_secs(1));
+        assert_eq!(reports.len(), 3);
+        for report in reports {
+            assert_eq!(report.backend, Backend::Dlx);
+            assert_eq!(report.solved, 6);
+            assert_eq!(report.runs, 6);
+            assert!(report.p50 <= report.p90 && report.p90 <= report.p99);
+            assert!(report.p99 <= report.max);
+        }
+
+        // Auto takes the path of a plain solve, without a timeout.
+        for report in bench(&[Backend::Auto], 1, Duration::ZERO) {
+            assert_eq!(report.solved, report.runs);
+        }
+    }
+}
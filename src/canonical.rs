@@ -0,0 +1,273 @@
+use crate::{sudoku::Puzzle, transform::Transformation};
+
+/// Gets the canonical form of a puzzle: the smallest in lexicographic order (reading the digits in
+/// reading order, with blanks as 0) of all the puzzles it can be turned into while keeping its
+/// solutions valid, by relabeling digits, permuting the rows within a band, the bands, the cols
+/// within a stack and the stacks, and transposing.
+///
+/// Two puzzles have the same canonical form exactly when they are equivalent, so the canonical form
+/// can be used as a key to find duplicates in a collection.
+pub fn canonicalize(puzzle: Puzzle) -> Puzzle {
+    Canonical::find(puzzle).grid
+}
+
+/// Decides whether two puzzles are the same up to relabeling digits, permuting rows and cols
+/// within bands and stacks, permuting bands and stacks, and transposing, e.g. to spot a puzzle
+/// published again in disguise. Returns a transformation that turns the first puzzle into the
+/// second if so.
+pub fn are_equivalent(first: Puzzle, second: Puzzle) -> Option<Transformation> {
+    let first = Canonical::find(first);
+    let second = Canonical::find(second);
+
+    (first.grid == second.grid).then(|| first.transformation.then(&second.transformation.inverse()))
+}
+
+/// Search for the canonical form of a puzzle.
+struct Canonical {
+    /// The smallest candidate found so far.
+    grid: Puzzle,
+
+    /// A transformation that turns the puzzle into grid.
+    transformation: Transformation,
+
+    /// Whether the candidates being searched are transposed, and their order of cols.
+    transpose: bool,
+    cols: [usize; 9],
+}
+
+impl Canonical {
+    fn find(puzzle: Puzzle) -> Canonical {
+        let mut canonical = Canonical {
+            grid: [[u8::MAX; 9]; 9],
+            transformation: Transformation::identity(),
+            transpose: false,
+            cols: [0; 9],
+        };
+
+        for (transpose, grid) in [(false, puzzle), (true, transpose(puzzle))] {
+            for cols in line_orders() {
+                canonical.transpose = transpose;
+                canonical.cols = cols;
+
+                let permuted = grid.map(|row| cols.map(|col| row[col]));
+                canonical.search_rows(&permuted, &mut Vec::new(), [0; 10], 1);
+            }
+        }
+
+        canonical
+    }
+
+    /// Picks the remaining rows of a grid whose cols are already in place, one at a time, keeping
+    /// the rows of each band together, and keeps the smallest result. Digits are relabeled in
+    /// order of first appearance: labels maps each original digit to its new label (0 if not seen
+    /// yet), and next is the next label to hand out.
+    ///
+    /// Branches that start with a row larger than the same row of the smallest result are pruned.
+    fn search_rows(&mut self, grid: &Puzzle, picked: &mut Vec<usize>, labels: [u8; 10], next: u8) {
+        let depth = picked.len();
+        if depth == 9 {
+            // Every row is at most the same row of the smallest result, which was lowered to match
+            // if need be, so this candidate is the smallest result.
+            self.transformation = self.transformation_to(picked, labels, next);
+            return;
+        }
+
+        let choices = if depth.is_multiple_of(3) {
+            // Start a new band: any row of a band not used yet.
+            (0..9)
+                .filter(|&row| picked.iter().all(|picked| picked / 3 != row / 3))
+                .collect::<Vec<_>>()
+        } else {
+            let band = picked[depth - 1] / 3;
+            (band * 3..band * 3 + 3)
+                .filter(|row| !picked.contains(row))
+                .collect()
+        };
+
+        for row in choices {
+            let mut labels = labels;
+            let mut next = next;
+            let line = grid[row].map(|digit| {
+                if digit != 0 && labels[digit as usize] == 0 {
+                    labels[digit as usize] = next;
+                    next += 1;
+                }
+                labels[digit as usize]
+            });
+
+            // The rows picked so far always match the smallest result, as deeper rows never change
+            // shallower ones.
+            if line > self.grid[depth] {
+                continue;
+            }
+            if line < self.grid[depth] {
+                self.grid[depth] = line;
+                for later in &mut self.grid[depth + 1..] {
+                    *later = [u8::MAX; 9];
+                }
+            }
+
+            picked.push(row);
+            self.search_rows(grid, picked, labels, next);
+            picked.pop();
+        }
+    }
+
+    /// Gets the transformation to the candidate with the picked rows. Digits missing from the
+    /// puzzle get the labels left over.
+    fn transformation_to(
+        &self,
+        picked: &[usize],
+        labels: [u8; 10],
+        mut next: u8,
+    ) -> Transformation {
+        let mut digits = [0; 9];
+        for (index, digit) in digits.iter_mut().enumerate() {
+            *digit = match labels[index + 1] {
+                0 => {
+                    next += 1;
+                    next - 1
+                }
+                label => label,
+            };
+        }
+
+        let rows = picked.try_into().expect("all rows should be picked");
+        Transformation::new(self.transpose, rows, self.cols, digits)
+            .expect("rows and cols should keep bands and stacks together")
+    }
+}
+
+/// Swaps the rows and cols of a puzzle.
+fn transpose(puzzle: Puzzle) -> Puzzle {
+    std::array::from_fn(|row| std::array::from_fn(|col| puzzle[col][row]))
+}
+
+/// Gets every order of the 9 rows (or cols) that keeps the rows of each band together: 6 orders of
+/// the bands, times 6 orders of the rows within each of the 3 bands.
+fn line_orders() -> Vec<[usize; 9]> {
+    const PERMUTATIONS: [[usize; 3]; 6] = [
+        [0, 1, 2],
+        [0, 2, 1],
+        [1, 0, 2],
+        [1, 2, 0],
+        [2, 0, 1],
+        [2, 1, 0],
+    ];
+
+    let mut orders = Vec::with_capacity(6 * 6 * 6 * 6);
+    for bands in PERMUTATIONS {
+        for first in PERMUTATIONS {
+            for second in PERMUTATIONS {
+                for third in PERMUTATIONS {
+                    let within = [first, second, third];
+                    orders.push(std::array::from_fn(|index| {
+                        bands[index / 3] * 3 + within[index / 3][index % 3]
+                    }));
+                }
+            }
+        }
+    }
+
+    orders
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PUZZLE: Puzzle = [
+        [5, 3, 0, 0, 7, 0, 0, 0, 0],
+        [6, 0, 0, 1, 9, 5, 0, 0, 0],
+        [9, 8, 0, 0, 0, 0, 0, 6, 0],
+        [8, 0, 0, 0, 6, 0, 0, 0, 3],
+        [4, 0, 0, 8, 0, 3, 0, 0, 1],
+        [7, 0, 0, 0, 2, 0, 0, 0, 6],
+        [0, 6, 0, 0, 0, 0, 2, 8, 0],
+        [0, 0, 0, 4, 1, 9, 0, 0, 5],
+        [0, 0, 0, 0, 8, 0, 0, 7, 9],
+    ];
+
+    #[test]
+    fn check_line_orders() {
+        let orders = line_orders();
+        assert_eq!(orders.len(), 1296);
+        assert_eq!(orders[0], [0, 1, 2, 3, 4, 5, 6, 7, 8]);
+
+        let mut sorted = orders.clone();
+        sorted.sort();
+        sorted.dedup();
+        assert_eq!(sorted.len(), 1296);
+    }
+
+    #[test]
+    fn check_canonicalize() {
+        let canonical = canonicalize(PUZZLE);
+        assert_eq!(canonicalize(canonical), canonical);
+        assert!(canonical <= PUZZLE);
+        assert_eq!(
+            canonical
+                .iter()
+                .flatten()
+                .filter(|&&digit| digit != 0)
+                .count(),
+            30
+        );
+
+        // Transposing, swapping bands and stacks and rows within a band, and relabeling.
+        let mut other = transpose(PUZZLE);
+        other.swap(0, 6);
+        other.swap(1, 7);
+        other.swap(2, 8);
+        other.swap(3, 4);
+        for row in &mut other {
+            row.swap(0, 3);
+            row.swap(1, 4);
+            row.swap(2, 5);
+            for digit in row.iter_mut().filter(|digit| **digit != 0) {
+                *digit = 10 - *digit;
+            }
+        }
+        assert_eq!(canonicalize(other), canonical);
+
+        // Swapping two cols of different stacks is not a symmetry.
+        let mut different = PUZZLE;
+        for row in &mut different {
+            row.swap(0, 8);
+        }
+        assert_ne!(canonicalize(different), canonical);
+    }
+
+    #[test]
+    fn check_are_equivalent() {
+        let disguise = Transformation::rotate90()
+            .then(&Transformation::swap_bands(0, 2).unwrap())
+            .then(&Transformation::swap_cols(1, 0, 2).unwrap())
+            .then(&Transformation::permute_digits([4, 5, 6, 7, 8, 9, 1, 2, 3]).unwrap());
+        let other = disguise.apply(PUZZLE);
+
+        let mapping = are_equivalent(PUZZLE, other).unwrap();
+        assert_eq!(mapping.apply(PUZZLE), other);
+        assert_eq!(mapping.inverse().apply(other), PUZZLE);
+        assert_eq!(
+            are_equivalent(PUZZLE, PUZZLE).map(|mapping| mapping.apply(PUZZLE)),
+            Some(PUZZLE)
+        );
+
+        // Same givens but one, which is different.
+        let mut different = PUZZLE;
+        different[0][0] = 0;
+        different[0][2] = 5;
+        assert_eq!(are_equivalent(PUZZLE, different), None);
+
+        // A puzzle without some of the digits.
+        let mut sparse = [[0; 9]; 9];
+        sparse[0][0] = 3;
+        sparse[4][4] = 7;
+        let moved = Transformation::transpose()
+            .then(&Transformation::swap_stacks(0, 2).unwrap())
+            .apply(sparse);
+        let mapping = are_equivalent(sparse, moved).unwrap();
+        assert_eq!(mapping.apply(sparse), moved);
+    }
+}
@@ -0,0 +1,203 @@
+use crate::sudoku::{GridPos, Puzzle, Solution, candidates, fill_naked_singles, is_valid_puzzle};
+
+/// One node of a uniqueness proof.
+///
+/// At every node, the grid is first filled in with naked singles until none are left (see
+/// [Certificate::verify]). The node then records what that leads to.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ProofNode {
+    /// The grid is complete.
+    Solved,
+
+    /// The given blank has no candidates left.
+    DeadEnd(GridPos),
+
+    /// Case split on every candidate of the given blank, with a proof for each case.
+    Branch {
+        pos: GridPos,
+        cases: Vec<(u8, ProofNode)>,
+    },
+}
+
+/// Evidence that a puzzle has exactly one solution, which can be checked without searching.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Certificate {
+    pub solution: Solution,
+    pub proof: ProofNode,
+}
+
+impl Certificate {
+    /// Checks the certificate against a puzzle.
+    ///
+    /// This only replays the proof: at each node it fills in naked singles, then checks that the
+    /// node is a complete grid, a real dead end, or a split over exactly the candidates of a blank.
+    /// The certificate holds if exactly one branch ends in a complete grid, and that grid is the
+    /// solution.
+    pub fn verify(&self, puzzle: Puzzle) -> bool {
+        if !is_valid_puzzle(puzzle) {
+            return false;
+        }
+
+        let mut solutions = Vec::new();
+        verify_node(puzzle, &self.proof, &mut solutions) && solutions == [self.solution]
+    }
+}
+
+/// Proves that a puzzle has exactly one solution.
+///
+/// Returns None if the puzzle does not have exactly one solution.
+pub fn uniqueness_certificate(puzzle: Puzzle) -> Option<Certificate> {
+    if !is_valid_puzzle(puzzle) {
+        return None;
+    }
+
+    let mut solutions = Vec::new();
+    let proof = prove(puzzle, &mut solutions)?;
+
+    match solutions[..] {
+        [solution] => Some(Certificate { solution, proof }),
+        _ => None,
+    }
+}
+
+/// Builds the proof for a grid, collecting solutions. Returns None as soon as a second solution
+/// is found.
+fn prove(mut grid: Puzzle, solutions: &mut Vec<Solution>) -> Option<ProofNode> {
+    if let Err(pos) = fill_naked_singles(&mut grid) {
+        return Some(ProofNode::DeadEnd(pos));
+    }
+
+    let Some((pos, digits)) = branch_point(grid) else {
+        solutions.push(grid);
+        return if solutions.len() > 1 {
+            None
+        } else {
+            Some(ProofNode::Solved)
+        };
+    };
+
+    let mut cases = Vec::new();
+    for digit in digits {
+        let mut case = grid;
+        case[pos.0][pos.1] = digit;
+        cases.push((digit, prove(case, solutions)?));
+    }
+
+    Some(ProofNode::Branch { pos, cases })
+}
+
+/// Checks the proof for a grid, collecting solutions.
+fn verify_node(mut grid: Puzzle, node: &ProofNode, solutions: &mut Vec<Solution>) -> bool {
+    let filled = fill_naked_singles(&mut grid);
+
+    match (node, filled) {
+        (ProofNode::DeadEnd(pos), Err(dead_end)) => *pos == dead_end,
+        (ProofNode::Solved, Ok(())) => {
+            if branch_point(grid).is_some() {
+                return false;
+            }
+
+            solutions.push(grid);
+            true
+        }
+        (ProofNode::Branch { pos, cases }, Ok(())) => {
+            // Certificates may come from elsewhere, so check the fields before using them.
+            let in_grid = pos.0 < 9 && pos.1 < 9;
+            let valid_digits = cases.iter().all(|(digit, _)| (1..=9).contains(digit));
+            if !in_grid || !valid_digits || grid[pos.0][pos.1] != 0 {
+                return false;
+            }
+
+            let candidates = candidates(grid)[pos.0][pos.1];
+            let case_digits = cases
+                .iter()
+                .fold(0u16, |set, (digit, _)| set | 1 << (digit - 1));
+            if case_digits != candidates || cases.len() != candidates.count_ones() as usize {
+                return false;
+            }
+
+            cases.iter().all(|(digit, case)| {
+                let mut grid = grid;
+                grid[pos.0][pos.1] = *digit;
+                verify_node(grid, case, solutions)
+            })
+        }
+        _ => false,
+    }
+}
+
+/// Picks the first blank with the fewest candidates to split on, and its candidates.
+fn branch_point(grid: Puzzle) -> Option<(GridPos, Vec<u8>)> {
+    let candidates = candidates(grid);
+
+    (0..81)
+        .map(|cell| (cell / 9, cell % 9))
+        .filter(|&(row, col)| grid[row][col] == 0)
+        .min_by_key(|&(row, col)| candidates[row][col].count_ones())
+        .map(|(row, col)| {
+            let digits = (1..=9)
+                .filter(|digit| candidates[row][col] & (1 << (digit - 1)) != 0)
+                .collect();
+            ((row, col), digits)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Arto Inkala's "world's hardest sudoku".
+    const HARD: Puzzle = [
+        [8, 0, 0, 0, 0, 0, 0, 0, 0],
+        [0, 0, 3, 6, 0, 0, 0, 0, 0],
+        [0, 7, 0, 0, 9, 0, 2, 0, 0],
+        [0, 5, 0, 0, 0, 7, 0, 0, 0],
+        [0, 0, 0, 0, 4, 5, 7, 0, 0],
+        [0, 0, 0, 1, 0, 0, 0, 3, 0],
+        [0, 0, 1, 0, 0, 0, 0, 6, 8],
+        [0, 0, 8, 5, 0, 0, 0, 1, 0],
+        [0, 9, 0, 0, 0, 0, 4, 0, 0],
+    ];
+
+    #[test]
+    fn check_certificate() {
+        let certificate = uniqueness_certificate(HARD).unwrap();
+        assert!(matches!(certificate.proof, ProofNode::Branch { .. }));
+        assert!(certificate.verify(HARD));
+
+        let mut forged = certificate.clone();
+        forged.solution[0][1] = 0;
+        assert!(!forged.verify(HARD));
+
+        // Dropping a case leaves part of the search space unchecked.
+        let mut forged = certificate.clone();
+        if let ProofNode::Branch { cases, .. } = &mut forged.proof {
+            cases.pop();
+        }
+        assert!(!forged.verify(HARD));
+
+        let mut puzzle = HARD;
+        puzzle[0][0] = 0;
+        assert_eq!(uniqueness_certificate(puzzle), None);
+    }
+
+    #[test]
+    fn check_forged_certificate() {
+        let certificate = uniqueness_certificate(HARD).unwrap();
+        let forge = |pos: GridPos, digit: u8| Certificate {
+            proof: ProofNode::Branch {
+                pos,
+                cases: vec![(digit, ProofNode::Solved)],
+            },
+            ..certificate.clone()
+        };
+        let ProofNode::Branch { pos, .. } = certificate.proof else {
+            panic!("expected a branch");
+        };
+
+        assert!(!forge((9, 0), 1).verify(HARD));
+        assert!(!forge((0, 9), 1).verify(HARD));
+        assert!(!forge(pos, 0).verify(HARD));
+        assert!(!forge(pos, 20).verify(HARD));
+    }
+}
@@ -0,0 +1,258 @@
+use crate::{
+    logic::Board,
+    logic::{LogicalOutcome, LogicalSolver},
+    sudoku::{Backend, Puzzle, Solution, Solver, Uniqueness, verify_solution},
+};
+
+/// The backends checked by [verify_consistency], with the reference first. [Backend::Annealing]
+/// is left out, as it cannot tell whether a solution is unique.
+const BACKENDS: &[Backend] = &[
+    Backend::Backtracking,
+    Backend::Propagation,
+    Backend::Dlx,
+    #[cfg(feature = "sat")]
+    Backend::Sat,
+];
+
+/// What the solvers found for a puzzle, and where they disagree, found by [verify_consistency].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConsistencyReport {
+    /// Whether each backend found no solution, exactly one, or more.
+    pub backends: Vec<(Backend, Uniqueness)>,
+
+    /// How far the logical solver got, or None if the puzzle is invalid.
+    pub logical: Option<LogicalOutcome>,
+
+    /// Every disagreement found, empty if the solvers agree.
+    pub inconsistencies: Vec<Inconsistency>,
+}
+
+impl ConsistencyReport {
+    /// Checks whether the solvers agree.
+    pub fn is_consistent(&self) -> bool {
+        self.inconsistencies.is_empty()
+    }
+}
+
+/// A way in which a solver disagrees with the others.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Inconsistency {
+    /// The backend found a grid that does not solve the puzzle.
+    InvalidSolution(Backend),
+
+    /// The backend disagrees with [Backend::Backtracking] on whether the puzzle has no solution,
+    /// exactly one or more, or on what the single solution is.
+    Mismatch(Backend),
+
+    /// The logical solver filled in a grid other than the single solution.
+    LogicalSolution,
+
+    /// The logical solver left a blank without candidates, although the puzzle has a solution.
+    LogicalContradiction,
+
+    /// The logical solver got stuck after placing a digit other than that of the single solution,
+    /// or removing the solution digit from the candidates of a blank.
+    LogicalDeviation,
+}
+
+/// Solves a puzzle with every backend and with the logical solver, and checks that they agree on
+/// whether it has a solution, whether the solution is unique, and what it is, e.g. as an oracle for
+/// fuzzing changes to a solver. Panics are left to the caller:
+/// `assert!(report.is_consistent(), "{report:?}")`.
+///
+/// [Backend::Backtracking] is the reference, and is slow on puzzles with few givens. The logical
+/// solver uses almost locked sets, but not unique rectangles, which would assume the answer. As it
+/// never guesses, it only disagrees if it completes a grid, runs out of candidates, or gets stuck
+/// off the path to the single solution.
+pub fn verify_consistency(puzzle: Puzzle) -> ConsistencyReport {
+    let backends = BACKENDS
+        .iter()
+        .map(|&backend| {
+            let uniqueness = Solver::new()
+                .backend(backend)
+                .uniqueness(puzzle)
+                .expect("search without limits should finish");
+            (backend, uniqueness)
+        })
+        .collect::<Vec<_>>();
+    let logical = LogicalSolver::new()
+        .almost_locked_sets(true)
+        .try_solve(puzzle);
+
+    ConsistencyReport {
+        inconsistencies: inconsistencies(puzzle, &backends, logical),
+        backends,
+        logical,
+    }
+}
+
+/// Compares each backend with the first, and the logical solver with the first backend.
+fn inconsistencies(
+    puzzle: Puzzle,
+    backends: &[(Backend, Uniqueness)],
+    logical: Option<LogicalOutcome>,
+) -> Vec<Inconsistency> {
+    let mut inconsistencies = Vec::new();
+    let Some(&(_, reference)) = backends.first() else {
+        return inconsistencies;
+    };
+
+    for &(backend, uniqueness) in backends {
+        if let Uniqueness::One(solution) = uniqueness
+            && !verify_solution(puzzle, solution)
+        {
+            inconsistencies.push(Inconsistency::InvalidSolution(backend));
+        }
+        if uniqueness != reference {
+            inconsistencies.push(Inconsistency::Mismatch(backend));
+        }
+    }
+
+    match logical {
+        Some(LogicalOutcome::Solved(solution)) if reference != Uniqueness::One(solution) => {
+            inconsistencies.push(Inconsistency::LogicalSolution);
+        }
+        Some(LogicalOutcome::Stuck(board))
+            if board.is_broken() && reference != Uniqueness::Zero =>
+        {
+            inconsistencies.push(Inconsistency::LogicalContradiction);
+        }
+        Some(LogicalOutcome::Stuck(board)) => {
+            if let Uniqueness::One(solution) = reference
+                && deviates(&board, solution)
+            {
+                inconsistencies.push(Inconsistency::LogicalDeviation);
+            }
+        }
+        _ => {}
+    }
+
+    inconsistencies
+}
+
+/// Checks whether a board holds a digit other than that of the solution, or lacks the solution
+/// digit among the candidates of a blank.
+fn deviates(board: &Board, solution: Solution) -> bool {
+    let grid = board.grid();
+
+    (0..81)
+        .map(|index| (index / 9, index % 9))
+        .any(|(row, col)| {
+            let digit = solution[row][col];
+            match grid[row][col] {
+                0 => board.candidates((row, col)) & (1 << (digit - 1)) == 0,
+                placed => placed != digit,
+            }
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        logic::{Step, Technique},
+        sudoku::solve_any,
+    };
+
+    use super::*;
+
+    const PUZZLE: Puzzle = [
+        [5, 3, 0, 0, 7, 0, 0, 0, 0],
+        [6, 0, 0, 1, 9, 5, 0, 0, 0],
+        [9, 8, 0, 0, 0, 0, 0, 6, 0],
+        [8, 0, 0, 0, 6, 0, 0, 0, 3],
+        [4, 0, 0, 8, 0, 3, 0, 0, 1],
+        [7, 0, 0, 0, 2, 0, 0, 0, 6],
+        [0, 6, 0, 0, 0, 0, 2, 8, 0],
+        [0, 0, 0, 4, 1, 9, 0, 0, 5],
+        [0, 0, 0, 0, 8, 0, 0, 7, 9],
+    ];
+
+    #[test]
+    fn check_verify_consistency() {
+        let solution = solve_any(PUZZLE).unwrap();
+        let report = verify_consistency(PUZZLE);
+        assert!(report.is_consistent(), "{report:?}");
+        assert_eq!(report.backends.len(), BACKENDS.len());
+        assert!(
+            report
+                .backends
+                .iter()
+                .all(|&(_, uniqueness)| uniqueness == Uniqueness::One(solution))
+        );
+        assert_eq!(report.logical, Some(LogicalOutcome::Solved(solution)));
+
+        let several = verify_consistency([[0; 9]; 9]);
+        assert!(several.is_consistent(), "{several:?}");
+        assert_eq!(several.backends[0].1, Uniqueness::Multiple);
+
+        // No solution, and clashing givens.
+        let mut puzzle = PUZZLE;
+        puzzle[0][2] = 1;
+        puzzle[0][8] = 4;
+        assert!(verify_consistency(puzzle).is_consistent());
+        puzzle[0][2] = 5;
+        let invalid = verify_consistency(puzzle);
+        assert!(invalid.is_consistent());
+        assert_eq!(invalid.logical, None);
+    }
+
+    #[test]
+    fn check_inconsistencies() {
+        let solution = solve_any(PUZZLE).unwrap();
+        let mut wrong = solution;
+        wrong[0].swap(0, 1);
+        let backends = [
+            (Backend::Backtracking, Uniqueness::One(solution)),
+            (Backend::Propagation, Uniqueness::One(wrong)),
+            (Backend::Dlx, Uniqueness::Multiple),
+        ];
+        assert_eq!(
+            inconsistencies(PUZZLE, &backends, Some(LogicalOutcome::Solved(wrong))),
+            [
+                Inconsistency::InvalidSolution(Backend::Propagation),
+                Inconsistency::Mismatch(Backend::Propagation),
+                Inconsistency::Mismatch(Backend::Dlx),
+                Inconsistency::LogicalSolution,
+            ]
+        );
+
+        // Placing 1 in r1c1 leaves r1c2 without candidates.
+        let mut candidates = [[0x1ff; 9]; 9];
+        candidates[0] = [1, 1, 0x1ff, 0x1ff, 0x1ff, 0x1ff, 0x1ff, 0x1ff, 0x1ff];
+        let mut board = Board::from_candidates(candidates).unwrap();
+        let step = LogicalSolver::new().next_step(&board).unwrap();
+        board.apply(&step);
+        assert!(board.is_broken());
+        assert_eq!(
+            inconsistencies(PUZZLE, &backends[..1], Some(LogicalOutcome::Stuck(board))),
+            [Inconsistency::LogicalContradiction]
+        );
+
+        // Stuck with the solution digit of r1c3 removed, or with a wrong digit placed.
+        let reference = [(Backend::Backtracking, Uniqueness::One(solution))];
+        let mut board = Board::new(PUZZLE).unwrap();
+        assert_eq!(
+            inconsistencies(PUZZLE, &reference, Some(LogicalOutcome::Stuck(board))),
+            []
+        );
+        board.apply(&Step {
+            technique: Technique::XWing,
+            units: Vec::new(),
+            cells: Vec::new(),
+            links: Vec::new(),
+            eliminations: vec![((0, 2), solution[0][2])],
+            placement: None,
+        });
+        assert_eq!(
+            inconsistencies(PUZZLE, &reference, Some(LogicalOutcome::Stuck(board))),
+            [Inconsistency::LogicalDeviation]
+        );
+        let mut wrong = PUZZLE;
+        wrong[0][2] = 1;
+        let board = Board::new(wrong).unwrap();
+        assert_eq!(
+            inconsistencies(PUZZLE, &reference, Some(LogicalOutcome::Stuck(board))),
+            [Inconsistency::LogicalDeviation]
+        );
+    }
+}
@@ -0,0 +1,417 @@
+use std::fmt;
+
+use crate::{
+    logic::{Board, Link, LogicalSolver, Step, Technique},
+    sudoku::{Conflict, GridPos, Puzzle, Unit, find_conflicts, solve_any},
+};
+
+/// How much detail [explain] gives.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Verbosity {
+    /// One line in solver notation, e.g. "r4c6=7 (hidden single)".
+    Compact,
+
+    /// A sentence, e.g. "Row 4: 7 can only go in r4c6 (hidden single)", also suited to screen
+    /// readers.
+    Verbose,
+}
+
+/// Describes a step in English, e.g. to show the steps of a logical solve to a player.
+pub fn explain(step: &Step, verbosity: Verbosity) -> String {
+    let name = step.technique.name();
+    let text = match verbosity {
+        Verbosity::Compact => compact(step),
+        Verbosity::Verbose => verbose(step),
+    };
+
+    format!("{text} ({name})")
+}
+
+/// Why a puzzle has no solution, found by [explain_unsolvable]. Displays in English, like
+/// [explain]; the CLI builds its message from the catalog instead.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Unsolvable {
+    /// The givens put the same digit twice in a unit.
+    Conflict(Conflict),
+
+    /// A blank has no candidates left after the steps, which may be none if the givens alone rule
+    /// out every digit.
+    NoCandidates { pos: GridPos, steps: Vec<Step> },
+
+    /// A digit has no place left in a unit after the steps.
+    NoPlace {
+        unit: Unit,
+        digit: u8,
+        steps: Vec<Step>,
+    },
+
+    /// Logic gets stuck before reaching a contradiction, so only trial and error shows there is no
+    /// solution. Holds the board it gets stuck on.
+    Undetermined(Board),
+}
+
+impl fmt::Display for Unsolvable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (text, steps) = match self {
+            Unsolvable::Conflict(conflict) => {
+                return write!(
+                    f,
+                    "{} has {} more than once: {}",
+                    unit_name(conflict.unit),
+                    conflict.digit,
+                    list(&names(&conflict.positions))
+                );
+            }
+            Unsolvable::NoCandidates { pos, steps } => {
+                (format!("{} has no candidates left", cell_name(*pos)), steps)
+            }
+            Unsolvable::NoPlace { unit, digit, steps } => (
+                format!("{} has no place left for {digit}", unit_name(*unit)),
+                steps,
+            ),
+            Unsolvable::Undetermined(_) => {
+                return write!(
+                    f,
+                    "logic gets stuck before finding a contradiction, so only trial and error \
+                     shows that no digit fits"
+                );
+            }
+        };
+
+        if steps.is_empty() {
+            return write!(f, "{text} from the givens alone");
+        }
+
+        write!(f, "{text} after these steps:")?;
+        for (index, step) in steps.iter().enumerate() {
+            write!(f, "\n{}. {}", index + 1, explain(step, Verbosity::Compact))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Explains why a puzzle has no solution: either givens that clash, or the logical steps that lead
+/// to a contradiction, stopping at the first one found. As the solver tries the simplest technique
+/// first, the steps are usually few and easy to follow, though not always the fewest possible.
+/// Returns None if the puzzle has a solution.
+pub fn explain_unsolvable(puzzle: Puzzle) -> Option<Unsolvable> {
+    if let Some(conflict) = find_conflicts(puzzle).into_iter().next() {
+        return Some(Unsolvable::Conflict(conflict));
+    }
+    if solve_any(puzzle).is_some() {
+        return None;
+    }
+
+    // Without clashing givens, the board is valid.
+    let mut board = Board::new(puzzle)?;
+
+    let solver = LogicalSolver::new();
+    let mut steps = Vec::new();
+    loop {
+        if let Some(pos) = empty_cell(&board) {
+            return Some(Unsolvable::NoCandidates { pos, steps });
+        }
+        if let Some((unit, digit)) = missing_digit(&board) {
+            return Some(Unsolvable::NoPlace { unit, digit, steps });
+        }
+
+        let before = board;
+        let Some(step) = solver.next_step(&board) else {
+            return Some(Unsolvable::Undetermined(board));
+        };
+        board.apply(&step);
+        if board == before {
+            return Some(Unsolvable::Undetermined(board));
+        }
+        steps.push(step);
+    }
+}
+
+/// Finds a blank with no candidates left.
+fn empty_cell(board: &Board) -> Option<GridPos> {
+    (0..81)
+        .map(|index| (index / 9, index % 9))
+        .find(|&(row, col)| board.grid()[row][col] == 0 && board.candidates((row, col)) == 0)
+}
+
+/// Finds a digit that is neither placed in a unit nor a candidate of any of its cells.
+fn missing_digit(board: &Board) -> Option<(Unit, u8)> {
+    Unit::all().find_map(|unit| {
+        let positions = unit.positions();
+        (1..=9).find_map(|digit| {
+            let found = positions.iter().any(|&(row, col)| {
+                board.grid()[row][col] == digit
+                    || board.candidates((row, col)) & (1 << (digit - 1)) != 0
+            });
+            (!found).then_some((unit, digit))
+        })
+    })
+}
+
+/// Solver notation: "r4c6=7" for a placement, "r1c3,r1c4<>7" for eliminations.
+fn compact(step: &Step) -> String {
+    if let Some((pos, digit)) = step.placement {
+        return format!("{}={digit}", cell_name(pos));
+    }
+
+    by_digit(&step.eliminations)
+        .into_iter()
+        .map(|(digit, cells)| format!("{}<>{digit}", names(&cells).join(",")))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// A sentence naming the units and cells to look at, and what follows from them.
+fn verbose(step: &Step) -> String {
+    let context = step
+        .units
+        .iter()
+        .map(|&unit| unit_name(unit))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let context = match context.is_empty() {
+        true => String::new(),
+        false => format!("{context}: "),
+    };
+
+    let text = match (step.technique, step.placement) {
+        (Technique::HiddenSingle, Some((pos, digit))) => {
+            format!("{digit} can only go in {}", cell_name(pos))
+        }
+        (_, Some((pos, digit))) => {
+            format!("{} can only be {digit}", cell_name(pos))
+        }
+        (_, None) => {
+            let reason = match chain(&step.links) {
+                Some(chain) => format!("following {chain}"),
+                None => format!("because of {}", list(&names(&step.cells))),
+            };
+            let removals = by_digit(&step.eliminations)
+                .into_iter()
+                .map(|(digit, cells)| format!("{digit} from {}", list(&names(&cells))))
+                .collect::<Vec<_>>();
+
+            format!("{reason}, remove {}", list(&removals))
+        }
+    };
+
+    format!("{context}{text}")
+}
+
+/// Writes links that follow on from each other as a chain, e.g. "(3)r1c1=(3)r1c5-(3)r4c5", with
+/// "=" for strong links and "-" for weak ones. Returns None if there are no links, or they do not
+/// form a single chain, as with coloring.
+fn chain(links: &[Link]) -> Option<String> {
+    let first = links.first()?;
+    let candidate = |(pos, digit): (GridPos, u8)| format!("({digit}){}", cell_name(pos));
+
+    let mut text = candidate(first.from);
+    let mut end = first.from;
+    for link in links {
+        if link.from != end {
+            return None;
+        }
+
+        text.push(if link.strong { '=' } else { '-' });
+        text.push_str(&candidate(link.to));
+        end = link.to;
+    }
+
+    Some(text)
+}
+
+/// Groups eliminations by digit, in digit order, keeping the cells in reading order.
+fn by_digit(eliminations: &[(GridPos, u8)]) -> Vec<(u8, Vec<GridPos>)> {
+    (1..=9)
+        .filter_map(|digit| {
+            let cells = eliminations
+                .iter()
+                .filter(|&&(_, other)| other == digit)
+                .map(|&(pos, _)| pos)
+                .collect::<Vec<_>>();
+            (!cells.is_empty()).then_some((digit, cells))
+        })
+        .collect()
+}
+
+/// Joins items as in prose: "a", "a and b", "a, b and c".
+fn list(items: &[String]) -> String {
+    match items {
+        [] => String::new(),
+        [item] => item.clone(),
+        [rest @ .., last] => format!("{} and {last}", rest.join(", ")),
+    }
+}
+
+fn names(cells: &[GridPos]) -> Vec<String> {
+    cells.iter().map(|&pos| cell_name(pos)).collect()
+}
+
+/// Names a cell like "r4c6", counting from 1.
+fn cell_name((row, col): GridPos) -> String {
+    format!("r{}c{}", row + 1, col + 1)
+}
+
+/// Names a unit like "Row 4", counting from 1.
+fn unit_name(unit: Unit) -> String {
+    match unit {
+        Unit::Row(row) => format!("Row {}", row + 1),
+        Unit::Col(col) => format!("Column {}", col + 1),
+        Unit::Square(square) => format!("Box {}", square + 1),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn step(technique: Technique) -> Step {
+        Step {
+            technique,
+            units: Vec::new(),
+            cells: Vec::new(),
+            links: Vec::new(),
+            eliminations: Vec::new(),
+            placement: None,
+        }
+    }
+
+    #[test]
+    fn check_explain_placement() {
+        let hidden = Step {
+            units: vec![Unit::Row(3)],
+            cells: vec![(3, 5)],
+            placement: Some(((3, 5), 7)),
+            ..step(Technique::HiddenSingle)
+        };
+        assert_eq!(
+            explain(&hidden, Verbosity::Compact),
+            "r4c6=7 (hidden single)"
+        );
+        assert_eq!(
+            explain(&hidden, Verbosity::Verbose),
+            "Row 4: 7 can only go in r4c6 (hidden single)"
+        );
+
+        let naked = Step {
+            cells: vec![(0, 0)],
+            placement: Some(((0, 0), 2)),
+            ..step(Technique::NakedSingle)
+        };
+        assert_eq!(
+            explain(&naked, Verbosity::Verbose),
+            "r1c1 can only be 2 (naked single)"
+        );
+    }
+
+    #[test]
+    fn check_explain_eliminations() {
+        let pointing = Step {
+            units: vec![Unit::Square(0), Unit::Row(1)],
+            cells: vec![(1, 0), (1, 2)],
+            eliminations: vec![((1, 4), 3), ((1, 7), 3), ((1, 8), 5)],
+            ..step(Technique::Pointing)
+        };
+        assert_eq!(
+            explain(&pointing, Verbosity::Compact),
+            "r2c5,r2c8<>3, r2c9<>5 (pointing)"
+        );
+        assert_eq!(
+            explain(&pointing, Verbosity::Verbose),
+            "Box 1, Row 2: because of r2c1 and r2c3, remove 3 from r2c5 and r2c8 and 5 from r2c9 \
+             (pointing)"
+        );
+
+        let links = vec![
+            Link {
+                from: ((0, 0), 3),
+                to: ((0, 4), 3),
+                strong: true,
+            },
+            Link {
+                from: ((0, 4), 3),
+                to: ((3, 4), 3),
+                strong: false,
+            },
+            Link {
+                from: ((3, 4), 3),
+                to: ((3, 8), 3),
+                strong: true,
+            },
+        ];
+        let x_chain = Step {
+            cells: vec![(0, 0), (0, 4), (3, 4), (3, 8)],
+            links: links.clone(),
+            eliminations: vec![((0, 8), 3)],
+            ..step(Technique::XChain)
+        };
+        assert_eq!(
+            explain(&x_chain, Verbosity::Verbose),
+            "following (3)r1c1=(3)r1c5-(3)r4c5=(3)r4c9, remove 3 from r1c9 (X-Chain)"
+        );
+
+        // Not a single chain.
+        assert_eq!(chain(&[links[0], links[2]]), None);
+        assert_eq!(chain(&[]), None);
+    }
+
+    #[test]
+    fn check_explain_unsolvable() {
+        let mut puzzle = [[0; 9]; 9];
+        puzzle[0] = [1, 2, 3, 4, 5, 6, 7, 0, 0];
+        assert_eq!(explain_unsolvable(puzzle), None);
+
+        // Placing 9 in r1c8 leaves no room for 9 in row 5.
+        puzzle[4] = [2, 3, 4, 5, 6, 7, 1, 0, 0];
+        puzzle[8][8] = 9;
+        let Some(Unsolvable::NoPlace { unit, digit, steps }) = explain_unsolvable(puzzle) else {
+            panic!("expected no place");
+        };
+        assert_eq!((unit, digit, steps.len()), (Unit::Row(4), 9, 1));
+        assert_eq!(
+            explain_unsolvable(puzzle).unwrap().to_string(),
+            "Row 5 has no place left for 9 after these steps:\n1. r1c8=9 (hidden single)"
+        );
+
+        // Every digit but 9 is in row 1, and 9 is in col 9.
+        puzzle[4] = [0; 9];
+        puzzle[0][7] = 8;
+        assert_eq!(
+            explain_unsolvable(puzzle).unwrap().to_string(),
+            "r1c9 has no candidates left from the givens alone"
+        );
+
+        puzzle[0][8] = 1;
+        assert_eq!(
+            explain_unsolvable(puzzle).unwrap().to_string(),
+            "Row 1 has 1 more than once: r1c1 and r1c9"
+        );
+
+        // Inkala's puzzle, with a wrong digit that logic alone cannot rule out.
+        let hard = [
+            [8, 2, 0, 0, 0, 0, 0, 0, 0],
+            [0, 0, 3, 6, 0, 0, 0, 0, 0],
+            [0, 7, 0, 0, 9, 0, 2, 0, 0],
+            [0, 5, 0, 0, 0, 7, 0, 0, 0],
+            [0, 0, 0, 0, 4, 5, 7, 0, 0],
+            [0, 0, 0, 1, 0, 0, 0, 3, 0],
+            [0, 0, 1, 0, 0, 0, 0, 6, 8],
+            [0, 0, 8, 5, 0, 0, 0, 1, 0],
+            [0, 9, 0, 0, 0, 0, 4, 0, 0],
+        ];
+        assert!(matches!(
+            explain_unsolvable(hard),
+            Some(Unsolvable::Undetermined(_))
+        ));
+    }
+
+    #[test]
+    fn check_list() {
+        let items = ["a", "b", "c"].map(String::from);
+        assert_eq!(list(&items[..0]), "");
+        assert_eq!(list(&items[..1]), "a");
+        assert_eq!(list(&items[..2]), "a and b");
+        assert_eq!(list(&items), "a, b and c");
+    }
+}
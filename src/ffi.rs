@@ -0,0 +1,173 @@
+//! C interface, exported from the cdylib. See include/sudoku_solver.h for the declarations.
+//!
+//! Grids are passed as 81-char strings in reading order. Inputs must be NUL-terminated, and use
+//! digits 1-9 for givens and '.' or '0' for blanks; anything else is [SUDOKU_INVALID_ARGUMENT].
+//! Outputs are written as 81 digits followed by a NUL, so output buffers must hold at least 82
+//! chars.
+//!
+//! There is no sudoku_generate, as the crate has no puzzle generator.
+
+use std::{
+    ffi::{CStr, c_char, c_int, c_uint},
+    ptr,
+};
+
+use crate::{
+    io::parse_puzzle,
+    sudoku::{Puzzle, Solution, count_solutions, has_unique_solution, solve_any},
+};
+
+/// Return code: the call succeeded.
+pub const SUDOKU_OK: c_int = 0;
+
+/// Return code: the puzzle has no solution.
+pub const SUDOKU_NO_SOLUTION: c_int = 1;
+
+/// Return code: an argument is null or is not an 81-char grid.
+pub const SUDOKU_INVALID_ARGUMENT: c_int = -1;
+
+/// Solves in81 and writes the solution to out81.
+///
+/// Returns [SUDOKU_OK], [SUDOKU_NO_SOLUTION] or [SUDOKU_INVALID_ARGUMENT].
+///
+/// # Safety
+///
+/// in81 must be null or a NUL-terminated string. out81 must be null or point to at least 82
+/// writable chars.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn sudoku_solve(in81: *const c_char, out81: *mut c_char) -> c_int {
+    if out81.is_null() {
+        return SUDOKU_INVALID_ARGUMENT;
+    }
+
+    let Some(puzzle) = (unsafe { read_grid(in81) }) else {
+        return SUDOKU_INVALID_ARGUMENT;
+    };
+
+    match solve_any(puzzle) {
+        Some(solution) => {
+            unsafe { write_grid(solution, out81) };
+            SUDOKU_OK
+        }
+        None => SUDOKU_NO_SOLUTION,
+    }
+}
+
+/// Counts the solutions to in81, stopping once limit is reached.
+///
+/// Returns the count, or [SUDOKU_INVALID_ARGUMENT].
+///
+/// # Safety
+///
+/// in81 must be null or a NUL-terminated string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn sudoku_count(in81: *const c_char, limit: c_uint) -> c_int {
+    let Some(puzzle) = (unsafe { read_grid(in81) }) else {
+        return SUDOKU_INVALID_ARGUMENT;
+    };
+
+    let limit = limit.min(c_int::MAX as c_uint) as usize;
+    count_solutions(puzzle, Some(limit)) as c_int
+}
+
+/// Verifies whether in81 has exactly one solution.
+///
+/// Returns 1 if it does, 0 if it does not, or [SUDOKU_INVALID_ARGUMENT].
+///
+/// # Safety
+///
+/// in81 must be null or a NUL-terminated string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn sudoku_has_unique_solution(in81: *const c_char) -> c_int {
+    let Some(puzzle) = (unsafe { read_grid(in81) }) else {
+        return SUDOKU_INVALID_ARGUMENT;
+    };
+
+    has_unique_solution(puzzle) as c_int
+}
+
+/// Reads an 81-char grid from a C string.
+unsafe fn read_grid(in81: *const c_char) -> Option<Puzzle> {
+    if in81.is_null() {
+        return None;
+    }
+
+    let input = unsafe { CStr::from_ptr(in81) }.to_str().ok()?;
+    let is_grid_char = |c: char| matches!(c, '0'..='9' | '.');
+    if input.len() != 81 || !input.chars().all(is_grid_char) {
+        return None;
+    }
+
+    parse_puzzle(input).ok()
+}
+
+/// Writes a grid as 81 digits and a NUL.
+unsafe fn write_grid(grid: Solution, out81: *mut c_char) {
+    let mut output = [0; 82];
+    for (index, digit) in grid.as_flattened().iter().enumerate() {
+        output[index] = (digit + b'0') as c_char;
+    }
+
+    unsafe { ptr::copy_nonoverlapping(output.as_ptr(), out81, output.len()) };
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::CString;
+
+    use super::*;
+
+    const PUZZLE: &str =
+        "53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79";
+    const SOLUTION: &str =
+        "534678912672195348198342567859761423426853791713924856961537284287419635345286179";
+
+    #[test]
+    fn check_sudoku_solve() {
+        let input = CString::new(PUZZLE).unwrap();
+        let mut output = [0 as c_char; 82];
+
+        assert_eq!(
+            unsafe { sudoku_solve(input.as_ptr(), output.as_mut_ptr()) },
+            SUDOKU_OK
+        );
+        assert_eq!(
+            unsafe { CStr::from_ptr(output.as_ptr()) }.to_str(),
+            Ok(SOLUTION)
+        );
+
+        let input = CString::new(PUZZLE.replacen("53", "55", 1)).unwrap();
+        assert_eq!(
+            unsafe { sudoku_solve(input.as_ptr(), output.as_mut_ptr()) },
+            SUDOKU_NO_SOLUTION
+        );
+
+        for input in [
+            "53..7",
+            &PUZZLE.replacen("..", "\n.", 1),
+            &PUZZLE.replace('.', "x"),
+        ] {
+            let input = CString::new(input).unwrap();
+            assert_eq!(
+                unsafe { sudoku_solve(input.as_ptr(), output.as_mut_ptr()) },
+                SUDOKU_INVALID_ARGUMENT
+            );
+        }
+        assert_eq!(
+            unsafe { sudoku_solve(ptr::null(), output.as_mut_ptr()) },
+            SUDOKU_INVALID_ARGUMENT
+        );
+    }
+
+    #[test]
+    fn check_sudoku_count() {
+        let input = CString::new(PUZZLE).unwrap();
+        assert_eq!(unsafe { sudoku_count(input.as_ptr(), 10) }, 1);
+        assert_eq!(unsafe { sudoku_has_unique_solution(input.as_ptr()) }, 1);
+
+        let input = CString::new(format!("{}{}", ".".repeat(9), &PUZZLE[9..])).unwrap();
+        assert_eq!(unsafe { sudoku_count(input.as_ptr(), 2) }, 2);
+        assert_eq!(unsafe { sudoku_count(input.as_ptr(), 0) }, 0);
+        assert_eq!(unsafe { sudoku_has_unique_solution(input.as_ptr()) }, 0);
+    }
+}
@@ -0,0 +1,224 @@
+use std::{env, sync::OnceLock};
+
+/// Languages that have a message catalog.
+///
+/// To add a language, add a variant here, map its language tag in [Locale::from_tag], and provide
+/// a catalog function for it in [translate].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Locale {
+    English,
+}
+
+impl Locale {
+    /// Picks the locale from the environment (LC_ALL, LC_MESSAGES, then LANG), falling back to
+    /// English if none of them name a supported language.
+    pub fn from_env() -> Locale {
+        ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .into_iter()
+            .filter_map(|key| env::var(key).ok())
+            .find(|value| !value.is_empty())
+            .and_then(|value| Locale::from_tag(&value))
+            .unwrap_or(Locale::English)
+    }
+
+    /// Maps a POSIX locale name or language tag (e.g. "en", "en_GB.UTF-8", "en-US") to a locale.
+    pub fn from_tag(tag: &str) -> Option<Locale> {
+        let language = tag
+            .split(['_', '-', '.', '@'])
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+
+        match language.as_str() {
+            "en" | "c" | "posix" => Some(Locale::English),
+            _ => None,
+        }
+    }
+}
+
+/// User-facing strings, for the CLI and for errors returned by the library.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Message {
+    AppAbout,
+    InputFileHelp,
+    NoBorderHelp,
+    StatsHelp,
+    BenchAbout,
+    BenchRunsHelp,
+    BenchTimeoutHelp,
+    BackendHeader,
+    TierHeader,
+    SolvedHeader,
+    P99Header,
+    AutoBackend,
+    BacktrackingBackend,
+    PropagationBackend,
+    DlxBackend,
+    SatBackend,
+    AnnealingBackend,
+    EasyTier,
+    HardTier,
+    PathologicalTier,
+    ExplainAbout,
+    ExplainVerboseHelp,
+    ExplainSvgHelp,
+    LogicStuck,
+    NoSolution,
+    NoSolutionBecause,
+    NoCandidatesLeft,
+    NoPlaceLeft,
+    LogicUndetermined,
+    AfterTheseSteps,
+    ConflictingGivens,
+    InvalidRowCount,
+    InvalidRowLen,
+    InvalidTransformation,
+    InvalidPuzzleLine,
+    InvalidSukaku,
+    SukakuAbout,
+    SeveralSolutions,
+    ReportAbout,
+    EasyDifficulty,
+    MediumDifficulty,
+    HardDifficulty,
+    ExpertDifficulty,
+    BrutalDifficulty,
+    InvalidCount,
+    MeanHeader,
+    P50Header,
+    P90Header,
+    MaxHeader,
+    InvalidLabel,
+    OutliersLabel,
+    GivensLabel,
+    PerRowLabel,
+    PerColLabel,
+    PerSquareLabel,
+    PerDigitLabel,
+    EmptyUnitsLabel,
+    SymmetryLabel,
+    NoSymmetry,
+    Rotational180,
+    Rotational90,
+    MirrorHorizontal,
+    MirrorVertical,
+    Diagonal,
+    AntiDiagonal,
+}
+
+/// Gets the text for a message in the locale picked from the environment.
+pub fn tr(message: Message) -> &'static str {
+    static LOCALE: OnceLock<Locale> = OnceLock::new();
+
+    translate(*LOCALE.get_or_init(Locale::from_env), message)
+}
+
+/// Gets the text for a message in the given locale.
+pub fn translate(locale: Locale, message: Message) -> &'static str {
+    match locale {
+        Locale::English => english(message),
+    }
+}
+
+/// English catalog.
+fn english(message: Message) -> &'static str {
+    match message {
+        Message::AppAbout => "Solves a Sudoku puzzle and prints the solution to stdout.",
+        Message::InputFileHelp => "The input (puzzle) file to read from. Omit to read from stdin",
+        Message::NoBorderHelp => "Do not draw border to format the solution",
+        Message::StatsHelp => "Print statistics about the puzzle before the solution",
+        Message::BenchAbout => "Times the solver backends on a built-in corpus of puzzles",
+        Message::BenchRunsHelp => "Number of times to solve each puzzle with each backend",
+        Message::BenchTimeoutHelp => {
+            "Milliseconds to give each backtracking solve before giving up"
+        }
+        Message::BackendHeader => "backend",
+        Message::TierHeader => "tier",
+        Message::SolvedHeader => "solved",
+        Message::P99Header => "p99",
+        Message::AutoBackend => "auto",
+        Message::BacktrackingBackend => "backtracking",
+        Message::PropagationBackend => "propagation",
+        Message::DlxBackend => "dlx",
+        Message::SatBackend => "sat",
+        Message::AnnealingBackend => "annealing",
+        Message::EasyTier => "easy",
+        Message::HardTier => "hard",
+        Message::PathologicalTier => "pathological",
+        Message::ExplainAbout => "Solves a puzzle logically and explains each step",
+        Message::ExplainVerboseHelp => {
+            "Explain each step in a full sentence instead of solver notation"
+        }
+        Message::ExplainSvgHelp => {
+            "Also draw each step with links as an SVG image, step-N.svg, in this directory"
+        }
+        Message::LogicStuck => "Stuck: the rest needs techniques not supported, or guessing.",
+        Message::NoSolution => "No solution.",
+        Message::NoSolutionBecause => "No solution:",
+        Message::NoCandidatesLeft => "a blank has no candidates left:",
+        Message::NoPlaceLeft => "a digit has no place left in a unit:",
+        Message::LogicUndetermined => {
+            "logic gets stuck before finding a contradiction, so only trial and error shows that \
+             no digit fits."
+        }
+        Message::AfterTheseSteps => "After these steps:",
+        Message::ConflictingGivens => "Invalid input: the same digit is given twice in a unit:",
+        Message::InvalidRowCount => "Invalid input: incorrect number of rows.",
+        Message::InvalidRowLen => "Invalid input: incorrect row len.",
+        Message::InvalidTransformation => "Invalid input: not a valid transformation.",
+        Message::InvalidPuzzleLine => "Invalid input: expected a puzzle of 81 chars on line",
+        Message::InvalidSukaku => "Invalid input: not a candidate grid of 729 chars.",
+        Message::SukakuAbout => "Solves a pencil-mark puzzle given as 729 chars of candidates",
+        Message::SeveralSolutions => "The puzzle has more than one solution. One of them:",
+        Message::ReportAbout => {
+            "Rates a collection of puzzles, one per line, and summarizes their difficulty"
+        }
+        Message::EasyDifficulty => "Easy",
+        Message::MediumDifficulty => "Medium",
+        Message::HardDifficulty => "Hard",
+        Message::ExpertDifficulty => "Expert",
+        Message::BrutalDifficulty => "Brutal",
+        Message::InvalidCount => "Invalid",
+        Message::MeanHeader => "mean",
+        Message::P50Header => "p50",
+        Message::P90Header => "p90",
+        Message::MaxHeader => "max",
+        Message::InvalidLabel => "invalid:",
+        Message::OutliersLabel => "outliers:",
+        Message::GivensLabel => "Givens:",
+        Message::PerRowLabel => "Givens per row:",
+        Message::PerColLabel => "Givens per col:",
+        Message::PerSquareLabel => "Givens per box:",
+        Message::PerDigitLabel => "Givens per digit (1-9):",
+        Message::EmptyUnitsLabel => "Units without givens:",
+        Message::SymmetryLabel => "Symmetry:",
+        Message::NoSymmetry => "none",
+        Message::Rotational180 => "180° rotational",
+        Message::Rotational90 => "90° rotational",
+        Message::MirrorHorizontal => "horizontal mirror",
+        Message::MirrorVertical => "vertical mirror",
+        Message::Diagonal => "diagonal",
+        Message::AntiDiagonal => "anti-diagonal",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_locale_from_tag() {
+        assert_eq!(Locale::from_tag("en"), Some(Locale::English));
+        assert_eq!(Locale::from_tag("en_GB.UTF-8"), Some(Locale::English));
+        assert_eq!(Locale::from_tag("EN-us"), Some(Locale::English));
+        assert_eq!(Locale::from_tag("C"), Some(Locale::English));
+
+        assert_eq!(Locale::from_tag("xx_YY"), None);
+        assert_eq!(Locale::from_tag(""), None);
+    }
+
+    #[test]
+    fn check_translate() {
+        assert_eq!(translate(Locale::English, Message::NoSolution), "No solution.");
+    }
+}
@@ -6,14 +6,69 @@ use std::{
 
 use anyhow::{Result, anyhow};
 
-use crate::sudoku::{Puzzle, Solution};
+use crate::{
+    i18n::{Message, tr},
+    sudoku::{Puzzle, Solution},
+    sukaku::parse_sukaku,
+};
 
 /// Reads an input into a puzzle. If input file not provided, reads from stdin instead.
 ///
-/// The content of the file can either be exactly one of:
-/// (i)  a 9x9 char grid, with digits 1-9 in the appropriate positions.
-/// (ii) a 13x13 char grid, which is the same the 9x9 grid, but with an additional 1-char border
-///      around each 3x3 digit square.
+/// See [parse_puzzle] for the accepted formats.
+pub fn read_to_puzzle<P: AsRef<Path>>(input_file: Option<P>) -> Result<Puzzle> {
+    parse_puzzle(&read_input(input_file)?)
+}
+
+/// Reads an input into a collection of puzzles, one per line. If input file not provided, reads
+/// from stdin instead.
+///
+/// See [parse_puzzles] for the accepted format.
+pub fn read_to_puzzles<P: AsRef<Path>>(input_file: Option<P>) -> Result<Vec<Puzzle>> {
+    parse_puzzles(&read_input(input_file)?)
+}
+
+/// Reads an input into a grid of candidates. If input file not provided, reads from stdin
+/// instead.
+///
+/// See [parse_sukaku] for the accepted format.
+pub fn read_to_sukaku<P: AsRef<Path>>(input_file: Option<P>) -> Result<[[u16; 9]; 9]> {
+    parse_sukaku(&read_input(input_file)?)
+}
+
+/// Reads all of an input file, or stdin if not provided.
+fn read_input<P: AsRef<Path>>(input_file: Option<P>) -> Result<String> {
+    let mut buffer = String::new();
+    let mut reader: Box<dyn Read> = if let Some(input_file) = input_file {
+        Box::new(File::open(input_file)?)
+    } else {
+        Box::new(BufReader::new(io::stdin().lock()))
+    };
+    reader.read_to_string(&mut buffer)?;
+
+    Ok(buffer)
+}
+
+/// Parses a string into a collection of puzzles, one per line in the single line format of
+/// [parse_puzzle]. Empty lines and lines starting with # are skipped.
+pub fn parse_puzzles(input: &str) -> Result<Vec<Puzzle>> {
+    input
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty() && !line.starts_with('#'))
+        .map(|(index, line)| match line.trim_end().chars().count() {
+            81 => parse_puzzle(line.trim_end()),
+            _ => Err(anyhow!("{} {}", tr(Message::InvalidPuzzleLine), index + 1)),
+        })
+        .collect()
+}
+
+/// Parses a string into a puzzle.
+///
+/// The string can either be exactly one of:
+/// (i)   a 9x9 char grid, with digits 1-9 in the appropriate positions.
+/// (ii)  a 13x13 char grid, which is the same the 9x9 grid, but with an additional 1-char border
+///       around each 3x3 digit square.
+/// (iii) a single line of 81 chars, which is the 9x9 grid with its rows joined together.
 /// Non-digit chars, as well as the digit 0, will be regarded as blanks or part of the grid border.
 ///
 /// Examples of accepted input:
@@ -47,27 +102,28 @@ use crate::sudoku::{Puzzle, Solution};
 /// |   | 8 | 79|
 /// +---+---+---+
 /// ```
-pub fn read_to_puzzle<P: AsRef<Path>>(input_file: Option<P>) -> Result<Puzzle> {
-    let mut buffer = String::new();
-    let mut reader: Box<dyn Read> = if let Some(input_file) = input_file {
-        Box::new(File::open(input_file)?)
-    } else {
-        Box::new(BufReader::new(io::stdin().lock()))
-    };
-    reader.read_to_string(&mut buffer)?;
-
-    let mut lines = buffer
+///
+/// (c)
+/// ```text
+/// 53..7....6..195...98.....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79
+/// ```
+pub fn parse_puzzle(input: &str) -> Result<Puzzle> {
+    let mut lines = input
         .lines()
         .filter(|line| !line.is_empty())
         .map(String::from)
         .collect::<Vec<_>>();
+    if lines.len() == 1 && lines[0].chars().count() == 81 {
+        let chars = lines[0].chars().collect::<Vec<_>>();
+        lines = chars.chunks(9).map(String::from_iter).collect();
+    }
     if lines.len() != 9 && lines.len() != 13 {
-        return Err(anyhow!("Invalid input: incorrect number of rows."));
+        return Err(anyhow!(tr(Message::InvalidRowCount)));
     }
 
     if lines.len() == 13 {
         if !lines.iter().all(|line| line.chars().count() == 13) {
-            return Err(anyhow!("Invalid input: incorrect row len."));
+            return Err(anyhow!(tr(Message::InvalidRowLen)));
         }
 
         // Extract the embedded digits from the grid.
@@ -98,6 +154,10 @@ pub fn read_to_puzzle<P: AsRef<Path>>(input_file: Option<P>) -> Result<Puzzle> {
             .collect::<Vec<_>>();
     }
 
+    if lines.iter().any(|line| line.chars().count() > 9) {
+        return Err(anyhow!(tr(Message::InvalidRowLen)));
+    }
+
     let mut puzzle = [[0; 9]; 9];
     for (row, line) in lines.into_iter().enumerate() {
         for (col, c) in line.chars().enumerate() {
@@ -123,15 +183,15 @@ pub fn print_solution_with_border(solution: Solution) {
     println!("{}", solution_to_border_string(solution))
 }
 
-/// Converts a solution to a String for printing.
-fn solution_to_string(solution: Solution) -> String {
+/// Converts a solution to a String as a 9x9 grid (digits only).
+pub fn solution_to_string(solution: Solution) -> String {
     solution
         .map(|row| String::from_utf8_lossy(&row.map(|byte| byte + b'0')).to_string())
         .join("\n")
 }
 
-/// Converts a solution to a String for printing.
-fn solution_to_border_string(solution: Solution) -> String {
+/// Converts a solution to a String as a 13x13 grid (digits + border).
+pub fn solution_to_border_string(solution: Solution) -> String {
     format!(
         "╔═══╤═══╤═══╗\n{}╟───┼───┼───╢\n{}╟───┼───┼───╢\n{}╚═══╧═══╧═══╝",
         &solution[..3]
@@ -155,3 +215,55 @@ fn row_to_border_string(row: [u8; 9]) -> String {
         row[0], row[1], row[2], row[3], row[4], row[5], row[6], row[7], row[8],
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PUZZLE: Puzzle = [
+        [5, 3, 0, 0, 7, 0, 0, 0, 0],
+        [6, 0, 0, 1, 9, 5, 0, 0, 0],
+        [9, 8, 0, 0, 0, 0, 0, 6, 0],
+        [8, 0, 0, 0, 6, 0, 0, 0, 3],
+        [4, 0, 0, 8, 0, 3, 0, 0, 1],
+        [7, 0, 0, 0, 2, 0, 0, 0, 6],
+        [0, 6, 0, 0, 0, 0, 2, 8, 0],
+        [0, 0, 0, 4, 1, 9, 0, 0, 5],
+        [0, 0, 0, 0, 8, 0, 0, 7, 9],
+    ];
+
+    const LINE: &str =
+        "53..7....6..195...98.....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79";
+
+    #[test]
+    fn check_parse_puzzle_line() {
+        assert_eq!(parse_puzzle(LINE).unwrap(), PUZZLE);
+        assert_eq!(parse_puzzle(&format!("{LINE}\n")).unwrap(), PUZZLE);
+        assert_eq!(parse_puzzle(&LINE.replace('.', "0")).unwrap(), PUZZLE);
+
+        assert!(parse_puzzle(&LINE[1..]).is_err());
+        assert!(parse_puzzle(&format!("{LINE}.")).is_err());
+    }
+
+    #[test]
+    fn check_parse_puzzle_row_len() {
+        let rows = LINE
+            .as_bytes()
+            .chunks(9)
+            .map(|row| String::from_utf8_lossy(row).to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(parse_puzzle(&rows.join("\n")).unwrap(), PUZZLE);
+
+        // Short rows leave the rest blank.
+        let short = "5\n.\n.\n.\n.\n.\n.\n.\n.\n";
+        assert_eq!(parse_puzzle(short).unwrap()[0], [5, 0, 0, 0, 0, 0, 0, 0, 0]);
+
+        let long = "1234567891\n.\n.\n.\n.\n.\n.\n.\n.\n";
+        let error = parse_puzzle(long).unwrap_err();
+        assert_eq!(error.to_string(), tr(Message::InvalidRowLen));
+
+        let mut rows = rows;
+        rows[8].push('.');
+        assert!(parse_puzzle(&rows.join("\n")).is_err());
+    }
+}
@@ -0,0 +1,58 @@
+/// Finds every set of size distinct digits (1-9) that adds up to sum and avoids the excluded
+/// digits -- the possible contents of a killer cage.
+///
+/// Each combination is in ascending order, and the combinations are in lexicographic order.
+pub fn cage_combinations(size: usize, sum: u32, excluded: &[u8]) -> Vec<Vec<u8>> {
+    let excluded = excluded
+        .iter()
+        .filter(|digit| (1..=9).contains(*digit))
+        .fold(0u16, |set, digit| set | 1 << (digit - 1));
+
+    let mut combinations = (0u16..(1 << 9))
+        .filter(|digits| digits.count_ones() as usize == size && digits & excluded == 0)
+        .map(|digits| {
+            (1..=9)
+                .filter(|digit| digits & (1 << (digit - 1)) != 0)
+                .collect::<Vec<u8>>()
+        })
+        .filter(|digits| digits.iter().map(|digit| *digit as u32).sum::<u32>() == sum)
+        .collect::<Vec<_>>();
+    combinations.sort();
+
+    combinations
+}
+
+/// Finds the digits that can appear anywhere in a killer cage, i.e. the union of
+/// [cage_combinations], with bit (digit - 1) set for each digit.
+pub fn cage_candidates(size: usize, sum: u32, excluded: &[u8]) -> u16 {
+    cage_combinations(size, sum, excluded)
+        .into_iter()
+        .flatten()
+        .fold(0, |set, digit| set | 1 << (digit - 1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_cage_combinations() {
+        assert_eq!(cage_combinations(2, 3, &[]), vec![vec![1, 2]]);
+        assert_eq!(cage_combinations(2, 17, &[]), vec![vec![8, 9]]);
+        assert_eq!(
+            cage_combinations(3, 10, &[]),
+            vec![vec![1, 2, 7], vec![1, 3, 6], vec![1, 4, 5], vec![2, 3, 5]]
+        );
+        assert_eq!(cage_combinations(3, 10, &[1]), vec![vec![2, 3, 5]]);
+        assert_eq!(cage_combinations(9, 45, &[]).len(), 1);
+        assert!(cage_combinations(2, 18, &[]).is_empty());
+        assert_eq!(cage_combinations(0, 0, &[]), vec![Vec::<u8>::new()]);
+    }
+
+    #[test]
+    fn check_cage_candidates() {
+        assert_eq!(cage_candidates(2, 3, &[]), 0b11);
+        assert_eq!(cage_candidates(3, 10, &[1]), 0b10110);
+        assert_eq!(cage_candidates(2, 18, &[]), 0);
+    }
+}
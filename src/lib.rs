@@ -1,9 +1,74 @@
+mod analysis;
+mod bench;
+mod canonical;
+mod certificate;
+mod consistency;
+mod explain;
+mod ffi;
+mod i18n;
 mod io;
+mod killer;
+mod logic;
+mod marks;
+mod rating;
+mod render;
+mod replay;
+mod rng;
 mod sudoku;
+mod sukaku;
+#[cfg(feature = "json")]
+mod trace;
+mod transform;
 
 pub mod prelude {
     pub use super::{
-        io::{print_solution, print_solution_with_border, read_to_puzzle},
-        sudoku::{Puzzle, Solution, has_unique_solution, solve, solve_any},
+        analysis::{
+            Ambiguity, ClueStats, Profile, Symmetry, ambiguity, backdoor_size, clue_sensitivity,
+            detect_symmetry, estimate_solutions, is_minimal, minimize, minimize_with_effort,
+            profile, redundant_clues, stats, unavoidable_sets,
+        },
+        bench::{BenchPuzzle, BenchReport, CORPUS, Tier, bench, bench_backends},
+        canonical::{are_equivalent, canonicalize},
+        certificate::{Certificate, ProofNode, uniqueness_certificate},
+        consistency::{ConsistencyReport, Inconsistency, verify_consistency},
+        explain::{Unsolvable, Verbosity, explain, explain_unsolvable},
+        i18n::{Locale, Message, tr, translate},
+        io::{
+            parse_puzzle, parse_puzzles, print_solution, print_solution_with_border,
+            read_to_puzzle, read_to_puzzles, read_to_sukaku, solution_to_border_string,
+            solution_to_string,
+        },
+        killer::{cage_candidates, cage_combinations},
+        logic::{
+            Board, CustomTechnique, Hint, HintLevel, Link, LogicalOutcome, LogicalSolver,
+            PartialHint, Step, Technique, TechniqueCategory, hint, hint_at_level,
+        },
+        marks::{
+            MarkIssue, MarkIssueKind, candidate_count, cell_candidates, check_candidates,
+            check_pencil_marks, has_candidate,
+        },
+        rating::{
+            Difficulty, DifficultyReport, HardestCell, LogicalRating, SearchRating, SkillLevel,
+            difficulty_report, estimate_solve_time, guessing_point, hardest_cell, logic_coverage,
+            logical_rating, rate, requires_guessing, requires_technique, se_rating, search_rating,
+        },
+        render::{chain_to_border_string, chain_to_svg},
+        replay::Replay,
+        sudoku::{
+            Backend, CancelToken, Cancelled, Classification, Conflict, DigitOrder, GridPos,
+            Interruption, PartialGrid, Puzzle, Schedule, Snapshot, Solution, SolutionOrder,
+            SolveOutcome, SolveStats, Solver, SolverContext, StepSolver, StepStatus,
+            TooManySolutions, Uniqueness, Unit, candidates, classify, classify_with_limit, compare,
+            count_solutions, count_solutions_cancellable, count_solutions_exact,
+            count_solutions_exact_with_limit, find_conflicting_pairs, find_conflicts,
+            has_unique_solution, has_unique_solution_with, solve, solve_any, solve_any_with_order,
+            solve_batch, solve_cancellable, solve_in_place, solve_iter, solve_random, solve_with,
+            solve_with_order, solve_with_stats, solve_with_timeout, uniqueness, verify_solution,
+        },
+        sukaku::{parse_sukaku, solve_sukaku, sukaku_from_puzzle, sukaku_to_string},
+        transform::Transformation,
     };
+
+    #[cfg(feature = "json")]
+    pub use super::trace::{trace_json, trace_ndjson};
 }
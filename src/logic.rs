@@ -0,0 +1,1474 @@
+mod als;
+mod chains;
+mod coloring;
+mod fish;
+mod intersections;
+mod rectangles;
+mod singles;
+mod subsets;
+mod turbot;
+mod wings;
+
+use std::{collections::HashMap, fmt, sync::Arc};
+
+use crate::sudoku::{DigitSet, GridPos, Puzzle, Solution, Unit, candidates, cell, is_valid_puzzle};
+
+/// A human solving technique. Techniques are ordered from simplest to hardest.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Technique {
+    /// A digit that fits in only one cell of a unit.
+    HiddenSingle,
+
+    /// A cell that has only one candidate left.
+    NakedSingle,
+
+    /// A digit whose cells in a square all lie in one row or col, and so cannot go anywhere else
+    /// in that line.
+    Pointing,
+
+    /// A digit whose cells in a row or col all lie in one square, and so cannot go anywhere else
+    /// in that square.
+    BoxLineReduction,
+
+    /// Two cells of a unit with only the same two candidates.
+    NakedPair,
+
+    /// Two rows where a digit fits only in the same two cols, or the other way around.
+    XWing,
+
+    /// Two digits of a unit that fit in the same two cells only.
+    HiddenPair,
+
+    /// An X-Wing with extra cells in one square, including the sashimi kind.
+    FinnedXWing,
+
+    /// Three cells of a unit with only three candidates between them.
+    NakedTriple,
+
+    /// Three rows where a digit fits only in the same three cols between them, or the other way
+    /// around.
+    Swordfish,
+
+    /// Three digits of a unit that fit in only three cells between them.
+    HiddenTriple,
+
+    /// A Swordfish with extra cells in one square, including the sashimi kind.
+    FinnedSwordfish,
+
+    /// Two conjugate pairs of a digit in parallel lines, with one end of each in a shared line.
+    Skyscraper,
+
+    /// Two conjugate pairs of a digit, one in a row and one in a col, with one end of each in a
+    /// shared square.
+    TwoStringKite,
+
+    /// Two conjugate pairs of a digit, with one end of each seeing the other, in any other shape.
+    TurbotFish,
+
+    /// A pivot with candidates xy that sees two pincers with candidates xz and yz.
+    XYWing,
+
+    /// A pivot with candidates xyz that sees two pincers with candidates xz and yz.
+    XYZWing,
+
+    /// Four corners of a rectangle that would allow two solutions if they could only hold the same
+    /// two digits. Only used if the solution is assumed to be unique.
+    UniqueRectangle,
+
+    /// Two colors of a digit along conjugate pairs, one of which holds the digit everywhere.
+    SimpleColoring,
+
+    /// Four cells of a unit with only four candidates between them.
+    NakedQuad,
+
+    /// Four rows where a digit fits only in the same four cols between them, or the other way
+    /// around.
+    Jellyfish,
+
+    /// A Jellyfish with extra cells in one square, including the sashimi kind.
+    FinnedJellyfish,
+
+    /// Four digits of a unit that fit in only four cells between them.
+    HiddenQuad,
+
+    /// A chain of cells for one digit, alternating between conjugate pairs and cells that see
+    /// each other.
+    XChain,
+
+    /// A chain of cells with two candidates each, where each cell sees the next and shares a
+    /// candidate with it.
+    XYChain,
+
+    /// Two almost locked sets (N cells of a unit with N + 1 candidates) that share a restricted
+    /// common candidate. Only used if almost locked sets are turned on, as it is slow to look for.
+    AlsXz,
+
+    /// A technique added with [LogicalSolver::custom_technique], with its name. Not in
+    /// [Technique::ALL].
+    Custom(&'static str),
+}
+
+impl Technique {
+    /// Every technique, from simplest to hardest.
+    pub const ALL: [Technique; 26] = [
+        Technique::HiddenSingle,
+        Technique::NakedSingle,
+        Technique::Pointing,
+        Technique::BoxLineReduction,
+        Technique::NakedPair,
+        Technique::XWing,
+        Technique::HiddenPair,
+        Technique::FinnedXWing,
+        Technique::NakedTriple,
+        Technique::Swordfish,
+        Technique::HiddenTriple,
+        Technique::FinnedSwordfish,
+        Technique::Skyscraper,
+        Technique::TwoStringKite,
+        Technique::TurbotFish,
+        Technique::XYWing,
+        Technique::XYZWing,
+        Technique::UniqueRectangle,
+        Technique::SimpleColoring,
+        Technique::NakedQuad,
+        Technique::Jellyfish,
+        Technique::FinnedJellyfish,
+        Technique::HiddenQuad,
+        Technique::XChain,
+        Technique::XYChain,
+        Technique::AlsXz,
+    ];
+
+    /// Checks whether this technique relies on the puzzle having a unique solution, and so may
+    /// remove the wrong candidates if it has several.
+    pub fn assumes_uniqueness(self) -> bool {
+        self == Technique::UniqueRectangle
+    }
+
+    /// Gets the name of this technique, as shown to players, e.g. "hidden single" or "X-Wing".
+    pub fn name(self) -> &'static str {
+        match self {
+            Technique::HiddenSingle => "hidden single",
+            Technique::NakedSingle => "naked single",
+            Technique::Pointing => "pointing",
+            Technique::BoxLineReduction => "box/line reduction",
+            Technique::NakedPair => "naked pair",
+            Technique::XWing => "X-Wing",
+            Technique::HiddenPair => "hidden pair",
+            Technique::FinnedXWing => "finned X-Wing",
+            Technique::NakedTriple => "naked triple",
+            Technique::Swordfish => "Swordfish",
+            Technique::HiddenTriple => "hidden triple",
+            Technique::FinnedSwordfish => "finned Swordfish",
+            Technique::Skyscraper => "Skyscraper",
+            Technique::TwoStringKite => "Two-String Kite",
+            Technique::TurbotFish => "Turbot Fish",
+            Technique::XYWing => "XY-Wing",
+            Technique::XYZWing => "XYZ-Wing",
+            Technique::UniqueRectangle => "unique rectangle",
+            Technique::SimpleColoring => "simple coloring",
+            Technique::NakedQuad => "naked quad",
+            Technique::Jellyfish => "Jellyfish",
+            Technique::FinnedJellyfish => "finned Jellyfish",
+            Technique::HiddenQuad => "hidden quad",
+            Technique::XChain => "X-Chain",
+            Technique::XYChain => "XY-Chain",
+            Technique::AlsXz => "ALS-XZ",
+            Technique::Custom(name) => name,
+        }
+    }
+
+    /// Gets how hard this technique is, on a scale akin to Sudoku Explainer's ratings (1.0-11+),
+    /// e.g. to weight the steps of a solve. Techniques that Sudoku Explainer lacks are slotted in
+    /// between their neighbours, and custom techniques are as hard as the hardest other one.
+    pub fn weight(self) -> f32 {
+        match self {
+            Technique::HiddenSingle => 1.2,
+            Technique::NakedSingle => 2.3,
+            Technique::Pointing => 2.6,
+            Technique::BoxLineReduction => 2.8,
+            Technique::NakedPair => 3.0,
+            Technique::XWing => 3.2,
+            Technique::HiddenPair => 3.4,
+            Technique::FinnedXWing => 3.5,
+            Technique::NakedTriple => 3.6,
+            Technique::Swordfish => 3.8,
+            Technique::HiddenTriple => 4.0,
+            Technique::FinnedSwordfish
+            | Technique::Skyscraper
+            | Technique::TwoStringKite
+            | Technique::TurbotFish => 4.1,
+            Technique::XYWing => 4.2,
+            Technique::XYZWing => 4.4,
+            Technique::UniqueRectangle => 4.5,
+            Technique::SimpleColoring => 4.7,
+            Technique::NakedQuad => 5.0,
+            Technique::Jellyfish => 5.2,
+            Technique::FinnedJellyfish => 5.3,
+            Technique::HiddenQuad => 5.4,
+            Technique::XChain => 6.5,
+            Technique::XYChain => 6.6,
+            Technique::AlsXz | Technique::Custom(_) => 6.8,
+        }
+    }
+
+    /// Gets the family this technique belongs to, e.g. to group techniques in a legend.
+    pub fn category(self) -> TechniqueCategory {
+        match self {
+            Technique::HiddenSingle | Technique::NakedSingle => TechniqueCategory::Singles,
+            Technique::Pointing | Technique::BoxLineReduction => TechniqueCategory::Intersections,
+            Technique::NakedPair
+            | Technique::HiddenPair
+            | Technique::NakedTriple
+            | Technique::HiddenTriple
+            | Technique::NakedQuad
+            | Technique::HiddenQuad => TechniqueCategory::Subsets,
+            Technique::XWing
+            | Technique::FinnedXWing
+            | Technique::Swordfish
+            | Technique::FinnedSwordfish
+            | Technique::Jellyfish
+            | Technique::FinnedJellyfish => TechniqueCategory::Fish,
+            Technique::Skyscraper | Technique::TwoStringKite | Technique::TurbotFish => {
+                TechniqueCategory::SingleDigitPatterns
+            }
+            Technique::XYWing | Technique::XYZWing => TechniqueCategory::Wings,
+            Technique::UniqueRectangle => TechniqueCategory::Uniqueness,
+            Technique::SimpleColoring => TechniqueCategory::Coloring,
+            Technique::XChain | Technique::XYChain => TechniqueCategory::Chains,
+            Technique::AlsXz => TechniqueCategory::AlmostLockedSets,
+            Technique::Custom(_) => TechniqueCategory::Custom,
+        }
+    }
+
+    /// Finds a deduction on a board using this technique, with the options of a solver.
+    fn find(self, solver: &LogicalSolver, board: &Board) -> Option<Step> {
+        match self {
+            Technique::HiddenSingle => singles::hidden_single(board),
+            Technique::NakedSingle => singles::naked_single(board),
+            Technique::Pointing => intersections::pointing(board),
+            Technique::BoxLineReduction => intersections::box_line_reduction(board),
+            Technique::NakedPair => subsets::naked_subset(board, self, 2),
+            Technique::XWing => fish::fish(board, self, 2),
+            Technique::HiddenPair => subsets::hidden_subset(board, self, 2),
+            Technique::FinnedXWing => fish::finned_fish(board, self, 2),
+            Technique::NakedTriple => subsets::naked_subset(board, self, 3),
+            Technique::Swordfish => fish::fish(board, self, 3),
+            Technique::HiddenTriple => subsets::hidden_subset(board, self, 3),
+            Technique::FinnedSwordfish => fish::finned_fish(board, self, 3),
+            Technique::Skyscraper | Technique::TwoStringKite | Technique::TurbotFish => {
+                turbot::turbot_fish(board, self)
+            }
+            Technique::XYWing => wings::xy_wing(board),
+            Technique::XYZWing => wings::xyz_wing(board),
+            Technique::UniqueRectangle => rectangles::unique_rectangle(board),
+            Technique::SimpleColoring => coloring::simple_coloring(board),
+            Technique::NakedQuad => subsets::naked_subset(board, self, 4),
+            Technique::Jellyfish => fish::fish(board, self, 4),
+            Technique::FinnedJellyfish => fish::finned_fish(board, self, 4),
+            Technique::HiddenQuad => subsets::hidden_subset(board, self, 4),
+            Technique::XChain => chains::x_chain(board, solver.limit_of(self)),
+            Technique::XYChain => chains::xy_chain(board, solver.limit_of(self)),
+            Technique::AlsXz => als::als_xz(board, solver.limit_of(self)),
+            Technique::Custom(_) => None,
+        }
+    }
+}
+
+/// A family of related [Technique]s.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum TechniqueCategory {
+    /// Hidden and naked singles.
+    Singles,
+
+    /// Pointing and box/line reduction.
+    Intersections,
+
+    /// Naked and hidden pairs, triples and quads.
+    Subsets,
+
+    /// X-Wing, Swordfish and Jellyfish, finned or not.
+    Fish,
+
+    /// Skyscraper, Two-String Kite and Turbot Fish.
+    SingleDigitPatterns,
+
+    /// XY-Wing and XYZ-Wing.
+    Wings,
+
+    /// Techniques that assume the puzzle has a unique solution.
+    Uniqueness,
+
+    /// Simple coloring.
+    Coloring,
+
+    /// X-Chains and XY-Chains.
+    Chains,
+
+    /// ALS-XZ.
+    AlmostLockedSets,
+
+    /// Techniques added with [LogicalSolver::custom_technique].
+    Custom,
+}
+
+/// A solving technique that is not built in, e.g. a new strategy to experiment with, added to a
+/// solver with [LogicalSolver::custom_technique].
+pub trait CustomTechnique: fmt::Debug + Send + Sync {
+    /// Finds a deduction on a board, without applying it, as a step whose technique is
+    /// [Technique::Custom]. The solver applies the step, and stops if it makes no progress.
+    fn find(&self, board: &Board) -> Option<Step>;
+}
+
+/// One deduction made by a [LogicalSolver]: either a digit placed in a cell, or candidates
+/// removed from cells.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Step {
+    pub technique: Technique,
+
+    /// The units the pattern was found in, if any.
+    pub units: Vec<Unit>,
+
+    /// The cells that make up the pattern, in reading order, except for wings, whose pivot comes
+    /// first, and chains, which are in chain order.
+    pub cells: Vec<GridPos>,
+
+    /// The links between candidates followed by a chain or coloring, in order, for drawing.
+    pub links: Vec<Link>,
+
+    /// The candidates removed, in reading order, then digit order.
+    pub eliminations: Vec<(GridPos, u8)>,
+
+    /// The digit placed, if any. Placing a digit also removes it from the candidates of the cell's
+    /// peers, which is not listed in eliminations.
+    pub placement: Option<(GridPos, u8)>,
+}
+
+/// A link between two candidates, each a digit in a cell.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Link {
+    pub from: (GridPos, u8),
+    pub to: (GridPos, u8),
+
+    /// Whether at least one of the candidates must be true. Otherwise, at most one can be.
+    pub strong: bool,
+}
+
+/// The state of a logical solve: the digits placed so far, and the candidates left in each blank.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Board {
+    grid: Puzzle,
+
+    /// Candidates of each cell, with bit (digit - 1) set for each digit. Filled cells have none.
+    candidates: [[DigitSet; 9]; 9],
+}
+
+impl Board {
+    /// Sets up a board for a puzzle, with every digit that fits as a candidate. Returns None if the
+    /// puzzle is invalid.
+    pub fn new(puzzle: Puzzle) -> Option<Board> {
+        is_valid_puzzle(puzzle).then(|| Board {
+            grid: puzzle,
+            candidates: candidates(puzzle),
+        })
+    }
+
+    /// Sets up a board with no digits placed and the given candidates, with bit (digit - 1) set for
+    /// each digit, e.g. for a pencil-mark puzzle. Returns None if a cell has no candidates.
+    pub fn from_candidates(candidates: [[u16; 9]; 9]) -> Option<Board> {
+        let candidates = candidates.map(|row| row.map(|set| set & 0x1ff));
+        candidates
+            .iter()
+            .flatten()
+            .all(|&set| set != 0)
+            .then_some(Board {
+                grid: [[0; 9]; 9],
+                candidates,
+            })
+    }
+
+    /// Gets the digits placed so far.
+    pub fn grid(&self) -> Puzzle {
+        self.grid
+    }
+
+    /// Gets the candidates of a cell, with bit (digit - 1) set for each digit. A filled cell has no
+    /// candidates.
+    pub fn candidates(&self, (row, col): GridPos) -> u16 {
+        self.candidates[row][col]
+    }
+
+    /// Checks whether every cell is filled in.
+    pub fn is_solved(&self) -> bool {
+        self.grid.iter().flatten().all(|&digit| digit != 0)
+    }
+
+    /// Checks whether a blank has run out of candidates, i.e. the board cannot be solved.
+    pub fn is_broken(&self) -> bool {
+        all_cells().any(|(row, col)| self.grid[row][col] == 0 && self.candidates[row][col] == 0)
+    }
+
+    /// Applies the eliminations and placement of a step.
+    pub fn apply(&mut self, step: &Step) {
+        for &((row, col), digit) in &step.eliminations {
+            self.candidates[row][col] &= !(1 << (digit - 1));
+        }
+
+        if let Some((pos, digit)) = step.placement {
+            self.place(pos, digit);
+        }
+    }
+
+    /// Puts a digit in a blank, removing it from the candidates of the cell's peers.
+    fn place(&mut self, (row, col): GridPos, digit: u8) {
+        self.grid[row][col] = digit;
+        self.candidates[row][col] = 0;
+        for &(peer_row, peer_col) in &cell((row, col)).peers {
+            self.candidates[peer_row][peer_col] &= !(1 << (digit - 1));
+        }
+    }
+}
+
+/// How far a [LogicalSolver] got with a puzzle.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LogicalOutcome {
+    Solved(Solution),
+
+    /// No technique makes progress. Holds the digits placed and the candidates left.
+    Stuck(Board),
+}
+
+/// The next logical move on a puzzle, found by [hint].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Hint {
+    pub technique: Technique,
+
+    /// The units and cells to look at, as in [Step].
+    pub units: Vec<Unit>,
+    pub cells: Vec<GridPos>,
+
+    /// The candidates the move removes, in reading order, then digit order.
+    pub eliminations: Vec<(GridPos, u8)>,
+
+    /// The digit the move places, if any.
+    pub placement: Option<(GridPos, u8)>,
+}
+
+impl From<Step> for Hint {
+    fn from(step: Step) -> Hint {
+        Hint {
+            technique: step.technique,
+            units: step.units,
+            cells: step.cells,
+            eliminations: step.eliminations,
+            placement: step.placement,
+        }
+    }
+}
+
+impl Hint {
+    /// Gives away only as much of the hint as the level allows, for progressive disclosure.
+    pub fn reveal(&self, level: HintLevel) -> PartialHint {
+        // Chains and the like name no units, so point at the squares of the cells they change.
+        let mut units = self.units.clone();
+        if units.is_empty() {
+            for pos in self.targets() {
+                let square = Unit::Square(cell(pos).square);
+                if !units.contains(&square) {
+                    units.push(square);
+                }
+            }
+        }
+
+        let mut hint = PartialHint {
+            level,
+            units,
+            technique: None,
+            targets: Vec::new(),
+            eliminations: Vec::new(),
+            placement: None,
+        };
+        if level >= HintLevel::Technique {
+            hint.technique = Some(self.technique);
+        }
+        if level >= HintLevel::Cell {
+            hint.targets = self.targets();
+        }
+        if level >= HintLevel::Digit {
+            hint.eliminations = self.eliminations.clone();
+            hint.placement = self.placement;
+        }
+
+        hint
+    }
+
+    /// Gets the cells the move changes: the cell it fills in, or the cells that lose candidates, in
+    /// reading order.
+    fn targets(&self) -> Vec<GridPos> {
+        let mut targets = self
+            .placement
+            .iter()
+            .chain(&self.eliminations)
+            .map(|&(pos, _)| pos)
+            .collect::<Vec<_>>();
+        targets.dedup();
+
+        targets
+    }
+}
+
+/// How much of a [Hint] to give away, from a nudge (1) to the answer (4). Each level also reveals
+/// what the levels before it do.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum HintLevel {
+    /// The units to look at.
+    Region = 1,
+
+    /// The technique to use.
+    Technique = 2,
+
+    /// The cells the move changes.
+    Cell = 3,
+
+    /// The digit placed or the candidates removed.
+    Digit = 4,
+}
+
+/// What a [Hint] reveals at a level, found by [Hint::reveal] or [hint_at_level]. The parts not yet
+/// revealed are None or empty.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PartialHint {
+    pub level: HintLevel,
+
+    /// The units to look at. Always revealed.
+    pub units: Vec<Unit>,
+
+    /// Revealed from [HintLevel::Technique].
+    pub technique: Option<Technique>,
+
+    /// The cells the move changes, in reading order. Revealed from [HintLevel::Cell].
+    pub targets: Vec<GridPos>,
+
+    /// Revealed at [HintLevel::Digit], as in [Hint].
+    pub eliminations: Vec<(GridPos, u8)>,
+    pub placement: Option<(GridPos, u8)>,
+}
+
+/// Finds the next logical move on a puzzle, e.g. to nudge a player without giving away the whole
+/// solution, using the simplest technique of a default [LogicalSolver] that makes progress. See
+/// [LogicalSolver::hint] to pick the techniques.
+///
+/// Returns None if the puzzle is invalid or solved, or no technique makes progress.
+pub fn hint(puzzle: Puzzle) -> Option<Hint> {
+    LogicalSolver::new().hint(puzzle)
+}
+
+/// Like [hint], but only reveals as much of the move as the level allows.
+pub fn hint_at_level(puzzle: Puzzle, level: HintLevel) -> Option<PartialHint> {
+    Some(hint(puzzle)?.reveal(level))
+}
+
+/// Solves puzzles the way a person would, one deduction at a time, always using the simplest
+/// technique that makes progress. Never guesses.
+#[derive(Clone, Debug)]
+pub struct LogicalSolver {
+    /// The techniques to look for.
+    techniques: Vec<Technique>,
+
+    /// Limits set on the size of the patterns of techniques, see [LogicalSolver::limit].
+    limits: HashMap<Technique, usize>,
+
+    /// Techniques that are not built in, looked for after the others, in the order added.
+    custom: Vec<Arc<dyn CustomTechnique>>,
+}
+
+impl LogicalSolver {
+    /// Sets up a solver with every technique that holds for any puzzle except almost locked sets,
+    /// chains of up to 8 cells, and almost locked sets of up to 4 cells.
+    pub fn new() -> LogicalSolver {
+        LogicalSolver {
+            techniques: Technique::ALL
+                .into_iter()
+                .filter(|&technique| {
+                    !technique.assumes_uniqueness() && technique != Technique::AlsXz
+                })
+                .collect(),
+            limits: HashMap::new(),
+            custom: Vec::new(),
+        }
+    }
+
+    /// Sets up a solver that only looks for the given techniques, e.g. to check whether a puzzle
+    /// can be solved with singles alone.
+    pub fn with_techniques(techniques: &[Technique]) -> LogicalSolver {
+        LogicalSolver {
+            techniques: techniques.to_vec(),
+            ..LogicalSolver::new()
+        }
+    }
+
+    /// Turns techniques that assume that the puzzle has a unique solution on or off. They are off
+    /// by default, as they can remove the wrong candidates from puzzles with several solutions.
+    pub fn assume_uniqueness(self, assume_uniqueness: bool) -> LogicalSolver {
+        self.technique(Technique::UniqueRectangle, assume_uniqueness)
+    }
+
+    /// Sets the longest chain to look for, in cells, for both X-Chains and XY-Chains. Longer chains
+    /// find more deductions, but take longer to search for.
+    pub fn max_chain_length(self, max_chain_length: usize) -> LogicalSolver {
+        self.limit(Technique::XChain, max_chain_length)
+            .limit(Technique::XYChain, max_chain_length)
+    }
+
+    /// Turns techniques based on almost locked sets on or off. They are off by default, as they are
+    /// slow to look for.
+    pub fn almost_locked_sets(self, almost_locked_sets: bool) -> LogicalSolver {
+        self.technique(Technique::AlsXz, almost_locked_sets)
+    }
+
+    /// Turns a single technique on or off, e.g. to match the techniques of another grader.
+    pub fn technique(mut self, technique: Technique, enabled: bool) -> LogicalSolver {
+        self.techniques.retain(|&other| other != technique);
+        if enabled {
+            self.techniques.push(technique);
+        }
+
+        self
+    }
+
+    /// Sets the limit on the size of the patterns a technique looks for: the longest chain, in
+    /// cells, for X-Chains (8 by default) and XY-Chains (8), and the largest almost locked set, in
+    /// cells, for ALS-XZ (4). Other techniques have no limit, and ignore it.
+    pub fn limit(mut self, technique: Technique, limit: usize) -> LogicalSolver {
+        self.limits.insert(technique, limit);
+        self
+    }
+
+    /// Adds a technique that is not built in, looked for after the built-in ones.
+    pub fn custom_technique(mut self, technique: impl CustomTechnique + 'static) -> LogicalSolver {
+        self.custom.push(Arc::new(technique));
+        self
+    }
+
+    /// Finds the steps that solve a puzzle, stopping early if no technique makes progress. Returns
+    /// None if the puzzle is invalid.
+    pub fn solve(&self, puzzle: Puzzle) -> Option<Vec<Step>> {
+        Some(self.solve_board(Board::new(puzzle)?))
+    }
+
+    /// Finds the steps that solve a board, e.g. one set up with [Board::from_candidates], stopping
+    /// early if no technique makes progress.
+    pub fn solve_board(&self, mut board: Board) -> Vec<Step> {
+        let mut steps = Vec::new();
+        while !board.is_solved() && !board.is_broken() {
+            let Some(step) = self.next_step(&board) else {
+                break;
+            };
+
+            // Guards against custom techniques that make no progress.
+            let before = board;
+            board.apply(&step);
+            if board == before {
+                break;
+            }
+
+            steps.push(step);
+        }
+
+        steps
+    }
+
+    /// Solves a puzzle as far as the techniques go. Returns None if the puzzle is invalid.
+    pub fn try_solve(&self, puzzle: Puzzle) -> Option<LogicalOutcome> {
+        Some(self.try_solve_board(Board::new(puzzle)?))
+    }
+
+    /// Solves a board as far as the techniques go.
+    pub fn try_solve_board(&self, mut board: Board) -> LogicalOutcome {
+        for step in self.solve_board(board) {
+            board.apply(&step);
+        }
+
+        if board.is_solved() {
+            LogicalOutcome::Solved(board.grid())
+        } else {
+            LogicalOutcome::Stuck(board)
+        }
+    }
+
+    /// Finds the next logical move on a puzzle, like [hint], but only with the techniques of this
+    /// solver.
+    pub fn hint(&self, puzzle: Puzzle) -> Option<Hint> {
+        let board = Board::new(puzzle)?;
+        if board.is_broken() {
+            return None;
+        }
+
+        self.next_step(&board).map(Hint::from)
+    }
+
+    /// Finds the next deduction on a board, using the simplest technique that makes progress.
+    pub fn next_step(&self, board: &Board) -> Option<Step> {
+        Technique::ALL
+            .iter()
+            .filter(|&&technique| self.is_enabled(technique))
+            .find_map(|technique| technique.find(self, board))
+            .or_else(|| {
+                self.custom
+                    .iter()
+                    .find_map(|technique| technique.find(board))
+            })
+    }
+
+    /// Checks whether the solver looks for a built-in technique.
+    pub fn is_enabled(&self, technique: Technique) -> bool {
+        self.techniques.contains(&technique)
+    }
+
+    /// Gets the limit on the size of the patterns of a technique, see [LogicalSolver::limit].
+    fn limit_of(&self, technique: Technique) -> usize {
+        let default = match technique {
+            Technique::AlsXz => 4,
+            _ => 8,
+        };
+
+        self.limits.get(&technique).copied().unwrap_or(default)
+    }
+}
+
+impl Default for LogicalSolver {
+    fn default() -> Self {
+        LogicalSolver::new()
+    }
+}
+
+/// Iterates over the positions of all cells, in reading order.
+fn all_cells() -> impl Iterator<Item = GridPos> {
+    (0..81).map(|index| (index / 9, index % 9))
+}
+
+/// Checks whether two different cells share a row, col or square.
+fn sees(first: GridPos, second: GridPos) -> bool {
+    cell(first).peers.contains(&second)
+}
+
+/// Iterates over the digits in a set of candidates, in order.
+fn digits(candidates: DigitSet) -> impl Iterator<Item = u8> {
+    (1..=9).filter(move |digit| candidates & (1 << (digit - 1)) != 0)
+}
+
+/// Gets every way to pick size items, keeping their order.
+fn combinations<T: Copy>(items: &[T], size: usize) -> Vec<Vec<T>> {
+    if size == 0 {
+        return vec![Vec::new()];
+    }
+
+    (0..items.len())
+        .flat_map(|index| {
+            combinations(&items[index + 1..], size - 1)
+                .into_iter()
+                .map(move |mut rest| {
+                    rest.insert(0, items[index]);
+                    rest
+                })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::sudoku::solve_any;
+
+    use super::*;
+
+    const PUZZLE: Puzzle = [
+        [5, 3, 0, 0, 7, 0, 0, 0, 0],
+        [6, 0, 0, 1, 9, 5, 0, 0, 0],
+        [9, 8, 0, 0, 0, 0, 0, 6, 0],
+        [8, 0, 0, 0, 6, 0, 0, 0, 3],
+        [4, 0, 0, 8, 0, 3, 0, 0, 1],
+        [7, 0, 0, 0, 2, 0, 0, 0, 6],
+        [0, 6, 0, 0, 0, 0, 2, 8, 0],
+        [0, 0, 0, 4, 1, 9, 0, 0, 5],
+        [0, 0, 0, 0, 8, 0, 0, 7, 9],
+    ];
+
+    /// Needs pointing.
+    const MEDIUM: Puzzle = [
+        [4, 0, 0, 0, 0, 0, 8, 0, 5],
+        [0, 3, 0, 0, 0, 0, 0, 0, 0],
+        [0, 0, 0, 7, 0, 0, 0, 0, 0],
+        [0, 2, 0, 0, 0, 0, 0, 6, 0],
+        [0, 0, 0, 0, 8, 0, 4, 0, 0],
+        [0, 0, 0, 0, 1, 0, 0, 0, 0],
+        [0, 0, 0, 6, 0, 3, 0, 7, 0],
+        [5, 0, 0, 2, 0, 0, 0, 0, 0],
+        [1, 0, 4, 0, 0, 0, 0, 0, 0],
+    ];
+
+    const HARD: Puzzle = [
+        [8, 0, 0, 0, 0, 0, 0, 0, 0],
+        [0, 0, 3, 6, 0, 0, 0, 0, 0],
+        [0, 7, 0, 0, 9, 0, 2, 0, 0],
+        [0, 5, 0, 0, 0, 7, 0, 0, 0],
+        [0, 0, 0, 0, 4, 5, 7, 0, 0],
+        [0, 0, 0, 1, 0, 0, 0, 3, 0],
+        [0, 0, 1, 0, 0, 0, 0, 6, 8],
+        [0, 0, 8, 5, 0, 0, 0, 1, 0],
+        [0, 9, 0, 0, 0, 0, 4, 0, 0],
+    ];
+
+    /// Replays steps on a puzzle.
+    fn replay(puzzle: Puzzle, steps: &[Step]) -> Board {
+        let mut board = Board::new(puzzle).unwrap();
+        for step in steps {
+            board.apply(step);
+        }
+
+        board
+    }
+
+    #[test]
+    fn check_board() {
+        let mut board = Board::new(PUZZLE).unwrap();
+        assert_eq!(board.grid(), PUZZLE);
+        assert_eq!(board.candidates((0, 0)), 0);
+        assert_eq!(board.candidates((0, 2)), 0b000001011);
+        assert!(!board.is_solved());
+        assert!(!board.is_broken());
+
+        board.apply(&Step {
+            technique: Technique::NakedSingle,
+            units: Vec::new(),
+            cells: vec![(0, 2)],
+            links: Vec::new(),
+            eliminations: vec![((0, 3), 6)],
+            placement: Some(((0, 2), 4)),
+        });
+        assert_eq!(board.grid()[0][2], 4);
+        assert_eq!(board.candidates((0, 2)), 0);
+        assert_eq!(board.candidates((1, 2)) & 0b000001000, 0);
+        assert_eq!(board.candidates((0, 3)), 0b000000010);
+
+        let mut invalid = PUZZLE;
+        invalid[0][2] = 5;
+        assert_eq!(Board::new(invalid), None);
+    }
+
+    #[test]
+    fn check_combinations() {
+        assert_eq!(
+            combinations(&[1, 2, 3, 4], 2),
+            [[1, 2], [1, 3], [1, 4], [2, 3], [2, 4], [3, 4]]
+        );
+        assert_eq!(combinations(&[1, 2], 3), Vec::<Vec<i32>>::new());
+        assert_eq!(digits(0b100010100).collect::<Vec<_>>(), [3, 5, 9]);
+    }
+
+    #[test]
+    fn check_locked_candidates() {
+        let solver = LogicalSolver::new();
+
+        // 1 fits only in row 0 within square 0.
+        let mut board = Board::new([[0; 9]; 9]).unwrap();
+        for (row, col) in [(1, 0), (1, 1), (1, 2), (2, 0), (2, 1), (2, 2)] {
+            board.candidates[row][col] &= !1;
+        }
+        let step = solver.next_step(&board).unwrap();
+        assert_eq!(step.technique, Technique::Pointing);
+        assert_eq!(step.units, [Unit::Square(0), Unit::Row(0)]);
+        assert_eq!(step.cells, [(0, 0), (0, 1), (0, 2)]);
+        assert_eq!(
+            step.eliminations,
+            (3..9).map(|col| ((0, col), 1)).collect::<Vec<_>>()
+        );
+
+        // 1 fits only in square 0 within row 0.
+        let mut board = Board::new([[0; 9]; 9]).unwrap();
+        for col in 2..9 {
+            board.candidates[0][col] &= !1;
+        }
+        let step = solver.next_step(&board).unwrap();
+        assert_eq!(step.technique, Technique::BoxLineReduction);
+        assert_eq!(step.units, [Unit::Row(0), Unit::Square(0)]);
+        assert_eq!(step.cells, [(0, 0), (0, 1)]);
+        assert_eq!(step.eliminations.len(), 6);
+        assert_eq!(step.eliminations[0], ((1, 0), 1));
+    }
+
+    #[test]
+    fn check_naked_subsets() {
+        let solver = LogicalSolver::new();
+
+        let mut board = Board::new([[0; 9]; 9]).unwrap();
+        board.candidates[0][0] = 0b011;
+        board.candidates[0][1] = 0b011;
+        let step = solver.next_step(&board).unwrap();
+        assert_eq!(step.technique, Technique::NakedPair);
+        assert_eq!(step.units, [Unit::Row(0)]);
+        assert_eq!(step.cells, [(0, 0), (0, 1)]);
+        assert_eq!(step.eliminations.len(), 14);
+        assert_eq!(step.eliminations[..2], [((0, 2), 1), ((0, 2), 2)]);
+        assert_eq!(step.placement, None);
+
+        let mut board = Board::new([[0; 9]; 9]).unwrap();
+        board.candidates[0][0] = 0b011;
+        board.candidates[0][4] = 0b110;
+        board.candidates[0][8] = 0b101;
+        let step = solver.next_step(&board).unwrap();
+        assert_eq!(step.technique, Technique::NakedTriple);
+        assert_eq!(step.cells, [(0, 0), (0, 4), (0, 8)]);
+        assert_eq!(step.eliminations.len(), 18);
+    }
+
+    #[test]
+    fn check_hidden_subsets() {
+        let solver = LogicalSolver::new();
+
+        // 1 and 2 fit only in two cells of row 0, in different squares.
+        let mut board = Board::new([[0; 9]; 9]).unwrap();
+        for col in [1, 2, 3, 5, 6, 7, 8] {
+            board.candidates[0][col] = 0b111111100;
+        }
+        let step = solver.next_step(&board).unwrap();
+        assert_eq!(step.technique, Technique::HiddenPair);
+        assert_eq!(step.units, [Unit::Row(0)]);
+        assert_eq!(step.cells, [(0, 0), (0, 4)]);
+        assert_eq!(step.eliminations.len(), 14);
+        assert_eq!(step.eliminations[..2], [((0, 0), 3), ((0, 0), 4)]);
+
+        // 1, 2 and 3 fit only in three cells of row 0, but no two of them share two cells.
+        let mut board = Board::new([[0; 9]; 9]).unwrap();
+        for col in 0..9 {
+            board.candidates[0][col] = match col {
+                0 => 0b111111011,
+                4 => 0b111111110,
+                8 => 0b111111101,
+                _ => 0b111111000,
+            };
+        }
+        let step = solver.next_step(&board).unwrap();
+        assert_eq!(step.technique, Technique::HiddenTriple);
+        assert_eq!(step.cells, [(0, 0), (0, 4), (0, 8)]);
+        assert_eq!(step.eliminations.len(), 18);
+    }
+
+    #[test]
+    fn check_fish() {
+        let solver = LogicalSolver::new();
+
+        // 1 fits only in cols 0 and 4 within rows 0 and 3.
+        let mut board = Board::new([[0; 9]; 9]).unwrap();
+        for row in [0, 3] {
+            for col in [1, 2, 3, 5, 6, 7, 8] {
+                board.candidates[row][col] &= !1;
+            }
+        }
+        let step = solver.next_step(&board).unwrap();
+        assert_eq!(step.technique, Technique::XWing);
+        assert_eq!(
+            step.units,
+            [Unit::Row(0), Unit::Row(3), Unit::Col(0), Unit::Col(4)]
+        );
+        assert_eq!(step.cells, [(0, 0), (0, 4), (3, 0), (3, 4)]);
+        assert_eq!(step.eliminations.len(), 14);
+        assert_eq!(step.eliminations[..2], [((1, 0), 1), ((1, 4), 1)]);
+
+        // 1 fits only in cols 0, 4 and 8 within rows 0, 3 and 6, two cols per row.
+        let mut board = Board::new([[0; 9]; 9]).unwrap();
+        for (row, cols) in [(0, [0, 4]), (3, [4, 8]), (6, [0, 8])] {
+            for col in (0..9).filter(|col| !cols.contains(col)) {
+                board.candidates[row][col] &= !1;
+            }
+        }
+        let step = solver.next_step(&board).unwrap();
+        assert_eq!(step.technique, Technique::Swordfish);
+        assert_eq!(step.units.len(), 6);
+        assert_eq!(step.cells, [(0, 0), (0, 4), (3, 4), (3, 8), (6, 0), (6, 8)]);
+        assert_eq!(step.eliminations.len(), 18);
+
+        // The same with cols and rows swapped, spread over four lines.
+        let mut board = Board::new([[0; 9]; 9]).unwrap();
+        for (col, rows) in [(0, [0, 6]), (1, [3, 7]), (4, [3, 6]), (7, [0, 7])] {
+            for row in (0..9).filter(|row| !rows.contains(row)) {
+                board.candidates[row][col] &= !1;
+            }
+        }
+        let step = fish::fish(&board, Technique::Jellyfish, 4).unwrap();
+        assert_eq!(step.technique, Technique::Jellyfish);
+        assert_eq!(
+            step.units[..4],
+            [Unit::Col(0), Unit::Col(1), Unit::Col(4), Unit::Col(7)]
+        );
+        assert_eq!(step.eliminations.len(), 20);
+    }
+
+    #[test]
+    fn check_finned_fish() {
+        // An X-Wing on r1 and r4, c1 and c5, with a fin in r1c6.
+        let mut board = Board::new([[0; 9]; 9]).unwrap();
+        for (row, cols) in [(0, vec![0, 4, 5]), (3, vec![0, 4])] {
+            for col in (0..9).filter(|col| !cols.contains(col)) {
+                board.candidates[row][col] &= !1;
+            }
+        }
+        assert_eq!(fish::fish(&board, Technique::XWing, 2), None);
+        let step = fish::finned_fish(&board, Technique::FinnedXWing, 2).unwrap();
+        assert_eq!(step.technique, Technique::FinnedXWing);
+        assert_eq!(
+            step.units,
+            [Unit::Row(0), Unit::Row(3), Unit::Col(0), Unit::Col(4)]
+        );
+        assert_eq!(step.cells, [(0, 0), (0, 4), (0, 5), (3, 0), (3, 4)]);
+        assert_eq!(step.eliminations, [((1, 4), 1), ((2, 4), 1)]);
+
+        // The same without r1c5, which leaves r1c1 alone.
+        board.candidates[0][4] &= !1;
+        let step = fish::finned_fish(&board, Technique::FinnedXWing, 2).unwrap();
+        assert_eq!(step.eliminations, [((1, 4), 1), ((2, 4), 1)]);
+    }
+
+    #[test]
+    fn check_wings() {
+        let solver = LogicalSolver::new();
+
+        let mut board = Board::new([[0; 9]; 9]).unwrap();
+        board.candidates[0][0] = 0b011;
+        board.candidates[0][4] = 0b101;
+        board.candidates[4][0] = 0b110;
+        let step = solver.next_step(&board).unwrap();
+        assert_eq!(step.technique, Technique::XYWing);
+        assert_eq!(step.cells, [(0, 0), (0, 4), (4, 0)]);
+        assert_eq!(step.eliminations, [((4, 4), 3)]);
+
+        let mut board = Board::new([[0; 9]; 9]).unwrap();
+        board.candidates[0][0] = 0b111;
+        board.candidates[0][4] = 0b101;
+        board.candidates[1][1] = 0b110;
+        let step = solver.next_step(&board).unwrap();
+        assert_eq!(step.technique, Technique::XYZWing);
+        assert_eq!(step.cells, [(0, 0), (0, 4), (1, 1)]);
+        assert_eq!(step.eliminations, [((0, 1), 3), ((0, 2), 3)]);
+    }
+
+    /// Sets up a board with the given candidates in the corners of the rectangle r1c1 r1c4 r2c1
+    /// r2c4, where 1 is left only in the corners, and 2 is left only in the corners within row 0,
+    /// col 0 and square 0.
+    fn rectangle_board(corners: [DigitSet; 4]) -> Board {
+        let mut board = Board::new([[0; 9]; 9]).unwrap();
+        for (row, col) in all_cells() {
+            board.candidates[row][col] &= !0b1;
+            if row == 0 || col == 0 || (row < 3 && col < 3) {
+                board.candidates[row][col] &= !0b10;
+            }
+        }
+        for (pos, candidates) in [(0, 0), (0, 3), (1, 0), (1, 3)].into_iter().zip(corners) {
+            board.candidates[pos.0][pos.1] = candidates;
+        }
+
+        board
+    }
+
+    #[test]
+    fn check_unique_rectangle() {
+        let board = rectangle_board([0b011, 0b011, 0b011, 0b111]);
+        assert_eq!(LogicalSolver::new().next_step(&board), None);
+        let step = LogicalSolver::new()
+            .assume_uniqueness(true)
+            .next_step(&board)
+            .unwrap();
+        assert_eq!(step.technique, Technique::UniqueRectangle);
+        assert_eq!(step.cells, [(0, 0), (0, 3), (1, 0), (1, 3)]);
+        assert_eq!(step.eliminations, [((1, 3), 1), ((1, 3), 2)]);
+
+        let step = rectangles::unique_rectangle(&rectangle_board([0b011, 0b011, 0b111, 0b111]));
+        let eliminations = [1, 2, 4, 5, 6, 7, 8].map(|col| ((1, col), 3));
+        assert_eq!(step.unwrap().eliminations, eliminations);
+
+        let mut board = rectangle_board([0b011, 0b011, 0b0111, 0b1011]);
+        board.candidates[1][5] = 0b1100;
+        let step = rectangles::unique_rectangle(&board);
+        assert_eq!(step.unwrap().eliminations.len(), 12);
+
+        let board = rectangle_board([0b011, 0b011, 0b0111, 0b1011]);
+        let step = rectangles::unique_rectangle(&board);
+        assert_eq!(step.unwrap().eliminations, [((1, 0), 2), ((1, 3), 2)]);
+    }
+
+    /// Sets up a board where 1 is left only in the given cells.
+    fn digit_board(cells: &[GridPos]) -> Board {
+        let mut board = Board::new([[0; 9]; 9]).unwrap();
+        for (row, col) in all_cells() {
+            if !cells.contains(&(row, col)) {
+                board.candidates[row][col] &= !1;
+            }
+        }
+
+        board
+    }
+
+    #[test]
+    fn check_simple_coloring() {
+        // r1c1 and r5c2 have different colors, and both see r2c2.
+        let board = digit_board(&[(0, 0), (0, 4), (4, 4), (4, 1), (1, 1), (7, 1), (2, 2)]);
+        let step = coloring::simple_coloring(&board).unwrap();
+        assert_eq!(step.technique, Technique::SimpleColoring);
+        assert_eq!(step.cells, [(0, 0), (0, 4), (4, 1), (4, 4)]);
+        assert_eq!(step.links.len(), 3);
+        assert_eq!(step.links[0].from, ((0, 0), 1));
+        assert_eq!(step.links[0].to, ((0, 4), 1));
+        assert!(step.links.iter().all(|link| link.strong));
+        assert_eq!(step.eliminations, [((1, 1), 1)]);
+
+        // r1c1 and r2c2 have the same color, and see each other.
+        let board = digit_board(&[(0, 0), (0, 4), (4, 4), (4, 1), (1, 1), (2, 2)]);
+        let step = coloring::simple_coloring(&board).unwrap();
+        assert_eq!(step.eliminations, [((0, 0), 1), ((1, 1), 1), ((4, 4), 1)]);
+    }
+
+    #[test]
+    fn check_chains() {
+        // r1c1 = r7c1 - r7c5 = r2c5, so one of r1c1 and r2c5 holds 1.
+        let board = digit_board(&[
+            (0, 0),
+            (6, 0),
+            (6, 4),
+            (1, 4),
+            (6, 8),
+            (1, 1),
+            (1, 7),
+            (2, 2),
+        ]);
+        let step = chains::x_chain(&board, 8).unwrap();
+        assert_eq!(step.technique, Technique::XChain);
+        assert_eq!(step.cells, [(0, 0), (6, 0), (6, 4), (1, 4)]);
+        assert_eq!(
+            step.links
+                .iter()
+                .map(|link| link.strong)
+                .collect::<Vec<_>>(),
+            [true, false, true]
+        );
+        assert_eq!(step.eliminations, [((1, 1), 1)]);
+        assert_eq!(chains::x_chain(&board, 3), None);
+
+        let mut board = Board::new([[0; 9]; 9]).unwrap();
+        board.candidates[0][0] = 0b0011;
+        board.candidates[0][4] = 0b0110;
+        board.candidates[4][4] = 0b1100;
+        board.candidates[4][1] = 0b1001;
+        let step = chains::xy_chain(&board, 8).unwrap();
+        assert_eq!(step.technique, Technique::XYChain);
+        assert_eq!(step.cells, [(0, 0), (0, 4), (4, 4), (4, 1)]);
+        assert_eq!(step.links.len(), 7);
+        assert_eq!(step.links[1].from, ((0, 0), 2));
+        assert_eq!(step.links[1].to, ((0, 4), 2));
+        assert_eq!(step.eliminations.len(), 6);
+        assert!(step.eliminations.iter().all(|&(_, digit)| digit == 1));
+        assert_eq!(chains::xy_chain(&board, 3), None);
+    }
+
+    #[test]
+    fn check_turbot_fish() {
+        let board = digit_board(&[
+            (0, 0),
+            (6, 0),
+            (6, 4),
+            (1, 4),
+            (6, 8),
+            (1, 1),
+            (1, 7),
+            (2, 2),
+        ]);
+        let step = turbot::turbot_fish(&board, Technique::Skyscraper).unwrap();
+        assert_eq!(step.technique, Technique::Skyscraper);
+        assert_eq!(step.units, [Unit::Col(0), Unit::Col(4)]);
+        assert_eq!(step.cells, [(0, 0), (6, 0), (6, 4), (1, 4)]);
+        assert_eq!(step.eliminations, [((1, 1), 1)]);
+
+        let board = digit_board(&[(0, 7), (0, 1), (2, 0), (6, 0), (6, 7)]);
+        let step = turbot::turbot_fish(&board, Technique::TwoStringKite).unwrap();
+        assert_eq!(step.eliminations, [((6, 7), 1)]);
+
+        let board = digit_board(&[
+            (0, 0),
+            (1, 2),
+            (1, 4),
+            (1, 6),
+            (5, 0),
+            (5, 3),
+            (5, 6),
+            (7, 0),
+        ]);
+        assert_eq!(turbot::turbot_fish(&board, Technique::Skyscraper), None);
+        let step = turbot::turbot_fish(&board, Technique::TurbotFish).unwrap();
+        assert_eq!(step.units, [Unit::Col(6), Unit::Square(0)]);
+        assert_eq!(step.eliminations, [((5, 0), 1)]);
+    }
+
+    #[test]
+    fn check_als_xz() {
+        // r1c1 and r1c5 r5c5 share 1, which r1c1 and r1c5 cannot both hold, and 2.
+        let mut board = Board::new([[0; 9]; 9]).unwrap();
+        board.candidates[0][0] = 0b011;
+        board.candidates[0][4] = 0b101;
+        board.candidates[4][4] = 0b110;
+        let step = als::als_xz(&board, 4).unwrap();
+        assert_eq!(step.technique, Technique::AlsXz);
+        assert_eq!(step.eliminations, [((4, 0), 2)]);
+        assert_eq!(als::als_xz(&board, 1), None);
+
+        let solver = LogicalSolver::new();
+        assert!(!solver.is_enabled(Technique::AlsXz));
+        assert!(solver.almost_locked_sets(true).is_enabled(Technique::AlsXz));
+    }
+
+    #[test]
+    fn check_technique_metadata() {
+        for pair in Technique::ALL.windows(2) {
+            assert!(pair[0].weight() <= pair[1].weight(), "{pair:?}");
+        }
+
+        let mut names = Technique::ALL.map(Technique::name);
+        names.sort();
+        assert!(names.windows(2).all(|pair| pair[0] != pair[1]));
+
+        assert_eq!(Technique::XWing.name(), "X-Wing");
+        assert_eq!(Technique::HiddenSingle.weight(), 1.2);
+        assert_eq!(
+            Technique::FinnedJellyfish.category(),
+            TechniqueCategory::Fish
+        );
+        assert!(
+            Technique::ALL
+                .iter()
+                .all(|technique| technique.assumes_uniqueness()
+                    == (technique.category() == TechniqueCategory::Uniqueness))
+        );
+    }
+
+    #[test]
+    fn check_hint() {
+        let next = hint(PUZZLE).unwrap();
+        let step = LogicalSolver::new().solve(PUZZLE).unwrap().remove(0);
+        assert_eq!(next.technique, step.technique);
+        assert_eq!(next.cells, step.cells);
+        assert_eq!(next.placement, step.placement);
+        let ((row, col), digit) = next.placement.unwrap();
+        assert_eq!(solve_any(PUZZLE).unwrap()[row][col], digit);
+
+        // Only eliminations left once singles run out.
+        let singles =
+            LogicalSolver::with_techniques(&[Technique::HiddenSingle, Technique::NakedSingle]);
+        let Some(LogicalOutcome::Stuck(board)) = singles.try_solve(MEDIUM) else {
+            panic!("singles should not solve MEDIUM");
+        };
+        let stuck = hint(board.grid()).unwrap();
+        assert_eq!(stuck.technique, Technique::Pointing);
+        assert_eq!(stuck.placement, None);
+        assert!(!stuck.eliminations.is_empty());
+
+        // Techniques can be left out of hints.
+        assert_eq!(singles.hint(board.grid()), None);
+        let locked = LogicalSolver::with_techniques(&[Technique::BoxLineReduction]);
+        assert_eq!(
+            locked.hint(board.grid()).map(|hint| hint.technique),
+            Some(Technique::BoxLineReduction)
+        );
+
+        assert_eq!(hint(solve_any(PUZZLE).unwrap()), None);
+        let mut invalid = PUZZLE;
+        invalid[0][2] = 5;
+        assert_eq!(hint(invalid), None);
+    }
+
+    #[test]
+    fn check_hint_levels() {
+        let next = hint(PUZZLE).unwrap();
+        let ((row, col), digit) = next.placement.unwrap();
+
+        let region = hint_at_level(PUZZLE, HintLevel::Region).unwrap();
+        assert_eq!(region.units, next.units);
+        assert_eq!(region.technique, None);
+        assert!(region.targets.is_empty());
+        assert_eq!(region.placement, None);
+
+        let technique = next.reveal(HintLevel::Technique);
+        assert_eq!(technique.technique, Some(next.technique));
+        assert!(technique.targets.is_empty());
+
+        let cell = next.reveal(HintLevel::Cell);
+        assert_eq!(cell.targets, [(row, col)]);
+        assert_eq!(cell.placement, None);
+
+        let answer = next.reveal(HintLevel::Digit);
+        assert_eq!(answer.placement, Some(((row, col), digit)));
+        assert_eq!(answer.level, HintLevel::Digit);
+        assert!(HintLevel::Region < HintLevel::Digit);
+
+        // Moves without units point at the squares of the cells they change.
+        let chain = Hint {
+            technique: Technique::XChain,
+            units: Vec::new(),
+            cells: vec![(0, 0), (0, 4)],
+            eliminations: vec![((1, 1), 3), ((1, 1), 5), ((4, 4), 3)],
+            placement: None,
+        };
+        let region = chain.reveal(HintLevel::Region);
+        assert_eq!(region.units, [Unit::Square(0), Unit::Square(4)]);
+        assert_eq!(chain.reveal(HintLevel::Cell).targets, [(1, 1), (4, 4)]);
+        assert!(chain.reveal(HintLevel::Cell).eliminations.is_empty());
+        assert_eq!(
+            chain.reveal(HintLevel::Digit).eliminations,
+            chain.eliminations
+        );
+
+        assert_eq!(
+            hint_at_level(solve_any(PUZZLE).unwrap(), HintLevel::Digit),
+            None
+        );
+    }
+
+    #[test]
+    fn check_solve() {
+        let solver = LogicalSolver::new();
+
+        let steps = solver.solve(PUZZLE).unwrap();
+        assert_eq!(steps.len(), 51);
+        assert!(steps.iter().all(|step| step.placement.is_some()));
+        let board = replay(PUZZLE, &steps);
+        assert!(board.is_solved());
+        assert_eq!(Some(board.grid()), solve_any(PUZZLE));
+
+        // Needs more than these techniques.
+        let steps = solver.solve(HARD).unwrap();
+        let board = replay(HARD, &steps);
+        assert!(!board.is_solved());
+        assert_eq!(solver.next_step(&board), None);
+
+        let mut invalid = PUZZLE;
+        invalid[0][2] = 5;
+        assert_eq!(solver.solve(invalid), None);
+    }
+
+    /// Fills in the first blank from the solution.
+    #[derive(Debug)]
+    struct Oracle(Solution);
+
+    impl CustomTechnique for Oracle {
+        fn find(&self, board: &Board) -> Option<Step> {
+            let (row, col) = all_cells().find(|&(row, col)| board.grid()[row][col] == 0)?;
+            Some(Step {
+                technique: Technique::Custom("oracle"),
+                units: Vec::new(),
+                cells: vec![(row, col)],
+                links: Vec::new(),
+                eliminations: Vec::new(),
+                placement: Some(((row, col), self.0[row][col])),
+            })
+        }
+    }
+
+    /// Always finds a step that changes nothing.
+    #[derive(Debug)]
+    struct Idle;
+
+    impl CustomTechnique for Idle {
+        fn find(&self, _: &Board) -> Option<Step> {
+            Some(Step {
+                technique: Technique::Custom("idle"),
+                units: Vec::new(),
+                cells: Vec::new(),
+                links: Vec::new(),
+                eliminations: vec![((0, 0), 1)],
+                placement: None,
+            })
+        }
+    }
+
+    #[test]
+    fn check_custom_technique() {
+        let solution = solve_any(HARD).unwrap();
+        let solver = LogicalSolver::new().custom_technique(Oracle(solution));
+        assert_eq!(
+            solver.try_solve(HARD),
+            Some(LogicalOutcome::Solved(solution))
+        );
+
+        // Only used once the built-in techniques are stuck.
+        let steps = solver.solve(HARD).unwrap();
+        let first = steps
+            .iter()
+            .position(|step| step.technique == Technique::Custom("oracle"))
+            .unwrap();
+        let board = replay(HARD, &steps[..first]);
+        assert_eq!(LogicalSolver::new().next_step(&board), None);
+        assert_eq!(steps[first].technique.name(), "oracle");
+        assert_eq!(steps[first].technique.category(), TechniqueCategory::Custom);
+
+        // Steps that make no progress end the solve.
+        let idle = LogicalSolver::new().custom_technique(Idle);
+        assert_eq!(idle.solve(HARD), LogicalSolver::new().solve(HARD));
+        assert!(matches!(
+            idle.try_solve(HARD),
+            Some(LogicalOutcome::Stuck(_))
+        ));
+    }
+
+    #[test]
+    fn check_with_techniques() {
+        let singles =
+            LogicalSolver::with_techniques(&[Technique::HiddenSingle, Technique::NakedSingle]);
+        assert_eq!(
+            singles.try_solve(PUZZLE),
+            solve_any(PUZZLE).map(LogicalOutcome::Solved)
+        );
+
+        let Some(LogicalOutcome::Stuck(board)) = singles.try_solve(MEDIUM) else {
+            panic!("singles should not solve MEDIUM");
+        };
+        assert!(!board.is_solved());
+        assert_ne!(board.candidates((0, 1)), 0);
+        assert_eq!(singles.next_step(&board), None);
+
+        let locked = LogicalSolver::with_techniques(&[
+            Technique::HiddenSingle,
+            Technique::NakedSingle,
+            Technique::Pointing,
+        ]);
+        assert_eq!(
+            locked.try_solve(MEDIUM),
+            solve_any(MEDIUM).map(LogicalOutcome::Solved)
+        );
+
+        let mut invalid = PUZZLE;
+        invalid[0][2] = 5;
+        assert_eq!(singles.try_solve(invalid), None);
+    }
+
+    #[test]
+    fn check_technique_options() {
+        let singles =
+            LogicalSolver::with_techniques(&[Technique::HiddenSingle, Technique::NakedSingle]);
+        let locked = singles.clone().technique(Technique::Pointing, true);
+        assert!(locked.is_enabled(Technique::Pointing));
+        assert_eq!(
+            locked.try_solve(MEDIUM),
+            solve_any(MEDIUM).map(LogicalOutcome::Solved)
+        );
+        let unlocked = locked.technique(Technique::Pointing, false);
+        assert!(!unlocked.is_enabled(Technique::Pointing));
+        assert_eq!(unlocked.try_solve(MEDIUM), singles.try_solve(MEDIUM));
+
+        let solver = LogicalSolver::new();
+        assert_eq!(solver.limit_of(Technique::XChain), 8);
+        assert_eq!(solver.limit_of(Technique::AlsXz), 4);
+        let solver = solver.max_chain_length(5).limit(Technique::XYChain, 6);
+        assert_eq!(solver.limit_of(Technique::XChain), 5);
+        assert_eq!(solver.limit_of(Technique::XYChain), 6);
+        assert_eq!(
+            solver.limit(Technique::AlsXz, 2).limit_of(Technique::AlsXz),
+            2
+        );
+    }
+}
@@ -0,0 +1,112 @@
+use crate::sudoku::{DigitSet, GridPos, Unit};
+
+use super::{Board, Step, Technique, all_cells, combinations, digits, sees};
+
+/// N cells of a unit with N + 1 candidates between them.
+struct AlmostLockedSet {
+    unit: Unit,
+    cells: Vec<GridPos>,
+    candidates: DigitSet,
+}
+
+impl AlmostLockedSet {
+    /// Gets the cells of the set that have a digit as a candidate.
+    fn cells_with(&self, board: &Board, digit: u8) -> Vec<GridPos> {
+        self.cells
+            .iter()
+            .copied()
+            .filter(|&pos| board.candidates(pos) & (1 << (digit - 1)) != 0)
+            .collect()
+    }
+}
+
+/// Finds two almost locked sets that share a restricted common candidate x, i.e. every cell with x
+/// in one sees every cell with x in the other. x goes in at most one of them, so the other is
+/// locked, and holds all its other candidates. If both sets share another candidate z, one of them
+/// holds z, so z can be removed from every other cell that sees all their cells with z.
+pub(super) fn als_xz(board: &Board, max_size: usize) -> Option<Step> {
+    let sets = almost_locked_sets(board, max_size);
+
+    sets.iter().enumerate().find_map(|(index, first)| {
+        sets[index + 1..].iter().find_map(|second| {
+            if first.cells.iter().any(|pos| second.cells.contains(pos)) {
+                return None;
+            }
+
+            let common = first.candidates & second.candidates;
+            digits(common).find_map(|restricted| {
+                let (first_cells, second_cells) = (
+                    first.cells_with(board, restricted),
+                    second.cells_with(board, restricted),
+                );
+                if !first_cells
+                    .iter()
+                    .all(|&pos| second_cells.iter().all(|&other| sees(pos, other)))
+                {
+                    return None;
+                }
+
+                let eliminations = all_cells()
+                    .filter(|pos| !first.cells.contains(pos) && !second.cells.contains(pos))
+                    .flat_map(|pos| {
+                        digits(common & board.candidates(pos) & !(1 << (restricted - 1)))
+                            .filter(move |&digit| {
+                                first
+                                    .cells_with(board, digit)
+                                    .into_iter()
+                                    .chain(second.cells_with(board, digit))
+                                    .all(|other| sees(pos, other))
+                            })
+                            .map(move |digit| (pos, digit))
+                    })
+                    .collect::<Vec<_>>();
+                if eliminations.is_empty() {
+                    return None;
+                }
+
+                let mut cells = [first.cells.as_slice(), second.cells.as_slice()].concat();
+                cells.sort();
+                Some(Step {
+                    technique: Technique::AlsXz,
+                    units: vec![first.unit, second.unit],
+                    cells,
+                    links: Vec::new(),
+                    eliminations,
+                    placement: None,
+                })
+            })
+        })
+    })
+}
+
+/// Finds the almost locked sets of up to max_size cells, smallest first within each unit. A set
+/// that lies in several units is only listed for the first.
+fn almost_locked_sets(board: &Board, max_size: usize) -> Vec<AlmostLockedSet> {
+    let mut sets = Vec::<AlmostLockedSet>::new();
+    for unit in Unit::all() {
+        let blanks = unit
+            .positions()
+            .into_iter()
+            .filter(|&pos| board.candidates(pos) != 0)
+            .collect::<Vec<_>>();
+
+        for size in 1..=max_size {
+            for cells in combinations(&blanks, size) {
+                let candidates = cells
+                    .iter()
+                    .fold(0, |set, &pos| set | board.candidates(pos));
+                if candidates.count_ones() as usize == size + 1
+                    && !sets.iter().any(|set| set.cells == cells)
+                {
+                    sets.push(AlmostLockedSet {
+                        unit,
+                        cells,
+                        candidates,
+                    });
+                }
+            }
+        }
+    }
+
+    sets
+}
@@ -0,0 +1,207 @@
+use crate::sudoku::GridPos;
+
+use super::{Board, Link, Step, Technique, all_cells, coloring::conjugate_pairs, digits, sees};
+
+/// Finds a chain of cells for one digit, alternating between strong links (conjugate pairs) and
+/// weak links (cells that see each other), that starts and ends with a strong link. One of the
+/// two ends holds the digit, so it can be removed from every other cell that sees both ends.
+///
+/// Shorter chains are found first, up to max_length cells.
+pub(super) fn x_chain(board: &Board, max_length: usize) -> Option<Step> {
+    (4..=max_length).step_by(2).find_map(|length| {
+        (1..=9).find_map(|digit| {
+            let pairs = conjugate_pairs(board, digit);
+            let mut search = XChain {
+                board,
+                digit,
+                pairs: &pairs,
+                length,
+                path: Vec::new(),
+            };
+
+            all_cells()
+                .filter(|&pos| pairs.iter().any(|&(first, _)| first == pos))
+                .find_map(|start| {
+                    search.path.push(start);
+                    let step = search.extend();
+                    search.path.pop();
+                    step
+                })
+        })
+    })
+}
+
+/// Depth-first search for an X-Chain of a given length.
+struct XChain<'a> {
+    board: &'a Board,
+    digit: u8,
+    pairs: &'a [(GridPos, GridPos)],
+    length: usize,
+
+    /// The cells of the chain so far.
+    path: Vec<GridPos>,
+}
+
+impl XChain<'_> {
+    fn extend(&mut self) -> Option<Step> {
+        let last = *self.path.last()?;
+        let bit = 1 << (self.digit - 1);
+
+        if self.path.len() == self.length {
+            let first = self.path[0];
+            let eliminations = all_cells()
+                .filter(|pos| self.board.candidates(*pos) & bit != 0 && !self.path.contains(pos))
+                .filter(|&pos| sees(pos, first) && sees(pos, last))
+                .map(|pos| (pos, self.digit))
+                .collect::<Vec<_>>();
+
+            return (!eliminations.is_empty()).then(|| Step {
+                technique: Technique::XChain,
+                units: Vec::new(),
+                cells: self.path.clone(),
+                links: self
+                    .path
+                    .windows(2)
+                    .enumerate()
+                    .map(|(index, pair)| Link {
+                        from: (pair[0], self.digit),
+                        to: (pair[1], self.digit),
+                        strong: index % 2 == 0,
+                    })
+                    .collect(),
+                eliminations,
+                placement: None,
+            });
+        }
+
+        // Links alternate, starting with a strong one. A weak link only leads somewhere if the
+        // next cell has a strong link of its own.
+        let mut next = if self.path.len() % 2 == 1 {
+            self.pairs
+                .iter()
+                .filter(|&&(first, _)| first == last)
+                .map(|&(_, second)| second)
+                .collect::<Vec<_>>()
+        } else {
+            all_cells()
+                .filter(|&pos| self.board.candidates(pos) & bit != 0 && sees(pos, last))
+                .filter(|&pos| self.pairs.iter().any(|&(first, _)| first == pos))
+                .collect()
+        };
+
+        next.retain(|pos| !self.path.contains(pos));
+        next.into_iter().find_map(|pos| {
+            self.path.push(pos);
+            let step = self.extend();
+            self.path.pop();
+            step
+        })
+    }
+}
+
+/// Finds a chain of cells with two candidates each, where each cell sees the next, and shares a
+/// candidate with it: if the first cell does not hold x, the chain forces the last cell to hold x.
+/// One of the two ends holds x, so it can be removed from every other cell that sees both ends.
+///
+/// Shorter chains are found first, up to max_length cells.
+pub(super) fn xy_chain(board: &Board, max_length: usize) -> Option<Step> {
+    let cells = all_cells()
+        .filter(|&pos| board.candidates(pos).count_ones() == 2)
+        .collect::<Vec<_>>();
+
+    (3..=max_length).find_map(|length| {
+        let mut search = XYChain {
+            board,
+            cells: &cells,
+            length,
+            path: Vec::new(),
+        };
+
+        cells.iter().find_map(|&start| {
+            let candidates = board.candidates(start);
+            digits(candidates).find_map(|digit| {
+                let exit = (candidates & !(1 << (digit - 1))).trailing_zeros() as u8 + 1;
+                search.path.push((start, digit, exit));
+                let step = search.extend();
+                search.path.pop();
+                step
+            })
+        })
+    })
+}
+
+/// Depth-first search for an XY-Chain of a given length.
+struct XYChain<'a> {
+    board: &'a Board,
+
+    /// The cells with two candidates.
+    cells: &'a [GridPos],
+    length: usize,
+
+    /// The cells of the chain so far, with the digit that is false and the digit that is forced
+    /// to be true in each.
+    path: Vec<(GridPos, u8, u8)>,
+}
+
+impl XYChain<'_> {
+    fn extend(&mut self) -> Option<Step> {
+        let &(last, _, exit) = self.path.last()?;
+
+        if self.path.len() == self.length {
+            let (first, digit, _) = self.path[0];
+            if exit != digit {
+                return None;
+            }
+
+            let cells = self.path.iter().map(|&(pos, _, _)| pos).collect::<Vec<_>>();
+            let eliminations = all_cells()
+                .filter(|pos| self.board.candidates(*pos) & (1 << (digit - 1)) != 0)
+                .filter(|pos| !cells.contains(pos))
+                .filter(|&pos| sees(pos, first) && sees(pos, last))
+                .map(|pos| (pos, digit))
+                .collect::<Vec<_>>();
+
+            let mut links = Vec::new();
+            for (index, &(pos, entry, exit)) in self.path.iter().enumerate() {
+                if index > 0 {
+                    links.push(Link {
+                        from: (self.path[index - 1].0, entry),
+                        to: (pos, entry),
+                        strong: false,
+                    });
+                }
+                links.push(Link {
+                    from: (pos, entry),
+                    to: (pos, exit),
+                    strong: true,
+                });
+            }
+
+            return (!eliminations.is_empty()).then_some(Step {
+                technique: Technique::XYChain,
+                units: Vec::new(),
+                cells,
+                links,
+                eliminations,
+                placement: None,
+            });
+        }
+
+        let mut next = self
+            .cells
+            .iter()
+            .copied()
+            .filter(|&pos| self.board.candidates(pos) & (1 << (exit - 1)) != 0 && sees(pos, last))
+            .collect::<Vec<_>>();
+
+        next.retain(|pos| !self.path.iter().any(|(other, _, _)| other == pos));
+        next.into_iter().find_map(|pos| {
+            let forced = self.board.candidates(pos) & !(1 << (exit - 1));
+            self.path
+                .push((pos, exit, forced.trailing_zeros() as u8 + 1));
+            let step = self.extend();
+            self.path.pop();
+            step
+        })
+    }
+}
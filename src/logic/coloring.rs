@@ -0,0 +1,121 @@
+use std::collections::VecDeque;
+
+use crate::sudoku::{GridPos, Unit};
+
+use super::{Board, Link, Step, Technique, all_cells, sees};
+
+/// Colors the cells of a digit in two colors along conjugate pairs (the only two cells of a unit
+/// where the digit fits), so that the digit goes in every cell of one color and none of the other.
+///
+/// If two cells of the same color see each other, that color cannot hold the digit, so it can be
+/// removed from all of them. Otherwise, the digit can be removed from any other cell that sees
+/// both colors.
+pub(super) fn simple_coloring(board: &Board) -> Option<Step> {
+    (1..=9).find_map(|digit| {
+        let bit = 1 << (digit - 1);
+        let pairs = conjugate_pairs(board, digit);
+
+        let mut colors = [[None; 9]; 9];
+        all_cells()
+            .filter(|&pos| pairs.iter().any(|&(first, _)| first == pos))
+            .find_map(|start| {
+                if colors[start.0][start.1].is_some() {
+                    return None;
+                }
+
+                let (cells, links) = color(&pairs, start, &mut colors, digit);
+                let color_of = |pos: GridPos| colors[pos.0][pos.1];
+
+                let wrap = [false, true].into_iter().find(|&color| {
+                    cells.iter().any(|&first| {
+                        color_of(first) == Some(color)
+                            && cells.iter().any(|&second| {
+                                color_of(second) == Some(color) && sees(first, second)
+                            })
+                    })
+                });
+
+                let eliminations = match wrap {
+                    Some(color) => cells
+                        .iter()
+                        .filter(|&&pos| color_of(pos) == Some(color))
+                        .map(|&pos| (pos, digit))
+                        .collect::<Vec<_>>(),
+                    None => all_cells()
+                        .filter(|pos| board.candidates(*pos) & bit != 0 && !cells.contains(pos))
+                        .filter(|&pos| {
+                            [false, true].into_iter().all(|color| {
+                                cells.iter().any(|&other| {
+                                    color_of(other) == Some(color) && sees(pos, other)
+                                })
+                            })
+                        })
+                        .map(|pos| (pos, digit))
+                        .collect(),
+                };
+
+                (!eliminations.is_empty()).then(|| Step {
+                    technique: Technique::SimpleColoring,
+                    units: Vec::new(),
+                    cells,
+                    links,
+                    eliminations,
+                    placement: None,
+                })
+            })
+    })
+}
+
+/// Finds the pairs of cells that are the only two cells of a unit where a digit fits, both ways
+/// round.
+pub(super) fn conjugate_pairs(board: &Board, digit: u8) -> Vec<(GridPos, GridPos)> {
+    let mut pairs = Vec::new();
+    for unit in Unit::all() {
+        let mut cells = unit
+            .positions()
+            .into_iter()
+            .filter(|&pos| board.candidates(pos) & (1 << (digit - 1)) != 0);
+
+        if let (Some(first), Some(second), None) = (cells.next(), cells.next(), cells.next())
+            && !pairs.contains(&(first, second))
+        {
+            pairs.push((first, second));
+            pairs.push((second, first));
+        }
+    }
+
+    pairs
+}
+
+/// Colors the cells connected to start by conjugate pairs, breadth first. Returns the cells in
+/// reading order, and the links followed.
+fn color(
+    pairs: &[(GridPos, GridPos)],
+    start: GridPos,
+    colors: &mut [[Option<bool>; 9]; 9],
+    digit: u8,
+) -> (Vec<GridPos>, Vec<Link>) {
+    let mut cells = vec![start];
+    let mut links = Vec::new();
+
+    colors[start.0][start.1] = Some(false);
+    let mut queue = VecDeque::from([start]);
+    while let Some(pos) = queue.pop_front() {
+        let color = colors[pos.0][pos.1].map(|color| !color);
+        for &(_, next) in pairs.iter().filter(|&&(first, _)| first == pos) {
+            if colors[next.0][next.1].is_none() {
+                colors[next.0][next.1] = color;
+                cells.push(next);
+                links.push(Link {
+                    from: (pos, digit),
+                    to: (next, digit),
+                    strong: true,
+                });
+                queue.push_back(next);
+            }
+        }
+    }
+
+    cells.sort();
+    (cells, links)
+}
@@ -0,0 +1,143 @@
+use crate::sudoku::{GridPos, Unit, cell};
+
+use super::{Board, Step, Technique, combinations};
+
+/// Finds size rows where a digit fits only in the same size cols between them (or the other way
+/// around), so that the digit must go in those cols within those rows, and can be removed from the
+/// rest of the cols.
+pub(super) fn fish(board: &Board, technique: Technique, size: usize) -> Option<Step> {
+    (1..=9).find_map(|digit| {
+        [false, true]
+            .into_iter()
+            .find_map(|by_col| fish_for(board, technique, size, digit, by_col, false))
+    })
+}
+
+/// Finds a fish with fins: extra cells of the base lines outside the cover lines, all in one
+/// square. Either a fin holds the digit, or the fish without its fins does, so the digit can only
+/// be removed from the cells of the cover lines within the square of the fins.
+///
+/// Includes sashimi fish, where a base line has a single cell left once its fins are taken out.
+pub(super) fn finned_fish(board: &Board, technique: Technique, size: usize) -> Option<Step> {
+    (1..=9).find_map(|digit| {
+        [false, true]
+            .into_iter()
+            .find_map(|by_col| fish_for(board, technique, size, digit, by_col, true))
+    })
+}
+
+/// Finds a fish with rows as the base lines, or cols if by_col is set, with fins if finned is set.
+fn fish_for(
+    board: &Board,
+    technique: Technique,
+    size: usize,
+    digit: u8,
+    by_col: bool,
+    finned: bool,
+) -> Option<Step> {
+    let pos = |line: usize, index: usize| if by_col { (index, line) } else { (line, index) };
+    let unit = |line: usize| {
+        if by_col {
+            Unit::Col(line)
+        } else {
+            Unit::Row(line)
+        }
+    };
+    let cross = |line: usize| {
+        if by_col {
+            Unit::Row(line)
+        } else {
+            Unit::Col(line)
+        }
+    };
+
+    // For each line, the cross lines where the digit fits, with bit i set for cross line i.
+    let masks: [u16; 9] = std::array::from_fn(|line| {
+        (0..9)
+            .filter(|&index| board.candidates(pos(line, index)) & (1 << (digit - 1)) != 0)
+            .fold(0, |mask, index| mask | 1 << index)
+    });
+
+    // A fin can add up to three cells to a base line.
+    let counts = if finned { 1..=size + 3 } else { 2..=size };
+    let lines = (0..9)
+        .filter(|&line| counts.contains(&(masks[line].count_ones() as usize)))
+        .collect::<Vec<_>>();
+
+    combinations(&lines, size).into_iter().find_map(|base| {
+        let union = base.iter().fold(0, |mask, &line| mask | masks[line]);
+        let indexes = (0..9)
+            .filter(|index| union & (1 << index) != 0)
+            .collect::<Vec<_>>();
+
+        let covers = if finned {
+            combinations(&indexes, size)
+        } else if indexes.len() == size {
+            vec![indexes]
+        } else {
+            Vec::new()
+        };
+
+        covers.into_iter().find_map(|cover| {
+            let cover_mask = cover.iter().fold(0, |mask, index| mask | 1 << index);
+            if base.iter().any(|&line| masks[line] & cover_mask == 0) {
+                return None;
+            }
+
+            let fins = base
+                .iter()
+                .flat_map(|&line| {
+                    (0..9)
+                        .filter(move |index| masks[line] & !cover_mask & (1 << index) != 0)
+                        .map(move |index| pos(line, index))
+                })
+                .collect::<Vec<GridPos>>();
+            let fin_square = fins.first().map(|&fin| cell(fin).square);
+            if finned != fin_square.is_some()
+                || fins.iter().any(|&fin| Some(cell(fin).square) != fin_square)
+            {
+                return None;
+            }
+
+            let mut eliminations = Vec::new();
+            for line in (0..9).filter(|line| !base.contains(line)) {
+                for &index in cover
+                    .iter()
+                    .filter(|&&index| masks[line] & (1 << index) != 0)
+                {
+                    let target = pos(line, index);
+                    if fin_square.is_none_or(|square| cell(target).square == square) {
+                        eliminations.push((target, digit));
+                    }
+                }
+            }
+            if eliminations.is_empty() {
+                return None;
+            }
+            eliminations.sort();
+
+            let mut cells = base
+                .iter()
+                .flat_map(|&line| {
+                    (0..9)
+                        .filter(move |index| masks[line] & (1 << index) != 0)
+                        .map(move |index| pos(line, index))
+                })
+                .collect::<Vec<GridPos>>();
+            cells.sort();
+
+            Some(Step {
+                technique,
+                units: base
+                    .iter()
+                    .map(|&line| unit(line))
+                    .chain(cover.iter().map(|&index| cross(index)))
+                    .collect(),
+                cells,
+                links: Vec::new(),
+                eliminations,
+                placement: None,
+            })
+        })
+    })
+}
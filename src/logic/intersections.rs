@@ -0,0 +1,83 @@
+use crate::sudoku::{GridPos, Unit, cell};
+
+use super::{Board, Step, Technique};
+
+/// Finds a digit whose cells in a square all lie in one row or col, so that it can be removed from
+/// the rest of that line.
+pub(super) fn pointing(board: &Board) -> Option<Step> {
+    (0..9).map(Unit::Square).find_map(|square| {
+        (1..=9).find_map(|digit| {
+            let cells = cells_with(board, square, digit);
+            let &(row, col) = cells.first()?;
+
+            [Unit::Row(row), Unit::Col(col)]
+                .into_iter()
+                .filter(|&line| cells.iter().all(|&pos| in_unit(pos, line)))
+                .find_map(|line| locked(board, Technique::Pointing, [square, line], &cells, digit))
+        })
+    })
+}
+
+/// Finds a digit whose cells in a row or col all lie in one square, so that it can be removed from
+/// the rest of that square.
+pub(super) fn box_line_reduction(board: &Board) -> Option<Step> {
+    Unit::all()
+        .filter(|unit| !matches!(unit, Unit::Square(_)))
+        .find_map(|line| {
+            (1..=9).find_map(|digit| {
+                let cells = cells_with(board, line, digit);
+                let square = Unit::Square(cell(*cells.first()?).square);
+                if !cells.iter().all(|&pos| in_unit(pos, square)) {
+                    return None;
+                }
+
+                locked(
+                    board,
+                    Technique::BoxLineReduction,
+                    [line, square],
+                    &cells,
+                    digit,
+                )
+            })
+        })
+}
+
+/// Gets the cells of a unit that have a digit as a candidate.
+fn cells_with(board: &Board, unit: Unit, digit: u8) -> Vec<GridPos> {
+    unit.positions()
+        .into_iter()
+        .filter(|&pos| board.candidates(pos) & (1 << (digit - 1)) != 0)
+        .collect()
+}
+
+fn in_unit((row, col): GridPos, unit: Unit) -> bool {
+    match unit {
+        Unit::Row(index) => row == index,
+        Unit::Col(index) => col == index,
+        Unit::Square(index) => cell((row, col)).square == index,
+    }
+}
+
+/// Removes a digit from the second unit, except from the cells of the first unit that lock it in.
+fn locked(
+    board: &Board,
+    technique: Technique,
+    units: [Unit; 2],
+    cells: &[GridPos],
+    digit: u8,
+) -> Option<Step> {
+    let eliminations = cells_with(board, units[1], digit)
+        .into_iter()
+        .filter(|&pos| !in_unit(pos, units[0]))
+        .map(|pos| (pos, digit))
+        .collect::<Vec<_>>();
+
+    (!eliminations.is_empty()).then(|| Step {
+        technique,
+        units: units.to_vec(),
+        cells: cells.to_vec(),
+        links: Vec::new(),
+        eliminations,
+        placement: None,
+    })
+}
@@ -0,0 +1,162 @@
+use crate::sudoku::{DigitSet, GridPos, Unit, cell};
+
+use super::{Board, Step, Technique, all_cells, combinations, digits, sees};
+
+/// Finds a unique rectangle: four blanks at the corners of a rectangle spanning two rows, two cols
+/// and two squares, which all have the same two candidates ab. If they could only hold a and b, the
+/// two digits could be swapped to get a second solution, so assuming that the solution is unique,
+/// at least one of them holds something else:
+///
+/// 1. If three corners have only ab, a and b can be removed from the fourth.
+/// 2. If two corners on one line have only ab, and the other two have only abc, one of those holds
+///    c, so c can be removed from every cell that sees both.
+/// 3. If two corners on one line have only ab, the other two together act as a single cell with
+///    their extra candidates, which may form a naked subset with other cells of a shared unit.
+/// 4. If two corners on one line have only ab, and a fits only in the other two within a shared
+///    unit, one of them holds a, so neither can hold b.
+pub(super) fn unique_rectangle(board: &Board) -> Option<Step> {
+    rectangles().find_map(|corners| {
+        let common = corners
+            .iter()
+            .fold(0x1ff, |set, &pos| set & board.candidates(pos));
+
+        combinations(&digits(common).collect::<Vec<_>>(), 2)
+            .into_iter()
+            .find_map(|pair| {
+                let pair = (1 << (pair[0] - 1)) | (1 << (pair[1] - 1));
+                let (floor, roof): (Vec<GridPos>, Vec<GridPos>) = corners
+                    .iter()
+                    .partition(|&&pos| board.candidates(pos) == pair);
+
+                let eliminations = match floor.len() {
+                    3 => digits(pair).map(|digit| (roof[0], digit)).collect(),
+                    2 if shares_line(floor[0], floor[1]) => {
+                        let roof = [roof[0], roof[1]];
+                        type_2(board, pair, roof)
+                            .or_else(|| type_3(board, pair, roof))
+                            .or_else(|| type_4(board, pair, roof))
+                            .unwrap_or_default()
+                    }
+                    _ => Vec::new(),
+                };
+
+                (!eliminations.is_empty()).then(|| Step {
+                    technique: Technique::UniqueRectangle,
+                    units: Vec::new(),
+                    cells: corners.to_vec(),
+                    links: Vec::new(),
+                    eliminations,
+                    placement: None,
+                })
+            })
+    })
+}
+
+/// Both roof corners have the same single extra candidate.
+fn type_2(board: &Board, pair: DigitSet, roof: [GridPos; 2]) -> Option<Vec<(GridPos, u8)>> {
+    let extra = board.candidates(roof[0]) & !pair;
+    if extra.count_ones() != 1 || board.candidates(roof[1]) & !pair != extra {
+        return None;
+    }
+
+    let digit = extra.trailing_zeros() as u8 + 1;
+    let eliminations = all_cells()
+        .filter(|&pos| board.candidates(pos) & extra != 0)
+        .filter(|&pos| sees(pos, roof[0]) && sees(pos, roof[1]))
+        .map(|pos| (pos, digit))
+        .collect::<Vec<_>>();
+
+    (!eliminations.is_empty()).then_some(eliminations)
+}
+
+/// The extra candidates of the roof corners form a naked subset with other cells of a shared unit.
+fn type_3(board: &Board, pair: DigitSet, roof: [GridPos; 2]) -> Option<Vec<(GridPos, u8)>> {
+    let extra = (board.candidates(roof[0]) | board.candidates(roof[1])) & !pair;
+
+    shared_units(roof).into_iter().find_map(|unit| {
+        let others = unit
+            .positions()
+            .into_iter()
+            .filter(|&pos| !roof.contains(&pos) && board.candidates(pos) != 0)
+            .collect::<Vec<_>>();
+
+        (1..=3).find_map(|size| {
+            combinations(&others, size).into_iter().find_map(|cells| {
+                let subset = cells
+                    .iter()
+                    .fold(extra, |set, &pos| set | board.candidates(pos));
+                if subset.count_ones() as usize != size + 1 {
+                    return None;
+                }
+
+                let eliminations = others
+                    .iter()
+                    .filter(|pos| !cells.contains(pos))
+                    .flat_map(|&pos| {
+                        digits(board.candidates(pos) & subset).map(move |digit| (pos, digit))
+                    })
+                    .collect::<Vec<_>>();
+
+                (!eliminations.is_empty()).then_some(eliminations)
+            })
+        })
+    })
+}
+
+/// One digit of the pair fits only in the roof corners within a shared unit, so the other cannot
+/// go in either.
+fn type_4(board: &Board, pair: DigitSet, roof: [GridPos; 2]) -> Option<Vec<(GridPos, u8)>> {
+    shared_units(roof).into_iter().find_map(|unit| {
+        let mut pair_digits = digits(pair);
+        let (first, second) = (pair_digits.next()?, pair_digits.next()?);
+
+        [(first, second), (second, first)]
+            .into_iter()
+            .find(|&(locked, _)| {
+                unit.positions()
+                    .into_iter()
+                    .filter(|&pos| board.candidates(pos) & (1 << (locked - 1)) != 0)
+                    .all(|pos| roof.contains(&pos))
+            })
+            .map(|(_, removed)| roof.iter().map(|&pos| (pos, removed)).collect())
+    })
+}
+
+/// Iterates over the rectangles whose corners lie in exactly two squares, corners in reading
+/// order.
+fn rectangles() -> impl Iterator<Item = [GridPos; 4]> {
+    combinations(&(0..9).collect::<Vec<_>>(), 2)
+        .into_iter()
+        .flat_map(|rows| {
+            combinations(&(0..9).collect::<Vec<_>>(), 2)
+                .into_iter()
+                .map(move |cols| {
+                    [
+                        (rows[0], cols[0]),
+                        (rows[0], cols[1]),
+                        (rows[1], cols[0]),
+                        (rows[1], cols[1]),
+                    ]
+                })
+        })
+        .filter(|corners| {
+            (corners[0].0 / 3 == corners[2].0 / 3) != (corners[0].1 / 3 == corners[1].1 / 3)
+        })
+}
+
+fn shares_line(first: GridPos, second: GridPos) -> bool {
+    first.0 == second.0 || first.1 == second.1
+}
+
+/// Gets the units that hold both cells.
+fn shared_units(cells: [GridPos; 2]) -> Vec<Unit> {
+    let [(row, col), other] = cells;
+    [
+        Unit::Row(row),
+        Unit::Col(col),
+        Unit::Square(cell((row, col)).square),
+    ]
+    .into_iter()
+    .filter(|unit| unit.positions().contains(&other))
+    .collect()
+}
@@ -0,0 +1,36 @@
+use crate::sudoku::{Unit, hidden_single as find_hidden_single};
+
+use super::{Board, Step, Technique, all_cells};
+
+/// Finds a digit that fits in only one cell of a unit, looking at squares first, then lines.
+pub(super) fn hidden_single(board: &Board) -> Option<Step> {
+    let squares = Unit::all().filter(|unit| matches!(unit, Unit::Square(_)));
+    let lines = Unit::all().filter(|unit| !matches!(unit, Unit::Square(_)));
+
+    squares.chain(lines).find_map(|unit| {
+        let (pos, digit) = find_hidden_single(&board.candidates, unit)?;
+        Some(Step {
+            technique: Technique::HiddenSingle,
+            units: vec![unit],
+            cells: vec![pos],
+            links: Vec::new(),
+            eliminations: Vec::new(),
+            placement: Some((pos, digit)),
+        })
+    })
+}
+
+/// Finds a blank with only one candidate left.
+pub(super) fn naked_single(board: &Board) -> Option<Step> {
+    all_cells().find_map(|pos| {
+        let candidates = board.candidates(pos);
+        (candidates.count_ones() == 1).then(|| Step {
+            technique: Technique::NakedSingle,
+            units: Vec::new(),
+            cells: vec![pos],
+            links: Vec::new(),
+            eliminations: Vec::new(),
+            placement: Some((pos, candidates.trailing_zeros() as u8 + 1)),
+        })
+    })
+}
@@ -0,0 +1,88 @@
+use crate::sudoku::Unit;
+
+use super::{Board, Step, Technique, combinations, digits};
+
+/// Finds size cells of a unit that have only size candidates between them, which must go in those
+/// cells, so can be removed from the rest of the unit.
+pub(super) fn naked_subset(board: &Board, technique: Technique, size: usize) -> Option<Step> {
+    Unit::all().find_map(|unit| {
+        let blanks = unit
+            .positions()
+            .into_iter()
+            .filter(|&pos| (2..=size as u32).contains(&board.candidates(pos).count_ones()))
+            .collect::<Vec<_>>();
+
+        combinations(&blanks, size).into_iter().find_map(|cells| {
+            let subset = cells
+                .iter()
+                .fold(0, |set, &pos| set | board.candidates(pos));
+            if subset.count_ones() as usize != size {
+                return None;
+            }
+
+            let eliminations = unit
+                .positions()
+                .into_iter()
+                .filter(|pos| !cells.contains(pos))
+                .flat_map(|pos| {
+                    digits(board.candidates(pos) & subset).map(move |digit| (pos, digit))
+                })
+                .collect::<Vec<_>>();
+
+            (!eliminations.is_empty()).then(|| Step {
+                technique,
+                units: vec![unit],
+                cells,
+                links: Vec::new(),
+                eliminations,
+                placement: None,
+            })
+        })
+    })
+}
+
+/// Finds size digits that fit in only size cells of a unit between them, which must hold those
+/// digits, so cannot hold any other.
+pub(super) fn hidden_subset(board: &Board, technique: Technique, size: usize) -> Option<Step> {
+    Unit::all().find_map(|unit| {
+        let positions = unit.positions();
+        let cells_of = |digit: u8| {
+            positions
+                .into_iter()
+                .filter(move |&pos| board.candidates(pos) & (1 << (digit - 1)) != 0)
+        };
+
+        let candidates = (1..=9)
+            .filter(|&digit| (2..=size).contains(&cells_of(digit).count()))
+            .collect::<Vec<_>>();
+
+        combinations(&candidates, size)
+            .into_iter()
+            .find_map(|subset| {
+                let mask = subset.iter().fold(0, |set, digit| set | 1 << (digit - 1));
+                let cells = positions
+                    .into_iter()
+                    .filter(|&pos| board.candidates(pos) & mask != 0)
+                    .collect::<Vec<_>>();
+                if cells.len() != size {
+                    return None;
+                }
+
+                let eliminations = cells
+                    .iter()
+                    .flat_map(|&pos| {
+                        digits(board.candidates(pos) & !mask).map(move |digit| (pos, digit))
+                    })
+                    .collect::<Vec<_>>();
+
+                (!eliminations.is_empty()).then(|| Step {
+                    technique,
+                    units: vec![unit],
+                    cells,
+                    links: Vec::new(),
+                    eliminations,
+                    placement: None,
+                })
+            })
+    })
+}
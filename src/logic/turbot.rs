@@ -0,0 +1,84 @@
+use crate::sudoku::{GridPos, Unit, cell};
+
+use super::{Board, Link, Step, Technique, all_cells, sees};
+
+/// Finds two conjugate pairs of a digit (the only two cells of a unit where it fits) a = b and
+/// c = d, where b sees c. b and c cannot both hold the digit, so a or d does, and it can be removed
+/// from every other cell that sees both.
+///
+/// The technique picks the shape to look for: a Skyscraper has both pairs in parallel lines, a
+/// Two-String Kite has one pair in a row and the other in a col, with b and c in the same square,
+/// and a Turbot Fish is any other shape.
+pub(super) fn turbot_fish(board: &Board, technique: Technique) -> Option<Step> {
+    (1..=9).find_map(|digit| {
+        let bit = 1 << (digit - 1);
+        let pairs = strong_links(board, digit);
+
+        pairs.iter().find_map(|&(first_unit, a, b)| {
+            pairs.iter().find_map(|&(second_unit, c, d)| {
+                if [a, b].contains(&c) || [a, b].contains(&d) || !sees(b, c) {
+                    return None;
+                }
+                if shape(first_unit, second_unit, b, c) != technique {
+                    return None;
+                }
+
+                let cells = vec![a, b, c, d];
+                let eliminations = all_cells()
+                    .filter(|pos| board.candidates(*pos) & bit != 0 && !cells.contains(pos))
+                    .filter(|&pos| sees(pos, a) && sees(pos, d))
+                    .map(|pos| (pos, digit))
+                    .collect::<Vec<_>>();
+
+                (!eliminations.is_empty()).then(|| Step {
+                    technique,
+                    units: vec![first_unit, second_unit],
+                    links: cells
+                        .windows(2)
+                        .enumerate()
+                        .map(|(index, pair)| Link {
+                            from: (pair[0], digit),
+                            to: (pair[1], digit),
+                            strong: index != 1,
+                        })
+                        .collect(),
+                    cells,
+                    eliminations,
+                    placement: None,
+                })
+            })
+        })
+    })
+}
+
+/// Finds the conjugate pairs of a digit with their units, both ways round.
+fn strong_links(board: &Board, digit: u8) -> Vec<(Unit, GridPos, GridPos)> {
+    Unit::all()
+        .flat_map(|unit| {
+            let mut cells = unit
+                .positions()
+                .into_iter()
+                .filter(|&pos| board.candidates(pos) & (1 << (digit - 1)) != 0);
+
+            match (cells.next(), cells.next(), cells.next()) {
+                (Some(first), Some(second), None) => {
+                    vec![(unit, first, second), (unit, second, first)]
+                }
+                _ => Vec::new(),
+            }
+        })
+        .collect()
+}
+
+/// Classifies the chain a = b - c = d by the units of its conjugate pairs.
+fn shape(first: Unit, second: Unit, b: GridPos, c: GridPos) -> Technique {
+    match (first, second) {
+        (Unit::Row(_), Unit::Row(_)) | (Unit::Col(_), Unit::Col(_)) => Technique::Skyscraper,
+        (Unit::Row(_), Unit::Col(_)) | (Unit::Col(_), Unit::Row(_))
+            if cell(b).square == cell(c).square =>
+        {
+            Technique::TwoStringKite
+        }
+        _ => Technique::TurbotFish,
+    }
+}
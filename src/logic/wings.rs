@@ -0,0 +1,70 @@
+use crate::sudoku::cell;
+
+use super::{Board, Step, Technique, all_cells, combinations, sees};
+
+/// Finds a pivot with candidates xy that sees two pincers with candidates xz and yz. Whichever
+/// digit goes in the pivot, one of the pincers holds z, so z can be removed from every cell that
+/// sees both pincers.
+pub(super) fn xy_wing(board: &Board) -> Option<Step> {
+    wing(board, Technique::XYWing, 2)
+}
+
+/// Finds a pivot with candidates xyz that sees two pincers with candidates xz and yz. One of the
+/// three holds z, so z can be removed from every cell that sees all three.
+pub(super) fn xyz_wing(board: &Board) -> Option<Step> {
+    wing(board, Technique::XYZWing, 3)
+}
+
+/// Finds a wing whose pivot has pivot_size candidates.
+fn wing(board: &Board, technique: Technique, pivot_size: u32) -> Option<Step> {
+    all_cells().find_map(|pivot| {
+        let candidates = board.candidates(pivot);
+        if candidates.count_ones() != pivot_size {
+            return None;
+        }
+
+        let pincers = cell(pivot)
+            .peers
+            .into_iter()
+            .filter(|&pos| board.candidates(pos).count_ones() == 2)
+            .collect::<Vec<_>>();
+
+        combinations(&pincers, 2).into_iter().find_map(|pincers| {
+            let (first, second) = (board.candidates(pincers[0]), board.candidates(pincers[1]));
+            if (candidates | first | second).count_ones() != 3
+                || first == second
+                || first == candidates
+                || second == candidates
+            {
+                return None;
+            }
+
+            let digit = first & second;
+            if digit.count_ones() != 1 || (pivot_size == 2 && candidates & digit != 0) {
+                return None;
+            }
+
+            // XYZ-Wing eliminations must also see the pivot, which can hold z too.
+            let pattern = vec![pivot, pincers[0], pincers[1]];
+            let seen = if pivot_size == 2 {
+                &pattern[1..]
+            } else {
+                &pattern[..]
+            };
+            let eliminations = all_cells()
+                .filter(|&pos| board.candidates(pos) & digit != 0)
+                .filter(|&pos| seen.iter().all(|&other| sees(pos, other)))
+                .map(|pos| (pos, digit.trailing_zeros() as u8 + 1))
+                .collect::<Vec<_>>();
+
+            (!eliminations.is_empty()).then(|| Step {
+                technique,
+                units: Vec::new(),
+                cells: pattern,
+                links: Vec::new(),
+                eliminations,
+                placement: None,
+            })
+        })
+    })
+}
@@ -1,25 +1,150 @@
-use std::path::PathBuf;
+use std::{
+    fs,
+    io::{IsTerminal, stdout},
+    path::{Path, PathBuf},
+    time::Duration,
+};
 
 use anyhow::{Result, anyhow};
-use clap::Parser;
+use clap::{CommandFactory, FromArgMatches, Parser, Subcommand};
 
 use sudoku_solver::prelude::*;
 
 #[derive(Parser)]
+#[command(args_conflicts_with_subcommands = true)]
 struct Args {
+    #[command(subcommand)]
+    command: Option<Command>,
+
     /// The input (puzzle) file to read from, omit to read from stdin.
     input_file: Option<PathBuf>,
 
     /// Do not draw border to format the solution.
     #[arg(short = 'n', long = "no-border")]
     plain_output: bool,
+
+    /// Print statistics about the puzzle before the solution.
+    #[arg(short = 's', long = "stats")]
+    stats: bool,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Times the solver backends on a built-in corpus of puzzles.
+    Bench {
+        /// Number of times to solve each puzzle with each backend.
+        #[arg(long, default_value_t = 5)]
+        runs: usize,
+
+        /// Milliseconds to give each backtracking solve before giving up.
+        #[arg(long = "timeout-ms", default_value_t = 1000)]
+        timeout_ms: u64,
+    },
+
+    /// Rates a collection of puzzles, one per line, and summarizes their difficulty.
+    Report {
+        /// The input (puzzle) file to read from, omit to read from stdin.
+        input_file: Option<PathBuf>,
+    },
+
+    /// Solves a pencil-mark puzzle given as 729 chars of candidates.
+    Sukaku {
+        /// The input (puzzle) file to read from, omit to read from stdin.
+        input_file: Option<PathBuf>,
+    },
+
+    /// Solves a puzzle logically and explains each step.
+    Explain {
+        /// The input (puzzle) file to read from, omit to read from stdin.
+        input_file: Option<PathBuf>,
+
+        /// Explain each step in a full sentence instead of solver notation.
+        #[arg(short = 'v', long = "verbose")]
+        verbose: bool,
+
+        /// Also draw each step with links as an SVG image, step-N.svg, in this directory.
+        #[arg(long = "svg")]
+        svg_dir: Option<PathBuf>,
+    },
+}
+
+impl Args {
+    /// Parses the command line, with the help text taken from the message catalog.
+    fn parse_localized() -> Args {
+        let command = Args::command()
+            .about(tr(Message::AppAbout))
+            .mut_arg("input_file", |arg| arg.help(tr(Message::InputFileHelp)))
+            .mut_arg("plain_output", |arg| arg.help(tr(Message::NoBorderHelp)))
+            .mut_arg("stats", |arg| arg.help(tr(Message::StatsHelp)))
+            .mut_subcommand("bench", |command| {
+                command
+                    .about(tr(Message::BenchAbout))
+                    .mut_arg("runs", |arg| arg.help(tr(Message::BenchRunsHelp)))
+                    .mut_arg("timeout_ms", |arg| arg.help(tr(Message::BenchTimeoutHelp)))
+            })
+            .mut_subcommand("report", |command| {
+                command
+                    .about(tr(Message::ReportAbout))
+                    .mut_arg("input_file", |arg| arg.help(tr(Message::InputFileHelp)))
+            })
+            .mut_subcommand("sukaku", |command| {
+                command
+                    .about(tr(Message::SukakuAbout))
+                    .mut_arg("input_file", |arg| arg.help(tr(Message::InputFileHelp)))
+            })
+            .mut_subcommand("explain", |command| {
+                command
+                    .about(tr(Message::ExplainAbout))
+                    .mut_arg("input_file", |arg| arg.help(tr(Message::InputFileHelp)))
+                    .mut_arg("verbose", |arg| arg.help(tr(Message::ExplainVerboseHelp)))
+                    .mut_arg("svg_dir", |arg| arg.help(tr(Message::ExplainSvgHelp)))
+            });
+
+        Args::from_arg_matches(&command.get_matches()).unwrap_or_else(|error| error.exit())
+    }
 }
 
 fn main() -> Result<()> {
-    let args = Args::parse();
+    let args = Args::parse_localized();
+
+    let input_file = match args.command {
+        Some(Command::Bench { runs, timeout_ms }) => {
+            print_bench(runs, Duration::from_millis(timeout_ms));
+            return Ok(());
+        }
+        Some(Command::Report { input_file }) => {
+            print_report(&read_to_puzzles(input_file)?);
+            return Ok(());
+        }
+        Some(Command::Sukaku { input_file }) => {
+            let solutions = solve_sukaku(read_to_sukaku(input_file)?, 2);
+            let solution = solutions.first().ok_or(anyhow!(tr(Message::NoSolution)))?;
+            if solutions.len() > 1 {
+                println!("{}", tr(Message::SeveralSolutions));
+            }
+            print_solution_with_border(*solution);
+            return Ok(());
+        }
+        Some(Command::Explain {
+            input_file,
+            verbose,
+            svg_dir,
+        }) => {
+            let puzzle = read_to_puzzle(input_file)?;
+            check_givens(puzzle)?;
+            return print_explanation(puzzle, verbose, svg_dir.as_deref());
+        }
+        None => args.input_file,
+    };
+
+    let puzzle = read_to_puzzle(input_file)?;
+    check_givens(puzzle)?;
 
-    let puzzle = read_to_puzzle(args.input_file)?;
-    let solution = solve_any(puzzle).ok_or(anyhow!("No solution."))?;
+    if args.stats {
+        print_stats(puzzle);
+    }
+
+    let solution = solve_any(puzzle).ok_or_else(|| no_solution(puzzle))?;
 
     if args.plain_output {
         print_solution(solution);
@@ -29,3 +154,262 @@ fn main() -> Result<()> {
 
     Ok(())
 }
+
+/// Fails if the same digit is given twice in a unit, listing the clashing cells.
+fn check_givens(puzzle: Puzzle) -> Result<()> {
+    let conflicts = find_conflicting_pairs(puzzle);
+    if !conflicts.is_empty() {
+        let cells = conflicts
+            .iter()
+            .map(|((row, col), (other_row, other_col), digit)| {
+                format!(
+                    "r{}c{}/r{}c{} ({digit})",
+                    row + 1,
+                    col + 1,
+                    other_row + 1,
+                    other_col + 1
+                )
+            })
+            .collect::<Vec<_>>();
+        return Err(anyhow!(
+            "{} {}",
+            tr(Message::ConflictingGivens),
+            cells.join(", ")
+        ));
+    }
+
+    Ok(())
+}
+
+/// Explains why a puzzle has no solution, as an error to return, followed by the steps that lead
+/// to the contradiction, if any.
+fn no_solution(puzzle: Puzzle) -> anyhow::Error {
+    let Some(reason) = explain_unsolvable(puzzle) else {
+        return anyhow!(tr(Message::NoSolution));
+    };
+
+    let (text, steps): (String, &[Step]) = match &reason {
+        Unsolvable::Conflict(conflict) => {
+            let cells = conflict.positions.iter().map(|&pos| cell_label(pos));
+            return anyhow!(
+                "{} {} ({})",
+                tr(Message::ConflictingGivens),
+                cells.collect::<Vec<_>>().join("/"),
+                conflict.digit
+            );
+        }
+        Unsolvable::NoCandidates { pos, steps } => (
+            format!("{} {}", tr(Message::NoCandidatesLeft), cell_label(*pos)),
+            steps,
+        ),
+        Unsolvable::NoPlace { unit, digit, steps } => (
+            format!(
+                "{} {} ({digit})",
+                tr(Message::NoPlaceLeft),
+                unit_label(*unit)
+            ),
+            steps,
+        ),
+        Unsolvable::Undetermined(_) => (tr(Message::LogicUndetermined).to_string(), &[]),
+    };
+
+    let mut text = format!("{} {text}", tr(Message::NoSolutionBecause));
+    if !steps.is_empty() {
+        text.push('\n');
+        text.push_str(tr(Message::AfterTheseSteps));
+    }
+    for (index, step) in steps.iter().enumerate() {
+        text.push_str(&format!(
+            "\n{}. {}",
+            index + 1,
+            explain(step, Verbosity::Compact)
+        ));
+    }
+
+    anyhow!(text)
+}
+
+/// Names a cell like "r4c6", counting from 1.
+fn cell_label((row, col): GridPos) -> String {
+    format!("r{}c{}", row + 1, col + 1)
+}
+
+/// Names a unit like "r4", "c6" or "b5", counting from 1.
+fn unit_label(unit: Unit) -> String {
+    match unit {
+        Unit::Row(row) => format!("r{}", row + 1),
+        Unit::Col(col) => format!("c{}", col + 1),
+        Unit::Square(square) => format!("b{}", square + 1),
+    }
+}
+
+/// Prints the steps of a logical solve, one per line, then the solution, or a note if the solver
+/// gets stuck. Steps with links, e.g. chains, are also drawn over the grid, and as SVG images in
+/// svg_dir if given.
+fn print_explanation(puzzle: Puzzle, verbose: bool, svg_dir: Option<&Path>) -> Result<()> {
+    let verbosity = match verbose {
+        true => Verbosity::Verbose,
+        false => Verbosity::Compact,
+    };
+
+    let solver = LogicalSolver::new().assume_uniqueness(has_unique_solution(puzzle));
+    let mut board = Board::new(puzzle).ok_or(anyhow!(tr(Message::NoSolution)))?;
+    for (index, step) in solver.solve(puzzle).into_iter().flatten().enumerate() {
+        println!("{}. {}", index + 1, explain(&step, verbosity));
+        if let Some(drawing) = chain_to_border_string(&board, &step, stdout().is_terminal()) {
+            println!("{drawing}");
+        }
+        if let Some(dir) = svg_dir
+            && let Some(svg) = chain_to_svg(&board, &step)
+        {
+            fs::write(dir.join(format!("step-{}.svg", index + 1)), svg)?;
+        }
+        board.apply(&step);
+    }
+
+    if board.is_solved() {
+        print_solution_with_border(board.grid());
+    } else {
+        println!("{}", tr(Message::LogicStuck));
+    }
+
+    Ok(())
+}
+
+/// Prints statistics about a puzzle.
+fn print_stats(puzzle: Puzzle) {
+    let stats = stats(puzzle);
+    let counts = |counts: [usize; 9]| counts.map(|count| count.to_string()).join(" ");
+
+    println!("{} {}", tr(Message::GivensLabel), stats.givens);
+    println!("{} {}", tr(Message::PerRowLabel), counts(stats.per_row));
+    println!("{} {}", tr(Message::PerColLabel), counts(stats.per_col));
+    println!(
+        "{} {}",
+        tr(Message::PerSquareLabel),
+        counts(stats.per_square)
+    );
+    println!("{} {}", tr(Message::PerDigitLabel), counts(stats.per_digit));
+    println!(
+        "{} {}",
+        tr(Message::EmptyUnitsLabel),
+        stats.empty_units.len()
+    );
+
+    let symmetries = detect_symmetry(puzzle)
+        .into_iter()
+        .map(|symmetry| {
+            tr(match symmetry {
+                Symmetry::Rotational180 => Message::Rotational180,
+                Symmetry::Rotational90 => Message::Rotational90,
+                Symmetry::MirrorHorizontal => Message::MirrorHorizontal,
+                Symmetry::MirrorVertical => Message::MirrorVertical,
+                Symmetry::Diagonal => Message::Diagonal,
+                Symmetry::AntiDiagonal => Message::AntiDiagonal,
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let symmetries = match symmetries.is_empty() {
+        true => tr(Message::NoSymmetry).to_string(),
+        false => symmetries.join(", "),
+    };
+    println!("{} {symmetries}", tr(Message::SymmetryLabel));
+}
+
+/// Rates puzzles and prints how many there are of each difficulty, score statistics, and the
+/// puzzles that stand out, numbered from 1.
+fn print_report(puzzles: &[Puzzle]) {
+    let report = difficulty_report(puzzles);
+    let numbers = |indices: &[usize]| {
+        indices
+            .iter()
+            .map(|index| (index + 1).to_string())
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+
+    for (difficulty, count) in report.histogram {
+        let label = tr(match difficulty {
+            Difficulty::Easy => Message::EasyDifficulty,
+            Difficulty::Medium => Message::MediumDifficulty,
+            Difficulty::Hard => Message::HardDifficulty,
+            Difficulty::Expert => Message::ExpertDifficulty,
+            Difficulty::Brutal => Message::BrutalDifficulty,
+        });
+        println!("{label:<10}{count:>6}");
+    }
+    println!(
+        "{:<10}{:>6}",
+        tr(Message::InvalidCount),
+        report.invalid.len()
+    );
+    println!();
+    println!(
+        "{:>8}{:>8}{:>8}{:>8}",
+        tr(Message::MeanHeader),
+        tr(Message::P50Header),
+        tr(Message::P90Header),
+        tr(Message::MaxHeader)
+    );
+    println!(
+        "{:>8.2}{:>8.2}{:>8.2}{:>8.2}",
+        report.mean, report.p50, report.p90, report.max
+    );
+    println!();
+    println!("{} {}", tr(Message::InvalidLabel), numbers(&report.invalid));
+    println!(
+        "{} {}",
+        tr(Message::OutliersLabel),
+        numbers(&report.outliers)
+    );
+}
+
+/// Runs the benchmark and prints a table of solve time percentiles.
+fn print_bench(runs: usize, timeout: Duration) {
+    println!(
+        "{:<14}{:<14}{:>9}{:>12}{:>12}{:>12}{:>12}",
+        tr(Message::BackendHeader),
+        tr(Message::TierHeader),
+        tr(Message::SolvedHeader),
+        tr(Message::P50Header),
+        tr(Message::P90Header),
+        tr(Message::P99Header),
+        tr(Message::MaxHeader)
+    );
+
+    for report in bench(&bench_backends(), runs, timeout) {
+        println!(
+            "{:<14}{:<14}{:>9}{:>12}{:>12}{:>12}{:>12}",
+            tr(backend_message(report.backend)),
+            tr(tier_message(report.tier)),
+            format!("{}/{}", report.solved, report.runs),
+            format!("{:.2?}", report.p50),
+            format!("{:.2?}", report.p90),
+            format!("{:.2?}", report.p99),
+            format!("{:.2?}", report.max),
+        );
+    }
+}
+
+/// Gets the catalog entry naming a backend.
+fn backend_message(backend: Backend) -> Message {
+    match backend {
+        Backend::Auto => Message::AutoBackend,
+        Backend::Backtracking => Message::BacktrackingBackend,
+        Backend::Propagation => Message::PropagationBackend,
+        Backend::Dlx => Message::DlxBackend,
+        #[cfg(feature = "sat")]
+        Backend::Sat => Message::SatBackend,
+        Backend::Annealing => Message::AnnealingBackend,
+    }
+}
+
+/// Gets the catalog entry naming a tier of the benchmark corpus.
+fn tier_message(tier: Tier) -> Message {
+    match tier {
+        Tier::Easy => Message::EasyTier,
+        Tier::Hard => Message::HardTier,
+        Tier::Pathological => Message::PathologicalTier,
+    }
+}
@@ -0,0 +1,231 @@
+use crate::sudoku::{GridPos, Puzzle, Uniqueness, candidates, uniqueness};
+
+/// What is wrong with a pencil mark.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MarkIssueKind {
+    /// The digit is marked, but a row, col or square already holds it.
+    Impossible,
+
+    /// The digit could go in the cell, but is not marked.
+    Missing,
+
+    /// The digit is the one the cell takes in the single solution, but is not marked.
+    Erased,
+}
+
+/// A problem with the pencil marks of one digit in one cell.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MarkIssue {
+    pub pos: GridPos,
+    pub digit: u8,
+    pub kind: MarkIssueKind,
+}
+
+/// Checks a user's pencil marks (candidate notes) against the true candidates of a puzzle, for a
+/// "check notes" button: marks that a row, col or square rules out are [MarkIssueKind::Impossible],
+/// and digits of the solution that are not marked are [MarkIssueKind::Erased].
+///
+/// Unlike [check_candidates] with include_missing, candidates erased by sound logic are not
+/// reported, only the ones that should have been kept. Unless the puzzle has exactly one solution,
+/// e.g. the user has placed a wrong digit, only impossible marks are reported, as there is no
+/// single digit a cell must keep. Issues are in reading order, then digit order.
+pub fn check_pencil_marks(puzzle: Puzzle, marks: [[u16; 9]; 9]) -> Vec<MarkIssue> {
+    let mut issues = check_candidates(puzzle, marks, false);
+
+    if let Uniqueness::One(solution) = uniqueness(puzzle) {
+        for (row, digits) in puzzle.iter().enumerate() {
+            for (col, &digit) in digits.iter().enumerate() {
+                let answer = solution[row][col];
+                if digit == 0 && !has_candidate(&marks, (row, col), answer) {
+                    issues.push(MarkIssue {
+                        pos: (row, col),
+                        digit: answer,
+                        kind: MarkIssueKind::Erased,
+                    });
+                }
+            }
+        }
+        issues.sort_by_key(|issue| (issue.pos, issue.digit));
+    }
+
+    issues
+}
+
+/// Checks a user's pencil marks (candidate notes) against the digits placed so far, for a "check
+/// my notes" feature.
+///
+/// marks holds the notes for each cell, with bit (digit - 1) set for each marked digit. Notes in
+/// cells that already hold a digit are ignored. Impossible marks are always reported; candidates
+/// that are not marked are reported only if include_missing is set. Issues are in reading order,
+/// then digit order.
+pub fn check_candidates(
+    grid: Puzzle,
+    marks: [[u16; 9]; 9],
+    include_missing: bool,
+) -> Vec<MarkIssue> {
+    let candidates = candidates(grid);
+
+    let mut issues = Vec::new();
+    for row in 0..9 {
+        for col in 0..9 {
+            if grid[row][col] != 0 {
+                continue;
+            }
+
+            for digit in 1..=9 {
+                let bit = 1 << (digit - 1);
+                let marked = marks[row][col] & bit != 0;
+                let possible = candidates[row][col] & bit != 0;
+
+                let kind = match (marked, possible) {
+                    (true, false) => MarkIssueKind::Impossible,
+                    (false, true) if include_missing => MarkIssueKind::Missing,
+                    _ => continue,
+                };
+
+                issues.push(MarkIssue {
+                    pos: (row, col),
+                    digit,
+                    kind,
+                });
+            }
+        }
+    }
+
+    issues
+}
+
+/// Checks whether a digit is among the candidates (or marks) of a cell.
+pub fn has_candidate(candidates: &[[u16; 9]; 9], (row, col): GridPos, digit: u8) -> bool {
+    (1..=9).contains(&digit) && candidates[row][col] & (1 << (digit - 1)) != 0
+}
+
+/// Iterates over the candidates (or marks) of a cell, in digit order.
+pub fn cell_candidates(
+    candidates: &[[u16; 9]; 9],
+    (row, col): GridPos,
+) -> impl Iterator<Item = u8> {
+    let set = candidates[row][col];
+    (1..=9).filter(move |digit| set & (1 << (digit - 1)) != 0)
+}
+
+/// Counts the candidates (or marks) of a cell.
+pub fn candidate_count(candidates: &[[u16; 9]; 9], (row, col): GridPos) -> u32 {
+    (candidates[row][col] & 0x1ff).count_ones()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PUZZLE: Puzzle = [
+        [5, 3, 0, 0, 7, 0, 0, 0, 0],
+        [6, 0, 0, 1, 9, 5, 0, 0, 0],
+        [9, 8, 0, 0, 0, 0, 0, 6, 0],
+        [8, 0, 0, 0, 6, 0, 0, 0, 3],
+        [4, 0, 0, 8, 0, 3, 0, 0, 1],
+        [7, 0, 0, 0, 2, 0, 0, 0, 6],
+        [0, 6, 0, 0, 0, 0, 2, 8, 0],
+        [0, 0, 0, 4, 1, 9, 0, 0, 5],
+        [0, 0, 0, 0, 8, 0, 0, 7, 9],
+    ];
+
+    #[test]
+    fn check_cell_candidates() {
+        let candidates = candidates(PUZZLE);
+        assert!(has_candidate(&candidates, (0, 2), 2));
+        assert!(!has_candidate(&candidates, (0, 2), 3));
+        assert!(!has_candidate(&candidates, (0, 2), 0));
+        assert_eq!(
+            cell_candidates(&candidates, (0, 2)).collect::<Vec<_>>(),
+            vec![1, 2, 4]
+        );
+        assert_eq!(candidate_count(&candidates, (0, 2)), 3);
+
+        // Givens have no candidates.
+        assert_eq!(cell_candidates(&candidates, (0, 0)).count(), 0);
+        assert_eq!(candidate_count(&candidates, (0, 0)), 0);
+    }
+
+    #[test]
+    fn check_check_candidates() {
+        let mut marks = candidates(PUZZLE);
+        assert!(check_candidates(PUZZLE, marks, true).is_empty());
+
+        // r0c2 can be 1, 2 or 4.
+        assert_eq!(marks[0][2], 0b1011);
+
+        marks[0][2] = 0b1101;
+        assert_eq!(
+            check_candidates(PUZZLE, marks, false),
+            vec![MarkIssue {
+                pos: (0, 2),
+                digit: 3,
+                kind: MarkIssueKind::Impossible,
+            }]
+        );
+        assert_eq!(
+            check_candidates(PUZZLE, marks, true),
+            vec![
+                MarkIssue {
+                    pos: (0, 2),
+                    digit: 2,
+                    kind: MarkIssueKind::Missing,
+                },
+                MarkIssue {
+                    pos: (0, 2),
+                    digit: 3,
+                    kind: MarkIssueKind::Impossible,
+                },
+            ]
+        );
+
+        // Notes in filled cells are ignored.
+        marks[0][0] = 0x1ff;
+        assert_eq!(check_candidates(PUZZLE, marks, false).len(), 1);
+    }
+
+    #[test]
+    fn check_check_pencil_marks() {
+        let mut marks = candidates(PUZZLE);
+        assert!(check_pencil_marks(PUZZLE, marks).is_empty());
+
+        // r0c2 is 4 in the solution, so erasing 1 is fine but erasing 4 is not.
+        marks[0][2] = 0b0110;
+        marks[0][3] = 0;
+        assert_eq!(
+            check_pencil_marks(PUZZLE, marks),
+            vec![
+                MarkIssue {
+                    pos: (0, 2),
+                    digit: 3,
+                    kind: MarkIssueKind::Impossible,
+                },
+                MarkIssue {
+                    pos: (0, 2),
+                    digit: 4,
+                    kind: MarkIssueKind::Erased,
+                },
+                MarkIssue {
+                    pos: (0, 3),
+                    digit: 6,
+                    kind: MarkIssueKind::Erased,
+                },
+            ]
+        );
+
+        // With a wrong digit placed, only impossible marks are reported.
+        let mut wrong = PUZZLE;
+        wrong[0][2] = 1;
+        assert_eq!(check_pencil_marks(wrong, [[0; 9]; 9]), Vec::new());
+        assert_eq!(
+            check_pencil_marks(wrong, [[0x1ff; 9]; 9]),
+            check_candidates(wrong, [[0x1ff; 9]; 9], false)
+        );
+
+        // Nor without a single solution, where no digit must be kept.
+        let mut marks = [[0x1ff; 9]; 9];
+        marks[0][0] = 0x1fe;
+        assert_eq!(check_pencil_marks([[0; 9]; 9], marks), Vec::new());
+    }
+}
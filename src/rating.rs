@@ -0,0 +1,915 @@
+use std::{num::NonZeroUsize, thread, time::Duration};
+
+use crate::{
+    logic::{Board, LogicalOutcome, LogicalSolver, Step, Technique},
+    rng::Rng,
+    sudoku::{
+        DigitSet, GridPos, Puzzle, Uniqueness, Unit, blanks, candidates, has_unique_solution,
+        hidden_single, is_valid_puzzle, uniqueness,
+    },
+};
+
+/// Sudoku Explainer rating of a hidden single within a square.
+const HIDDEN_SINGLE_SQUARE: f32 = 1.2;
+
+/// Sudoku Explainer rating of a hidden single within a row or col.
+const HIDDEN_SINGLE_LINE: f32 = 1.5;
+
+/// Sudoku Explainer rating of a naked single.
+const NAKED_SINGLE: f32 = 2.3;
+
+/// Rates a puzzle on the Sudoku Explainer scale (1.0-11+), so that ratings are comparable with
+/// published collections.
+///
+/// Like Sudoku Explainer, the puzzle is solved by always applying the easiest technique available,
+/// and the rating is that of the hardest step needed, i.e. [LogicalRating::rating]. A puzzle that
+/// needs trial and error is rated at least 7.0, where Sudoku Explainer would need forcing chains.
+/// A puzzle without blanks is rated 0.0.
+///
+/// Returns None if the puzzle is invalid.
+pub fn se_rating(puzzle: Puzzle) -> Option<f32> {
+    let logical = logical_rating(puzzle)?;
+
+    Some(match logical.difficulty {
+        Difficulty::Brutal => logical.rating.max(BRUTAL),
+        _ => logical.rating,
+    })
+}
+
+/// Lowest [LogicalRating::score] of a puzzle that needs trial and error.
+const BRUTAL: f32 = 7.0;
+
+/// Coarse difficulty of a puzzle, e.g. to label it in a collection.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Difficulty {
+    /// Singles only.
+    Easy,
+
+    /// Needs locked candidates, or naked or hidden pairs or triples.
+    Medium,
+
+    /// Needs basic fish, quads, wings or turbot fish.
+    Hard,
+
+    /// Needs bigger or finned fish, coloring, chains, unique rectangles or almost locked sets.
+    Expert,
+
+    /// Requires trial and error: the logical solver gets stuck.
+    Brutal,
+}
+
+impl Difficulty {
+    /// Gets the difficulty of a puzzle that needs the technique.
+    fn of(technique: Technique) -> Difficulty {
+        match technique {
+            Technique::HiddenSingle | Technique::NakedSingle => Difficulty::Easy,
+            Technique::Pointing
+            | Technique::BoxLineReduction
+            | Technique::NakedPair
+            | Technique::HiddenPair
+            | Technique::NakedTriple
+            | Technique::HiddenTriple => Difficulty::Medium,
+            Technique::XWing
+            | Technique::FinnedXWing
+            | Technique::Swordfish
+            | Technique::Skyscraper
+            | Technique::TwoStringKite
+            | Technique::TurbotFish
+            | Technique::XYWing
+            | Technique::XYZWing
+            | Technique::NakedQuad
+            | Technique::HiddenQuad => Difficulty::Hard,
+            Technique::FinnedSwordfish
+            | Technique::Jellyfish
+            | Technique::FinnedJellyfish
+            | Technique::UniqueRectangle
+            | Technique::SimpleColoring
+            | Technique::XChain
+            | Technique::XYChain
+            | Technique::AlsXz
+            | Technique::Custom(_) => Difficulty::Expert,
+        }
+    }
+}
+
+/// Gets the rating of a step: the weight of its technique, except that hidden singles within a
+/// row or col are rated higher than within a square, as in Sudoku Explainer.
+fn step_rating(step: &Step) -> f32 {
+    match step.technique {
+        Technique::HiddenSingle => match step.units.first() {
+            Some(Unit::Square(_)) => HIDDEN_SINGLE_SQUARE,
+            _ => HIDDEN_SINGLE_LINE,
+        },
+        technique => technique.weight(),
+    }
+}
+
+/// Rates a puzzle by the hardest technique the [LogicalSolver] needs to solve it, with almost
+/// locked sets, and with unique rectangles if the puzzle has a unique solution. A puzzle without
+/// blanks is [Difficulty::Easy].
+///
+/// Returns None if the puzzle is invalid.
+pub fn rate(puzzle: Puzzle) -> Option<Difficulty> {
+    Some(logical_rating(puzzle)?.difficulty)
+}
+
+/// Logical solving statistics, found by [logical_rating].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LogicalRating {
+    pub difficulty: Difficulty,
+
+    /// Rating of the hardest step taken, on a scale akin to Sudoku Explainer's, or 0.0 if no step
+    /// was taken.
+    pub rating: f32,
+
+    /// Number of steps that needed more than singles.
+    pub hard_steps: usize,
+
+    /// Sum of the ratings of all the steps taken.
+    pub total: f32,
+}
+
+impl LogicalRating {
+    /// Single number for sorting: the rating of the hardest step, plus less than 0.1 growing with
+    /// the number of hard steps, so puzzles needing the same technique more often sort later.
+    /// [Difficulty::Brutal] puzzles score at least 7.0, above any technique, as on the Sudoku
+    /// Explainer scale, where they would need forcing chains.
+    pub fn score(&self) -> f32 {
+        let rating = match self.difficulty {
+            Difficulty::Brutal => self.rating.max(BRUTAL),
+            _ => self.rating,
+        };
+        let hard_steps = self.hard_steps as f32;
+        rating + 0.1 * hard_steps / (hard_steps + 10.0)
+    }
+}
+
+/// Rates a puzzle like [rate], but also weights each step by its technique, so that puzzles of the
+/// same [Difficulty] can be sorted. A [Difficulty::Brutal] puzzle is rated on the steps taken
+/// until the solver got stuck.
+///
+/// Returns None if the puzzle is invalid.
+pub fn logical_rating(puzzle: Puzzle) -> Option<LogicalRating> {
+    let solver = full_solver(puzzle);
+
+    let mut board = Board::new(puzzle)?;
+    let mut logical = LogicalRating {
+        difficulty: Difficulty::Easy,
+        rating: 0.0,
+        hard_steps: 0,
+        total: 0.0,
+    };
+    for step in solver.solve(puzzle)? {
+        let rating = step_rating(&step);
+        logical.difficulty = logical.difficulty.max(Difficulty::of(step.technique));
+        logical.rating = logical.rating.max(rating);
+        logical.total += rating;
+        if rating > NAKED_SINGLE {
+            logical.hard_steps += 1;
+        }
+        board.apply(&step);
+    }
+
+    if !board.is_solved() {
+        logical.difficulty = Difficulty::Brutal;
+    }
+
+    Some(logical)
+}
+
+/// Summary of the difficulty of a collection of puzzles, found by [difficulty_report].
+#[derive(Clone, Debug, PartialEq)]
+pub struct DifficultyReport {
+    /// Number of puzzles of each difficulty, from easiest to hardest.
+    pub histogram: [(Difficulty, usize); 5],
+
+    /// Indices of the puzzles that are invalid, and so left out of everything else.
+    pub invalid: Vec<usize>,
+
+    /// Mean and percentiles of the [LogicalRating::score] of the valid puzzles, or 0.0 if there
+    /// are none.
+    pub mean: f32,
+    pub p50: f32,
+    pub p90: f32,
+    pub max: f32,
+
+    /// Indices of the puzzles whose score is far from the rest: below the first quartile, or above
+    /// the third, by more than 1.5 times the distance between them.
+    pub outliers: Vec<usize>,
+}
+
+/// Rates each of a collection of puzzles like [logical_rating], spreading the puzzles over all
+/// available cores, and summarizes the results, e.g. to check the balance of a puzzle pack.
+pub fn difficulty_report(puzzles: &[Puzzle]) -> DifficultyReport {
+    let threads = thread::available_parallelism().map_or(1, NonZeroUsize::get);
+    let chunk_size = puzzles.len().div_ceil(threads).max(1);
+
+    let ratings = thread::scope(|scope| {
+        let handles = puzzles
+            .chunks(chunk_size)
+            .map(|chunk| {
+                scope.spawn(|| chunk.iter().map(|&puzzle| logical_rating(puzzle)).collect())
+            })
+            .collect::<Vec<_>>();
+
+        handles
+            .into_iter()
+            .flat_map(|handle| -> Vec<_> { handle.join().unwrap() })
+            .collect::<Vec<_>>()
+    });
+
+    let mut histogram = [
+        Difficulty::Easy,
+        Difficulty::Medium,
+        Difficulty::Hard,
+        Difficulty::Expert,
+        Difficulty::Brutal,
+    ]
+    .map(|difficulty| (difficulty, 0));
+    let mut invalid = Vec::new();
+    let mut scores = Vec::new();
+    for (index, rating) in ratings.iter().enumerate() {
+        match rating {
+            Some(rating) => {
+                histogram[rating.difficulty as usize].1 += 1;
+                scores.push((index, rating.score()));
+            }
+            None => invalid.push(index),
+        }
+    }
+
+    let mut sorted = scores.iter().map(|&(_, score)| score).collect::<Vec<_>>();
+    sorted.sort_by(f32::total_cmp);
+    let (q1, q3) = (percentile(&sorted, 25), percentile(&sorted, 75));
+    let fence = 1.5 * (q3 - q1);
+    let outliers = scores
+        .iter()
+        .filter(|&&(_, score)| score < q1 - fence || score > q3 + fence)
+        .map(|&(index, _)| index)
+        .collect();
+
+    DifficultyReport {
+        histogram,
+        invalid,
+        mean: match sorted.len() {
+            0 => 0.0,
+            len => sorted.iter().sum::<f32>() / len as f32,
+        },
+        p50: percentile(&sorted, 50),
+        p90: percentile(&sorted, 90),
+        max: percentile(&sorted, 100),
+        outliers,
+    }
+}
+
+/// Gets the nearest-rank percentile of sorted scores, or 0.0 if there are none.
+fn percentile(sorted: &[f32], percent: usize) -> f32 {
+    match sorted.len() {
+        0 => 0.0,
+        len => sorted[(len * percent).div_ceil(100).max(1) - 1],
+    }
+}
+
+/// Checks whether solving a puzzle takes trial and error, i.e. the full set of techniques of the
+/// [LogicalSolver] gets stuck on it, e.g. to keep only puzzles that pure logic can solve. See
+/// [guessing_point] for where it gets stuck.
+///
+/// Returns false if the puzzle is invalid.
+pub fn requires_guessing(puzzle: Puzzle) -> bool {
+    guessing_point(puzzle).is_some()
+}
+
+/// Finds where logic runs out on a puzzle: the board, with the digits placed and candidates left,
+/// that the full set of techniques of the [LogicalSolver] gets stuck on.
+///
+/// Returns None if the puzzle is invalid, or can be solved without guessing.
+pub fn guessing_point(puzzle: Puzzle) -> Option<Board> {
+    match full_solver(puzzle).try_solve(puzzle)? {
+        LogicalOutcome::Solved(_) => None,
+        LogicalOutcome::Stuck(board) => Some(board),
+    }
+}
+
+/// Measures how far logic gets on a puzzle: the fraction of its blanks that the full set of
+/// techniques of the [LogicalSolver] fills in before it gets stuck, from 0.0 to 1.0, e.g. as a cheap
+/// proxy for hardness when filtering generated puzzles. A puzzle without blanks counts as 1.0.
+///
+/// Returns 0.0 if the puzzle is invalid.
+pub fn logic_coverage(puzzle: Puzzle) -> f32 {
+    let blanks = |grid: Puzzle| grid.iter().flatten().filter(|&&digit| digit == 0).count();
+    let board = match full_solver(puzzle).try_solve(puzzle) {
+        Some(LogicalOutcome::Solved(_)) => return 1.0,
+        Some(LogicalOutcome::Stuck(board)) => board,
+        None => return 0.0,
+    };
+
+    let total = blanks(puzzle);
+    (total - blanks(board.grid())) as f32 / total as f32
+}
+
+/// Checks whether a puzzle needs a technique: the full set of techniques of the [LogicalSolver]
+/// solves it, but not without that technique, e.g. to pick puzzles for a lesson on the technique.
+///
+/// Harder techniques often make up for easier ones, e.g. chains find what X-Wings do, so few
+/// puzzles need anything but the hardest techniques.
+///
+/// Returns false if the puzzle is invalid, or needs trial and error anyway.
+pub fn requires_technique(puzzle: Puzzle, technique: Technique) -> bool {
+    let unique = has_unique_solution(puzzle);
+    let solves =
+        |solver: LogicalSolver| matches!(solver.try_solve(puzzle), Some(LogicalOutcome::Solved(_)));
+
+    let others = Technique::ALL
+        .into_iter()
+        .filter(|&other| other != technique && (unique || !other.assumes_uniqueness()))
+        .collect::<Vec<_>>();
+
+    solves(full_solver(puzzle)) && !solves(LogicalSolver::with_techniques(&others))
+}
+
+/// Sets up a solver with every technique, including almost locked sets, and unique rectangles if
+/// the puzzle has a unique solution.
+fn full_solver(puzzle: Puzzle) -> LogicalSolver {
+    LogicalSolver::new()
+        .almost_locked_sets(true)
+        .assume_uniqueness(has_unique_solution(puzzle))
+}
+
+/// How quickly a person solves.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SkillLevel {
+    Beginner,
+    Casual,
+    Expert,
+}
+
+impl SkillLevel {
+    /// Seconds taken to spot and apply a step: singles by their Sudoku Explainer rating, and
+    /// harder techniques by their [Difficulty] tier.
+    fn seconds_per_step(self, step: &Step) -> f32 {
+        let base = match Difficulty::of(step.technique) {
+            Difficulty::Easy => match step_rating(step) {
+                rating if rating <= HIDDEN_SINGLE_SQUARE => 6.0,
+                rating if rating <= HIDDEN_SINGLE_LINE => 10.0,
+                _ => 20.0,
+            },
+            Difficulty::Medium => 45.0,
+            Difficulty::Hard => 90.0,
+            Difficulty::Expert | Difficulty::Brutal => 180.0,
+        };
+
+        match self {
+            SkillLevel::Beginner => base * 2.5,
+            SkillLevel::Casual => base,
+            SkillLevel::Expert => base * 0.4,
+        }
+    }
+}
+
+/// Estimates how long a person of the given skill takes to solve a puzzle, e.g. to label a puzzle
+/// as "about 10 minutes".
+///
+/// The estimate adds up a rough time for each step of the same solve path as [logical_rating].
+/// Returns None if the puzzle is invalid or needs trial and error, which cannot be timed.
+pub fn estimate_solve_time(puzzle: Puzzle, skill: SkillLevel) -> Option<Duration> {
+    let mut board = Board::new(puzzle)?;
+    let mut seconds = 0.0;
+    for step in full_solver(puzzle).solve(puzzle)? {
+        seconds += skill.seconds_per_step(&step);
+        board.apply(&step);
+    }
+
+    board.is_solved().then(|| Duration::from_secs_f32(seconds))
+}
+
+/// Finds the easiest single to fill in among the blanks, with its Sudoku Explainer rating.
+fn easiest_step(candidates: &[[DigitSet; 9]; 9], blanks: &[GridPos]) -> Option<(f32, GridPos, u8)> {
+    let mut squares = Unit::all().filter(|unit| matches!(unit, Unit::Square(_)));
+    let mut lines = Unit::all().filter(|unit| !matches!(unit, Unit::Square(_)));
+
+    if let Some((pos, digit)) = squares.find_map(|unit| hidden_single(candidates, unit)) {
+        Some((HIDDEN_SINGLE_SQUARE, pos, digit))
+    } else if let Some((pos, digit)) = lines.find_map(|unit| hidden_single(candidates, unit)) {
+        Some((HIDDEN_SINGLE_LINE, pos, digit))
+    } else {
+        blanks
+            .iter()
+            .find(|&&(row, col)| candidates[row][col].count_ones() == 1)
+            .map(|&(row, col)| {
+                let digit = candidates[row][col].trailing_zeros() as u8 + 1;
+                (NAKED_SINGLE, (row, col), digit)
+            })
+    }
+}
+
+/// The blank of a puzzle that takes the most reasoning to fill in, found by [hardest_cell].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct HardestCell {
+    pub pos: GridPos,
+
+    /// Number of placements made before this one, starting from 0.
+    pub step: usize,
+
+    /// Number of candidates the blank had when it was filled in.
+    pub candidates: u32,
+
+    /// Whether it had to be guessed, as no single was left.
+    pub guessed: bool,
+
+    /// Sudoku Explainer rating of the single that filled it in, or 0.0 if guessed.
+    pub rating: f32,
+}
+
+impl HardestCell {
+    /// Guessed blanks are harder than others, and the more candidates the harder. Blanks filled in
+    /// by singles are as hard as their rating.
+    fn is_easier_than(&self, other: &HardestCell) -> bool {
+        match (self.guessed, other.guessed) {
+            (true, true) => self.candidates < other.candidates,
+            (false, false) => self.rating < other.rating,
+            (guessed, _) => !guessed,
+        }
+    }
+}
+
+/// Finds the blank that takes the most reasoning to fill in, e.g. to point a player at the right
+/// area for a hint.
+///
+/// The puzzle is solved like [se_rating], always applying the easiest technique available, except
+/// that when no single is left, the blank with the fewest candidates is filled in from the
+/// solution, as a guess. The hardest cell is the guessed blank with the most candidates, or if
+/// nothing was guessed, the blank filled in by the hardest single. Ties go to the later blank.
+///
+/// Returns None if the puzzle does not have exactly one solution, or has no blanks.
+pub fn hardest_cell(mut puzzle: Puzzle) -> Option<HardestCell> {
+    let Uniqueness::One(solution) = uniqueness(puzzle) else {
+        return None;
+    };
+
+    let mut hardest: Option<HardestCell> = None;
+    for step in 0.. {
+        let candidates = candidates(puzzle);
+        let blanks = blanks(puzzle);
+        if blanks.is_empty() {
+            break;
+        }
+
+        let cell = match easiest_step(&candidates, &blanks) {
+            Some((rating, (row, col), digit)) => {
+                puzzle[row][col] = digit;
+                HardestCell {
+                    pos: (row, col),
+                    step,
+                    candidates: candidates[row][col].count_ones(),
+                    guessed: false,
+                    rating,
+                }
+            }
+            None => {
+                let (row, col) = *blanks
+                    .iter()
+                    .min_by_key(|&&(row, col)| candidates[row][col].count_ones())?;
+                puzzle[row][col] = solution[row][col];
+                HardestCell {
+                    pos: (row, col),
+                    step,
+                    candidates: candidates[row][col].count_ones(),
+                    guessed: true,
+                    rating: 0.0,
+                }
+            }
+        };
+
+        if hardest.is_none_or(|hardest| !cell.is_easier_than(&hardest)) {
+            hardest = Some(cell);
+        }
+    }
+
+    hardest
+}
+
+/// Backtracking statistics, averaged over several randomized searches.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SearchRating {
+    /// Mean number of times a search had to pick between two or more candidates.
+    pub guesses: f32,
+
+    /// Mean number of placements that led to a dead end.
+    pub backtracks: f32,
+}
+
+impl SearchRating {
+    /// Single number for sorting: log2 of 1 + the mean backtracks, so 0.0 means no search was
+    /// needed, and each point is roughly twice as much work.
+    pub fn score(&self) -> f32 {
+        (1.0 + self.backtracks).log2()
+    }
+}
+
+/// Cheaply estimates how hard a puzzle is by running samples randomized backtracking searches,
+/// instead of the full technique ladder. Meant for triaging large numbers of puzzles.
+///
+/// Each search branches on a blank with the fewest candidates, trying the candidates in a random
+/// order, and gives up after visiting 100,000 grids, so that a sparse grid without a solution
+/// cannot stall triage. The same seed always gives the same rating. Returns None if the puzzle has
+/// no solution, a search gives up, or samples is 0.
+pub fn search_rating(puzzle: Puzzle, samples: usize, seed: u64) -> Option<SearchRating> {
+    if samples == 0 || !is_valid_puzzle(puzzle) {
+        return None;
+    }
+
+    let mut rng = Rng::new(seed);
+    let mut counts = SearchCounts::default();
+    for _ in 0..samples {
+        let mut grid = puzzle;
+        counts.nodes = 0;
+        if !sample_search(&mut grid, &mut rng, &mut counts, SAMPLE_NODES) {
+            return None;
+        }
+    }
+
+    Some(SearchRating {
+        guesses: counts.guesses as f32 / samples as f32,
+        backtracks: counts.backtracks as f32 / samples as f32,
+    })
+}
+
+/// Most grids visited by one search of [search_rating] before it gives up.
+const SAMPLE_NODES: usize = 100_000;
+
+/// Running totals for [search_rating].
+#[derive(Default)]
+struct SearchCounts {
+    guesses: usize,
+    backtracks: usize,
+
+    /// Grids visited by the current search.
+    nodes: usize,
+}
+
+/// Runs one randomized search in place, adding to counts, and giving up once counts.nodes passes
+/// max_nodes. Returns whether a solution was found. Blanks are only left filled in if so.
+fn sample_search(
+    puzzle: &mut Puzzle,
+    rng: &mut Rng,
+    counts: &mut SearchCounts,
+    max_nodes: usize,
+) -> bool {
+    counts.nodes += 1;
+    if counts.nodes > max_nodes {
+        return false;
+    }
+
+    let candidates = candidates(*puzzle);
+
+    let mut best = Vec::new();
+    let mut fewest = u32::MAX;
+    for row in 0..9 {
+        for col in 0..9 {
+            if puzzle[row][col] != 0 {
+                continue;
+            }
+
+            let count = candidates[row][col].count_ones();
+            if count < fewest {
+                fewest = count;
+                best.clear();
+            }
+            if count == fewest {
+                best.push((row, col));
+            }
+        }
+    }
+
+    if best.is_empty() {
+        return true;
+    }
+
+    let (row, col) = best[rng.below(best.len())];
+    let mut digits = (1..=9)
+        .filter(|digit| candidates[row][col] & (1 << (digit - 1)) != 0)
+        .collect::<Vec<_>>();
+    rng.shuffle(&mut digits);
+    if digits.len() > 1 {
+        counts.guesses += 1;
+    }
+
+    for digit in digits {
+        puzzle[row][col] = digit;
+        if sample_search(puzzle, rng, counts, max_nodes) {
+            return true;
+        }
+
+        counts.backtracks += 1;
+    }
+
+    puzzle[row][col] = 0;
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::sudoku::solve_any;
+
+    use super::*;
+
+    const EASY: Puzzle = [
+        [5, 3, 0, 0, 7, 0, 0, 0, 0],
+        [6, 0, 0, 1, 9, 5, 0, 0, 0],
+        [9, 8, 0, 0, 0, 0, 0, 6, 0],
+        [8, 0, 0, 0, 6, 0, 0, 0, 3],
+        [4, 0, 0, 8, 0, 3, 0, 0, 1],
+        [7, 0, 0, 0, 2, 0, 0, 0, 6],
+        [0, 6, 0, 0, 0, 0, 2, 8, 0],
+        [0, 0, 0, 4, 1, 9, 0, 0, 5],
+        [0, 0, 0, 0, 8, 0, 0, 7, 9],
+    ];
+
+    /// Needs pointing.
+    const MEDIUM: Puzzle = [
+        [4, 0, 0, 0, 0, 0, 8, 0, 5],
+        [0, 3, 0, 0, 0, 0, 0, 0, 0],
+        [0, 0, 0, 7, 0, 0, 0, 0, 0],
+        [0, 2, 0, 0, 0, 0, 0, 6, 0],
+        [0, 0, 0, 0, 8, 0, 4, 0, 0],
+        [0, 0, 0, 0, 1, 0, 0, 0, 0],
+        [0, 0, 0, 6, 0, 3, 0, 7, 0],
+        [5, 0, 0, 2, 0, 0, 0, 0, 0],
+        [1, 0, 4, 0, 0, 0, 0, 0, 0],
+    ];
+
+    const TOUGH: Puzzle = [
+        [0, 0, 0, 0, 0, 0, 0, 0, 0],
+        [0, 0, 0, 0, 0, 0, 0, 3, 2],
+        [0, 0, 0, 0, 5, 9, 0, 0, 4],
+        [0, 0, 4, 0, 3, 0, 0, 0, 9],
+        [0, 0, 1, 0, 0, 0, 0, 2, 0],
+        [0, 0, 5, 6, 7, 0, 0, 0, 0],
+        [0, 0, 8, 0, 0, 0, 0, 0, 0],
+        [0, 5, 0, 0, 0, 2, 7, 9, 0],
+        [0, 7, 9, 0, 1, 0, 6, 8, 0],
+    ];
+
+    const EXPERT: Puzzle = [
+        [5, 2, 0, 9, 0, 0, 7, 0, 0],
+        [0, 0, 3, 0, 8, 0, 0, 0, 1],
+        [1, 0, 0, 4, 0, 0, 0, 0, 0],
+        [0, 0, 0, 0, 6, 0, 0, 0, 0],
+        [0, 4, 5, 0, 0, 0, 0, 6, 0],
+        [8, 0, 0, 2, 0, 1, 0, 5, 0],
+        [0, 0, 8, 0, 0, 0, 9, 0, 0],
+        [0, 9, 0, 7, 4, 0, 0, 0, 0],
+        [0, 0, 0, 0, 0, 0, 8, 0, 4],
+    ];
+
+    /// Arto Inkala's "world's hardest sudoku".
+    const HARD: Puzzle = [
+        [8, 0, 0, 0, 0, 0, 0, 0, 0],
+        [0, 0, 3, 6, 0, 0, 0, 0, 0],
+        [0, 7, 0, 0, 9, 0, 2, 0, 0],
+        [0, 5, 0, 0, 0, 7, 0, 0, 0],
+        [0, 0, 0, 0, 4, 5, 7, 0, 0],
+        [0, 0, 0, 1, 0, 0, 0, 3, 0],
+        [0, 0, 1, 0, 0, 0, 0, 6, 8],
+        [0, 0, 8, 5, 0, 0, 0, 1, 0],
+        [0, 9, 0, 0, 0, 0, 4, 0, 0],
+    ];
+
+    #[test]
+    fn check_se_rating() {
+        let rating = se_rating(EASY).unwrap();
+        assert!((HIDDEN_SINGLE_SQUARE..=NAKED_SINGLE).contains(&rating));
+
+        let medium = se_rating(MEDIUM).unwrap();
+        assert!(medium >= Technique::Pointing.weight());
+        assert!(rating < medium);
+        assert!(medium <= se_rating(TOUGH).unwrap());
+        assert!(se_rating(TOUGH).unwrap() <= se_rating(EXPERT).unwrap());
+        assert!(se_rating(HARD).unwrap() >= BRUTAL);
+        assert_eq!(se_rating(solve_any(EASY).unwrap()), Some(0.0));
+
+        let mut puzzle = EASY;
+        puzzle[0][2] = 5;
+        assert_eq!(se_rating(puzzle), None);
+    }
+
+    #[test]
+    fn check_rate() {
+        assert_eq!(rate(EASY), Some(Difficulty::Easy));
+        assert_eq!(rate(MEDIUM), Some(Difficulty::Medium));
+        assert_eq!(rate(TOUGH), Some(Difficulty::Hard));
+        assert_eq!(rate(EXPERT), Some(Difficulty::Expert));
+        assert_eq!(rate(HARD), Some(Difficulty::Brutal));
+        assert_eq!(rate(solve_any(EASY).unwrap()), Some(Difficulty::Easy));
+
+        let mut puzzle = EASY;
+        puzzle[0][2] = 5;
+        assert_eq!(rate(puzzle), None);
+    }
+
+    #[test]
+    fn check_logical_rating() {
+        let easy = logical_rating(EASY).unwrap();
+        assert_eq!(easy.difficulty, Difficulty::Easy);
+        assert_eq!(easy.hard_steps, 0);
+        assert!(easy.rating <= NAKED_SINGLE);
+        assert!(easy.total >= easy.rating);
+        assert_eq!(easy.score(), easy.rating);
+
+        let medium = logical_rating(MEDIUM).unwrap();
+        let tough = logical_rating(TOUGH).unwrap();
+        let expert = logical_rating(EXPERT).unwrap();
+        assert_eq!(medium.difficulty, Difficulty::Medium);
+        assert!(medium.hard_steps > 0);
+        assert!(easy.score() < medium.score());
+        assert!(medium.score() < tough.score());
+        assert!(tough.score() < expert.score());
+        assert!(medium.score() < medium.rating + 0.1);
+
+        let hard = logical_rating(HARD).unwrap();
+        assert_eq!(hard.difficulty, Difficulty::Brutal);
+        assert!(hard.score() >= BRUTAL);
+        assert!(expert.score() < hard.score());
+
+        let solved = logical_rating(solve_any(EASY).unwrap()).unwrap();
+        assert_eq!(
+            (solved.rating, solved.hard_steps, solved.score()),
+            (0.0, 0, 0.0)
+        );
+
+        let mut puzzle = EASY;
+        puzzle[0][2] = 5;
+        assert_eq!(logical_rating(puzzle), None);
+    }
+
+    #[test]
+    fn check_difficulty_report() {
+        let mut invalid = EASY;
+        invalid[0][2] = 5;
+        let mut puzzles = vec![EASY; 8];
+        puzzles.extend([MEDIUM, invalid, HARD]);
+
+        let report = difficulty_report(&puzzles);
+        assert_eq!(
+            report.histogram,
+            [
+                (Difficulty::Easy, 8),
+                (Difficulty::Medium, 1),
+                (Difficulty::Hard, 0),
+                (Difficulty::Expert, 0),
+                (Difficulty::Brutal, 1),
+            ]
+        );
+        assert_eq!(report.invalid, vec![9]);
+
+        let easy = logical_rating(EASY).unwrap().score();
+        let medium = logical_rating(MEDIUM).unwrap().score();
+        let hard = logical_rating(HARD).unwrap().score();
+        assert_eq!((report.p50, report.p90, report.max), (easy, medium, hard));
+        assert!(report.mean > easy && report.mean < hard);
+        assert_eq!(report.outliers, vec![8, 10]);
+
+        let empty = difficulty_report(&[]);
+        assert_eq!(empty.histogram[0], (Difficulty::Easy, 0));
+        assert_eq!((empty.mean, empty.max), (0.0, 0.0));
+        assert!(empty.outliers.is_empty());
+    }
+
+    #[test]
+    fn check_percentile() {
+        let sorted = [1.0, 2.0, 3.0, 4.0];
+        assert_eq!(percentile(&sorted, 25), 1.0);
+        assert_eq!(percentile(&sorted, 50), 2.0);
+        assert_eq!(percentile(&sorted, 90), 4.0);
+        assert_eq!(percentile(&sorted, 100), 4.0);
+        assert_eq!(percentile(&[], 50), 0.0);
+    }
+
+    #[test]
+    fn check_requires_guessing() {
+        assert!(!requires_guessing(EASY));
+        assert!(!requires_guessing(EXPERT));
+        assert!(requires_guessing(HARD));
+        assert_eq!(guessing_point(EXPERT), None);
+
+        let board = guessing_point(HARD).unwrap();
+        assert!(!board.is_solved());
+        assert_eq!(full_solver(HARD).next_step(&board), None);
+
+        // Several solutions always need a guess.
+        assert!(requires_guessing([[0; 9]; 9]));
+
+        let mut puzzle = EASY;
+        puzzle[0][2] = 5;
+        assert!(!requires_guessing(puzzle));
+    }
+
+    #[test]
+    fn check_logic_coverage() {
+        assert_eq!(logic_coverage(EASY), 1.0);
+        assert_eq!(logic_coverage(EXPERT), 1.0);
+        assert_eq!(logic_coverage(HARD), 0.0);
+        assert_eq!(logic_coverage(solve_any(EASY).unwrap()), 1.0);
+
+        // Logic fills in 22 of the 58 blanks.
+        let partial = [
+            [0, 2, 0, 0, 0, 6, 0, 0, 0],
+            [7, 0, 0, 0, 2, 0, 0, 5, 6],
+            [0, 0, 0, 0, 0, 9, 1, 0, 0],
+            [0, 0, 0, 0, 4, 0, 9, 0, 0],
+            [6, 0, 7, 3, 0, 0, 0, 4, 0],
+            [0, 0, 0, 6, 0, 0, 0, 0, 2],
+            [0, 0, 1, 5, 0, 0, 0, 9, 8],
+            [0, 0, 0, 0, 3, 0, 0, 0, 0],
+            [5, 0, 0, 0, 0, 0, 0, 0, 1],
+        ];
+        assert_eq!(logic_coverage(partial), 22.0 / 58.0);
+
+        let mut puzzle = EASY;
+        puzzle[0][2] = 5;
+        assert_eq!(logic_coverage(puzzle), 0.0);
+    }
+
+    #[test]
+    fn check_requires_technique() {
+        let als = [
+            [4, 0, 0, 0, 2, 0, 0, 0, 0],
+            [0, 6, 0, 7, 4, 0, 0, 0, 0],
+            [9, 0, 5, 3, 0, 6, 0, 7, 0],
+            [8, 0, 0, 5, 0, 0, 0, 4, 0],
+            [0, 0, 9, 0, 0, 0, 0, 0, 1],
+            [0, 0, 0, 0, 1, 2, 6, 0, 7],
+            [0, 0, 0, 0, 0, 9, 0, 0, 6],
+            [5, 9, 0, 0, 0, 0, 0, 0, 0],
+            [0, 0, 0, 0, 0, 0, 0, 2, 0],
+        ];
+        assert!(requires_technique(als, Technique::AlsXz));
+        assert!(!requires_technique(als, Technique::XWing));
+
+        // Other techniques make up for pointing.
+        assert!(!requires_technique(MEDIUM, Technique::Pointing));
+        assert!(!requires_technique(EASY, Technique::Custom("oracle")));
+
+        // Not solved by logic at all.
+        assert!(!requires_technique(HARD, Technique::AlsXz));
+
+        let mut puzzle = EASY;
+        puzzle[0][2] = 5;
+        assert!(!requires_technique(puzzle, Technique::HiddenSingle));
+    }
+
+    #[test]
+    fn check_hardest_cell() {
+        let easy = hardest_cell(EASY).unwrap();
+        assert!(!easy.guessed);
+        assert_eq!(easy.rating, se_rating(EASY).unwrap());
+        assert_eq!(EASY[easy.pos.0][easy.pos.1], 0);
+
+        let hard = hardest_cell(HARD).unwrap();
+        assert!(hard.guessed);
+        assert!(hard.candidates >= 2);
+        assert_eq!(HARD[hard.pos.0][hard.pos.1], 0);
+
+        assert_eq!(hardest_cell(solve_any(EASY).unwrap()), None);
+        assert_eq!(hardest_cell([[0; 9]; 9]), None);
+    }
+
+    #[test]
+    fn check_search_rating() {
+        let easy = search_rating(EASY, 5, 1).unwrap();
+        assert_eq!(easy.guesses, 0.0);
+        assert_eq!(easy.score(), 0.0);
+
+        let hard = search_rating(HARD, 5, 1).unwrap();
+        assert!(hard.guesses > 0.0);
+        assert!(hard.score() > easy.score());
+        assert_eq!(search_rating(HARD, 5, 1), Some(hard));
+
+        assert_eq!(search_rating(EASY, 0, 1), None);
+        let mut puzzle = EASY;
+        puzzle[0][2] = 5;
+        assert_eq!(search_rating(puzzle, 5, 1), None);
+
+        // A search that runs out of nodes gives up, leaving the blanks empty.
+        let mut grid = HARD;
+        let mut counts = SearchCounts::default();
+        assert!(!sample_search(&mut grid, &mut Rng::new(1), &mut counts, 10));
+        assert_eq!(grid, HARD);
+    }
+
+    #[test]
+    fn check_estimate_solve_time() {
+        let casual = estimate_solve_time(EASY, SkillLevel::Casual).unwrap();
+        assert!(casual >= Duration::from_secs(6 * 51));
+        assert!(casual <= Duration::from_secs(20 * 51));
+        assert!(estimate_solve_time(EASY, SkillLevel::Beginner).unwrap() > casual);
+        assert!(estimate_solve_time(EASY, SkillLevel::Expert).unwrap() < casual);
+
+        // Harder techniques take longer than singles.
+        let medium = estimate_solve_time(MEDIUM, SkillLevel::Casual).unwrap();
+        assert!(medium >= Duration::from_secs(6 * 64 + 45));
+        assert!(estimate_solve_time(EXPERT, SkillLevel::Casual).is_some());
+
+        assert_eq!(estimate_solve_time(HARD, SkillLevel::Casual), None);
+    }
+}
@@ -0,0 +1,309 @@
+use std::fmt::Write;
+
+use crate::{
+    logic::{Board, Step},
+    sudoku::GridPos,
+};
+
+/// Size of a cell in an SVG drawing, in pixels.
+const CELL: f32 = 48.0;
+
+/// Radius of the circle drawn around a candidate in an SVG drawing.
+const RADIUS: f32 = 7.0;
+
+const BOLD_CYAN: &str = "\x1b[1;36m";
+const BOLD_RED: &str = "\x1b[1;31m";
+const RESET: &str = "\x1b[0m";
+
+/// Draws the links of a step, e.g. a chain or coloring, over a 13x13 grid (digits + border), to
+/// follow along with [crate::prelude::explain].
+///
+/// The cells on the links are lettered a, b, c, ... in the order the links visit them, cells that
+/// lose candidates are marked x, digits placed are shown as is and other blanks as dots. A legend
+/// below names the lettered cells. With color set, letters and x are highlighted with ANSI escapes
+/// for a terminal. Returns None if the step has no links.
+pub fn chain_to_border_string(board: &Board, step: &Step, color: bool) -> Option<String> {
+    let cells = chain_cells(step)?;
+
+    let paint = |text: char, escape: &str| match color {
+        true => format!("{escape}{text}{RESET}"),
+        false => text.to_string(),
+    };
+    let grid = board.grid();
+    let symbols: [[String; 9]; 9] = std::array::from_fn(|row| {
+        std::array::from_fn(|col| {
+            if let Some(index) = cells.iter().position(|&pos| pos == (row, col)) {
+                paint(label(index), BOLD_CYAN)
+            } else if step.eliminations.iter().any(|&(pos, _)| pos == (row, col)) {
+                paint('x', BOLD_RED)
+            } else if grid[row][col] != 0 {
+                grid[row][col].to_string()
+            } else {
+                ".".to_string()
+            }
+        })
+    });
+
+    let legend = cells
+        .iter()
+        .enumerate()
+        .map(|(index, &(row, col))| format!("{}=r{}c{}", label(index), row + 1, col + 1))
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    Some(format!("{}\n{legend}", border(&symbols)))
+}
+
+/// Draws the links of a step as an SVG image of the board, with every candidate pencilled in.
+///
+/// Candidates on the links are circled in blue and joined by arrows, solid for strong links and
+/// dashed for weak ones. Candidates the step removes are circled in red. Returns None if the step
+/// has no links.
+pub fn chain_to_svg(board: &Board, step: &Step) -> Option<String> {
+    chain_cells(step)?;
+
+    let size = 9.0 * CELL;
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{size}\" height=\"{size}\" \
+         viewBox=\"0 0 {size} {size}\" font-family=\"sans-serif\" text-anchor=\"middle\" \
+         dominant-baseline=\"central\">\n\
+         <defs><marker id=\"arrow\" viewBox=\"0 0 10 10\" refX=\"10\" refY=\"5\" \
+         markerWidth=\"6\" markerHeight=\"6\" orient=\"auto\">\
+         <path d=\"M0,0 L10,5 L0,10 z\" fill=\"#1565c0\"/></marker></defs>\n\
+         <rect width=\"{size}\" height=\"{size}\" fill=\"white\"/>\n"
+    );
+
+    // Nodes and eliminations go under the digits, links over them.
+    let mut nodes = Vec::new();
+    for link in &step.links {
+        for candidate in [link.from, link.to] {
+            if !nodes.contains(&candidate) {
+                nodes.push(candidate);
+            }
+        }
+    }
+    let circles = nodes
+        .into_iter()
+        .map(|candidate| (candidate, "#bbdefb"))
+        .chain(
+            step.eliminations
+                .iter()
+                .map(|&candidate| (candidate, "#ffcdd2")),
+        );
+    for (candidate, fill) in circles {
+        let (x, y) = center(candidate);
+        let _ = writeln!(
+            svg,
+            "<circle cx=\"{x}\" cy=\"{y}\" r=\"{RADIUS}\" fill=\"{fill}\"/>"
+        );
+    }
+
+    let grid = board.grid();
+    for (row, col) in (0..81).map(|index| (index / 9, index % 9)) {
+        let (x, y) = (col as f32 * CELL, row as f32 * CELL);
+        if grid[row][col] != 0 {
+            let _ = writeln!(
+                svg,
+                "<text x=\"{}\" y=\"{}\" font-size=\"28\">{}</text>",
+                x + CELL / 2.0,
+                y + CELL / 2.0,
+                grid[row][col]
+            );
+            continue;
+        }
+
+        let set = board.candidates((row, col));
+        for digit in (1..=9u8).filter(|digit| set & (1 << (digit - 1)) != 0) {
+            let (x, y) = center(((row, col), digit));
+            let _ = writeln!(
+                svg,
+                "<text x=\"{x}\" y=\"{y}\" font-size=\"11\" fill=\"#555\">{digit}</text>"
+            );
+        }
+    }
+
+    for index in 0..=9 {
+        let at = index as f32 * CELL;
+        let width = if index % 3 == 0 { 2.5 } else { 0.75 };
+        let _ = writeln!(
+            svg,
+            "<path d=\"M{at},0 V{size} M0,{at} H{size}\" stroke=\"black\" \
+             stroke-width=\"{width}\"/>"
+        );
+    }
+
+    for link in &step.links {
+        let (from_x, from_y) = center(link.from);
+        let (to_x, to_y) = center(link.to);
+        let (dx, dy) = (to_x - from_x, to_y - from_y);
+        let length = dx.hypot(dy).max(1.0);
+        let (dx, dy) = (dx / length * RADIUS, dy / length * RADIUS);
+        let dash = match link.strong {
+            true => "",
+            false => " stroke-dasharray=\"4 3\"",
+        };
+        let _ = writeln!(
+            svg,
+            "<line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\" stroke=\"#1565c0\" \
+             stroke-width=\"1.5\"{dash} marker-end=\"url(#arrow)\"/>",
+            from_x + dx,
+            from_y + dy,
+            to_x - dx,
+            to_y - dy
+        );
+    }
+
+    svg.push_str("</svg>\n");
+
+    Some(svg)
+}
+
+/// Lists the cells on the links of a step, in the order first visited. Returns None if there are
+/// no links.
+fn chain_cells(step: &Step) -> Option<Vec<GridPos>> {
+    if step.links.is_empty() {
+        return None;
+    }
+
+    let mut cells = Vec::new();
+    for link in &step.links {
+        for (pos, _) in [link.from, link.to] {
+            if !cells.contains(&pos) {
+                cells.push(pos);
+            }
+        }
+    }
+
+    Some(cells)
+}
+
+/// Letters the cells of a chain a to z, then * for the rest.
+fn label(index: usize) -> char {
+    match u8::try_from(index) {
+        Ok(index) if index < 26 => char::from(b'a' + index),
+        _ => '*',
+    }
+}
+
+/// Gets the middle of where a candidate is pencilled in, with digits 1-9 laid out 3x3 in a cell.
+fn center(((row, col), digit): (GridPos, u8)) -> (f32, f32) {
+    let slot = CELL / 3.0;
+    let index = usize::from(digit - 1);
+
+    (
+        col as f32 * CELL + (index % 3) as f32 * slot + slot / 2.0,
+        row as f32 * CELL + (index / 3) as f32 * slot + slot / 2.0,
+    )
+}
+
+/// Lays out a symbol for each cell as a 13x13 grid, as in
+/// [crate::prelude::solution_to_border_string].
+fn border(symbols: &[[String; 9]; 9]) -> String {
+    let row = |row: &[String; 9]| {
+        format!(
+            "║{}│{}│{}║\n",
+            row[..3].concat(),
+            row[3..6].concat(),
+            row[6..].concat()
+        )
+    };
+    let band = |band: &[[String; 9]]| band.iter().map(row).collect::<String>();
+
+    format!(
+        "╔═══╤═══╤═══╗\n{}╟───┼───┼───╢\n{}╟───┼───┼───╢\n{}╚═══╧═══╧═══╝",
+        band(&symbols[..3]),
+        band(&symbols[3..6]),
+        band(&symbols[6..]),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        logic::{Link, Technique},
+        sudoku::Puzzle,
+    };
+
+    use super::*;
+
+    const PUZZLE: Puzzle = [
+        [5, 3, 0, 0, 7, 0, 0, 0, 0],
+        [6, 0, 0, 1, 9, 5, 0, 0, 0],
+        [9, 8, 0, 0, 0, 0, 0, 6, 0],
+        [8, 0, 0, 0, 6, 0, 0, 0, 3],
+        [4, 0, 0, 8, 0, 3, 0, 0, 1],
+        [7, 0, 0, 0, 2, 0, 0, 0, 6],
+        [0, 6, 0, 0, 0, 0, 2, 8, 0],
+        [0, 0, 0, 4, 1, 9, 0, 0, 5],
+        [0, 0, 0, 0, 8, 0, 0, 7, 9],
+    ];
+
+    fn step() -> Step {
+        Step {
+            technique: Technique::XChain,
+            units: Vec::new(),
+            cells: vec![(0, 2), (2, 2), (2, 4)],
+            links: vec![
+                Link {
+                    from: ((0, 2), 1),
+                    to: ((2, 2), 1),
+                    strong: true,
+                },
+                Link {
+                    from: ((2, 2), 1),
+                    to: ((2, 4), 1),
+                    strong: false,
+                },
+            ],
+            eliminations: vec![((0, 5), 1)],
+            placement: None,
+        }
+    }
+
+    #[test]
+    fn check_chain_to_border_string() {
+        let board = Board::new(PUZZLE).unwrap();
+        let text = chain_to_border_string(&board, &step(), false).unwrap();
+        let lines = text.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 14);
+        assert_eq!(lines[1], "║53a│.7x│...║");
+        assert_eq!(lines[3], "║98b│.c.│.6.║");
+        assert_eq!(lines[13], "a=r1c3 b=r3c3 c=r3c5");
+
+        let colored = chain_to_border_string(&board, &step(), true).unwrap();
+        assert!(colored.contains("\x1b[1;36ma\x1b[0m"));
+        assert!(colored.contains("\x1b[1;31mx\x1b[0m"));
+
+        let no_links = Step {
+            links: Vec::new(),
+            ..step()
+        };
+        assert_eq!(chain_to_border_string(&board, &no_links, false), None);
+    }
+
+    #[test]
+    fn check_chain_to_svg() {
+        let board = Board::new(PUZZLE).unwrap();
+        let svg = chain_to_svg(&board, &step()).unwrap();
+        assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+        assert!(svg.ends_with("</svg>\n"));
+        assert_eq!(svg.matches("<line ").count(), 2);
+        assert_eq!(svg.matches("stroke-dasharray").count(), 1);
+        // Three nodes and one elimination.
+        assert_eq!(svg.matches("<circle ").count(), 4);
+        assert!(svg.contains("font-size=\"28\">5</text>"));
+
+        let no_links = Step {
+            links: Vec::new(),
+            ..step()
+        };
+        assert_eq!(chain_to_svg(&board, &no_links), None);
+    }
+
+    #[test]
+    fn check_label() {
+        assert_eq!(label(0), 'a');
+        assert_eq!(label(25), 'z');
+        assert_eq!(label(26), '*');
+    }
+}
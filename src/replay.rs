@@ -0,0 +1,166 @@
+use crate::{
+    logic::{Board, LogicalSolver, Step},
+    sudoku::Puzzle,
+};
+
+/// The steps of a logical solve together with the board after each of them, to move through a
+/// solve forward and backward, e.g. to animate it in a GUI.
+///
+/// Position n is the board after the first n steps, so position 0 is the initial board. Iterating
+/// moves forward, yielding the board at each position after the current one.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Replay {
+    steps: Vec<Step>,
+
+    /// The board at each position, one more than steps.
+    boards: Vec<Board>,
+
+    position: usize,
+}
+
+impl Replay {
+    /// Solves a puzzle with a solver and records the steps taken. Returns None if the puzzle is
+    /// invalid.
+    pub fn new(puzzle: Puzzle, solver: &LogicalSolver) -> Option<Replay> {
+        Replay::from_steps(puzzle, solver.solve(puzzle)?)
+    }
+
+    /// Records steps found elsewhere, e.g. by a player, starting from a puzzle. Returns None if the
+    /// puzzle is invalid.
+    pub fn from_steps(puzzle: Puzzle, steps: Vec<Step>) -> Option<Replay> {
+        let mut board = Board::new(puzzle)?;
+
+        let mut boards = Vec::with_capacity(steps.len() + 1);
+        boards.push(board);
+        for step in &steps {
+            board.apply(step);
+            boards.push(board);
+        }
+
+        Some(Replay {
+            steps,
+            boards,
+            position: 0,
+        })
+    }
+
+    /// Gets the steps, in order.
+    pub fn steps(&self) -> &[Step] {
+        &self.steps
+    }
+
+    /// Gets the number of steps.
+    pub fn len(&self) -> usize {
+        self.steps.len()
+    }
+
+    /// Checks whether there are no steps.
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+
+    /// Gets the current position, from 0 to [Replay::len].
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Gets the board at the current position.
+    pub fn board(&self) -> Board {
+        self.boards[self.position]
+    }
+
+    /// Gets the step that led to the current position, or None at the start.
+    pub fn last_step(&self) -> Option<&Step> {
+        self.steps.get(self.position.checked_sub(1)?)
+    }
+
+    /// Moves back one step, returning the board there, or None if already at the start.
+    pub fn prev(&mut self) -> Option<Board> {
+        self.position = self.position.checked_sub(1)?;
+        Some(self.board())
+    }
+
+    /// Moves to a position, returning the board there, or None if there is no such position, in
+    /// which case the current position is kept.
+    pub fn seek(&mut self, position: usize) -> Option<Board> {
+        let board = *self.boards.get(position)?;
+        self.position = position;
+        Some(board)
+    }
+}
+
+impl Iterator for Replay {
+    type Item = Board;
+
+    /// Moves forward one step, returning the board there, or None if already at the end.
+    fn next(&mut self) -> Option<Board> {
+        self.seek(self.position + 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::sudoku::solve_any;
+
+    use super::*;
+
+    const PUZZLE: Puzzle = [
+        [5, 3, 0, 0, 7, 0, 0, 0, 0],
+        [6, 0, 0, 1, 9, 5, 0, 0, 0],
+        [9, 8, 0, 0, 0, 0, 0, 6, 0],
+        [8, 0, 0, 0, 6, 0, 0, 0, 3],
+        [4, 0, 0, 8, 0, 3, 0, 0, 1],
+        [7, 0, 0, 0, 2, 0, 0, 0, 6],
+        [0, 6, 0, 0, 0, 0, 2, 8, 0],
+        [0, 0, 0, 4, 1, 9, 0, 0, 5],
+        [0, 0, 0, 0, 8, 0, 0, 7, 9],
+    ];
+
+    #[test]
+    fn check_replay() {
+        let mut replay = Replay::new(PUZZLE, &LogicalSolver::new()).unwrap();
+        let start = Board::new(PUZZLE).unwrap();
+        assert_eq!(replay.len(), 51);
+        assert_eq!(replay.position(), 0);
+        assert_eq!(replay.board(), start);
+        assert_eq!(replay.last_step(), None);
+        assert_eq!(replay.prev(), None);
+
+        let first = replay.next().unwrap();
+        assert_eq!(replay.position(), 1);
+        let ((row, col), digit) = replay.last_step().unwrap().placement.unwrap();
+        assert_eq!(first.grid()[row][col], digit);
+        assert_eq!(replay.prev(), Some(start));
+
+        let end = replay.seek(51).unwrap();
+        assert_eq!(Some(end.grid()), solve_any(PUZZLE));
+        assert_eq!(replay.next(), None);
+        assert_eq!(replay.seek(52), None);
+        assert_eq!(replay.position(), 51);
+
+        // Moving back and forth gives the same boards.
+        let middle = replay.seek(20).unwrap();
+        replay.prev();
+        assert_eq!(replay.next(), Some(middle));
+        assert_eq!(replay.seek(0), Some(start));
+        assert_eq!(replay.count(), 51);
+    }
+
+    #[test]
+    fn check_replay_from_steps() {
+        let steps = LogicalSolver::new().solve(PUZZLE).unwrap();
+        let replay = Replay::from_steps(PUZZLE, steps[..3].to_vec()).unwrap();
+        assert_eq!(replay.steps(), &steps[..3]);
+        assert_eq!(
+            replay.clone().last().map(|board| board.is_solved()),
+            Some(false)
+        );
+
+        let empty = Replay::from_steps(PUZZLE, Vec::new()).unwrap();
+        assert!(empty.is_empty());
+
+        let mut invalid = PUZZLE;
+        invalid[0][2] = 5;
+        assert_eq!(Replay::from_steps(invalid, steps), None);
+    }
+}
@@ -1,4 +1,26 @@
-use std::array;
+mod annealing;
+mod dlx;
+#[cfg(feature = "sat")]
+mod sat;
+#[cfg(all(feature = "simd", target_arch = "x86_64"))]
+mod simd;
+
+use std::{
+    array,
+    error::Error,
+    fmt,
+    num::NonZeroUsize,
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicBool, Ordering},
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::rng::Rng;
+
+pub use annealing::Schedule;
 
 /// 9x9 Sudoku grid in reading order.
 ///
@@ -9,52 +31,776 @@ pub type Solution = Grid;
 
 /// Finds all solutions to the given puzzle, if any.
 pub fn solve(puzzle: Puzzle) -> Vec<Solution> {
-    if !is_valid_puzzle(puzzle) {
-        return vec![];
+    solve_with_order(puzzle, DigitOrder::Ascending)
+}
+
+/// Finds a solution to the given puzzle, if any.
+pub fn solve_any(puzzle: Puzzle) -> Option<Solution> {
+    solve_any_with_order(puzzle, DigitOrder::Ascending)
+}
+
+/// Fills in the blanks of the given puzzle with a solution, without copying the grid. Returns
+/// whether a solution was found; if not, the puzzle is left unchanged.
+///
+/// Meant for solving in tight loops. Always backtracks, with none of the [Solver] options.
+pub fn solve_in_place(puzzle: &mut Puzzle) -> bool {
+    let Some(mut bits) = BitGrid::from_puzzle(*puzzle) else {
+        return false;
+    };
+
+    if !fill_in_place(&mut bits) {
+        return false;
     }
 
-    let blanks = blanks(puzzle);
-    if blanks.is_empty() {
-        return vec![puzzle];
+    for info in &CELLS {
+        puzzle[info.row][info.col] = bits.digit(info.row, info.col);
     }
+    true
+}
 
-    let mut solutions = Vec::new();
-    find_solutions(puzzle, 0, &blanks, &mut solutions);
-    solutions
+/// Lazily finds the solutions to the given puzzle, one at a time, so that the first few solutions
+/// of an under-constrained grid can be looked at without finding them all.
+pub fn solve_iter(puzzle: Puzzle) -> impl Iterator<Item = Solution> {
+    Solver::new().iter(puzzle)
 }
 
-/// Finds a solution to the given puzzle, if any.
-pub fn solve_any(puzzle: Puzzle) -> Option<Solution> {
-    if !is_valid_puzzle(puzzle) {
-        return None;
+/// Finds a solution to the given puzzle, if any, trying the digits of each blank in a random
+/// order. An under-constrained puzzle gives a different solution for different seeds, but always
+/// the same solution for the same seed.
+pub fn solve_random(puzzle: Puzzle, seed: u64) -> Option<Solution> {
+    Solver::new().seed(seed).solve(puzzle).solution()
+}
+
+/// Outcome of a solve that may give up before finishing.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SolveOutcome {
+    Solved(Solution),
+    NoSolution,
+
+    /// The time ran out before a solution was found.
+    TimedOut,
+
+    /// The search was cancelled through a [CancelToken] before a solution was found.
+    Cancelled,
+
+    /// The search visited the maximum number of grids allowed before a solution was found.
+    NodeLimit,
+
+    /// The backend gave up without trying every grid, so there may still be a solution. Only
+    /// [Backend::Annealing] does this.
+    GaveUp,
+}
+
+impl SolveOutcome {
+    /// Gets the solution, if one was found.
+    pub fn solution(self) -> Option<Solution> {
+        match self {
+            SolveOutcome::Solved(solution) => Some(solution),
+            _ => None,
+        }
+    }
+}
+
+impl From<Interruption> for SolveOutcome {
+    fn from(interruption: Interruption) -> SolveOutcome {
+        match interruption {
+            Interruption::TimedOut => SolveOutcome::TimedOut,
+            Interruption::Cancelled => SolveOutcome::Cancelled,
+            Interruption::NodeLimit => SolveOutcome::NodeLimit,
+            Interruption::GaveUp => SolveOutcome::GaveUp,
+        }
+    }
+}
+
+/// Why a search gave up before finishing.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Interruption {
+    TimedOut,
+    Cancelled,
+    NodeLimit,
+
+    /// The backend does not try every grid, so cannot tell how many solutions there are.
+    GaveUp,
+}
+
+impl fmt::Display for Interruption {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Interruption::TimedOut => write!(f, "search timed out"),
+            Interruption::Cancelled => write!(f, "search cancelled"),
+            Interruption::NodeLimit => write!(f, "search reached its node limit"),
+            Interruption::GaveUp => write!(f, "search gave up without trying every grid"),
+        }
+    }
+}
+
+impl Error for Interruption {}
+
+/// Finds a solution to the given puzzle, if any, giving up once timeout has passed.
+pub fn solve_with_timeout(puzzle: Puzzle, timeout: Duration) -> SolveOutcome {
+    Solver::new().timeout(timeout).solve(puzzle)
+}
+
+/// Lets another thread, e.g. a GUI or a request handler, abort a running search. Clones share the
+/// same flag.
+#[derive(Clone, Debug, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    pub fn new() -> CancelToken {
+        CancelToken::default()
     }
 
-    let blanks = blanks(puzzle);
-    if blanks.is_empty() {
-        return Some(puzzle);
+    /// Asks every search using this token to stop as soon as possible.
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
     }
 
-    find_solution(puzzle, 0, &blanks)
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// Error returned when a search is cancelled through a [CancelToken].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Cancelled;
+
+impl fmt::Display for Cancelled {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "search cancelled")
+    }
+}
+
+impl Error for Cancelled {}
+
+/// Finds a solution to the given puzzle, if any, giving up once token is cancelled.
+pub fn solve_cancellable(puzzle: Puzzle, token: &CancelToken) -> SolveOutcome {
+    Solver::new().cancel_token(token.clone()).solve(puzzle)
+}
+
+/// Counts the solutions to the given puzzle like [count_solutions], giving up once token is
+/// cancelled.
+pub fn count_solutions_cancellable(
+    puzzle: Puzzle,
+    limit: Option<usize>,
+    token: &CancelToken,
+) -> Result<usize, Cancelled> {
+    Solver::new()
+        .cancel_token(token.clone())
+        .count(puzzle, limit)
+        .map_err(|_| Cancelled)
+}
+
+/// Statistics of a search, for comparing heuristics and roughly gauging how hard a puzzle is.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SolveStats {
+    /// Number of grids visited, including the puzzle itself.
+    pub nodes: usize,
+
+    /// Number of guesses that led to a dead end.
+    pub backtracks: usize,
+
+    /// Largest number of guesses in effect at once.
+    pub max_depth: usize,
+
+    /// Number of digits filled in as singles rather than guessed.
+    pub propagated: usize,
+
+    pub elapsed: Duration,
+}
+
+/// Finds a solution to the given puzzle, if any, together with statistics of the search.
+pub fn solve_with_stats(puzzle: Puzzle) -> (Option<Solution>, SolveStats) {
+    let (outcome, stats) = Solver::new().solve_with_stats(puzzle);
+    (outcome.solution(), stats)
+}
+
+/// Order in which the solver tries the candidates of a blank.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DigitOrder {
+    /// 1 to 9.
+    #[default]
+    Ascending,
+
+    /// Least constraining value first: the digit that is a candidate in the fewest blank peers,
+    /// i.e. that rules out the fewest options elsewhere. Ties go to the lower digit.
+    LeastConstraining,
+}
+
+/// Finds all solutions to the given puzzle, if any, trying digits in the given order.
+pub fn solve_with_order(puzzle: Puzzle, order: DigitOrder) -> Vec<Solution> {
+    Solver::new().order(order).iter(puzzle).collect()
+}
+
+/// Finds a solution to the given puzzle, if any, trying digits in the given order.
+pub fn solve_any_with_order(puzzle: Puzzle, order: DigitOrder) -> Option<Solution> {
+    Solver::new().order(order).solve(puzzle).solution()
 }
 
 /// Verifies whether a puzzle has exactly one solution.
 pub fn has_unique_solution(puzzle: Puzzle) -> bool {
+    matches!(uniqueness(puzzle), Uniqueness::One(_))
+}
+
+/// Whether a puzzle has no solution, exactly one, or more than one.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Uniqueness {
+    Zero,
+    One(Solution),
+    Multiple,
+}
+
+/// Checks whether a puzzle has exactly one solution, and gets it if so. The search stops as soon
+/// as a second solution turns up.
+pub fn uniqueness(puzzle: Puzzle) -> Uniqueness {
+    Solver::new()
+        .uniqueness(puzzle)
+        .expect("search without limits should finish")
+}
+
+/// Counts the solutions to the given puzzle, stopping early once limit is reached, if given.
+///
+/// A limit of 2 is enough to tell whether a puzzle has no solution, exactly one, or more than one.
+pub fn count_solutions(puzzle: Puzzle, limit: Option<usize>) -> usize {
+    Solver::new()
+        .count(puzzle, limit)
+        .expect("search without limits should finish")
+}
+
+/// Puzzles with fewer givens than this are solved with [Backend::Dlx] by [Backend::Auto].
+const FEW_GIVENS: usize = 22;
+
+/// Largest number of solutions counted by [count_solutions_exact], which takes several seconds.
+const EXACT_COUNT_LIMIT: u64 = 10_000_000;
+
+/// Counts every solution to the given puzzle, with dancing links. Fast enough for grids with very
+/// few givens, but gives up beyond ten million solutions; see
+/// [count_solutions_exact_with_limit].
+pub fn count_solutions_exact(puzzle: Puzzle) -> Result<u64, TooManySolutions> {
+    count_solutions_exact_with_limit(puzzle, EXACT_COUNT_LIMIT)
+}
+
+/// Counts every solution to the given puzzle, with dancing links, giving up once there turn out
+/// to be more than limit.
+///
+/// Counting takes time in proportion to the number of solutions, so a limit is always needed: the
+/// empty grid has about 6.67e21 solutions, more than even a u64 holds.
+pub fn count_solutions_exact_with_limit(
+    puzzle: Puzzle,
+    limit: u64,
+) -> Result<u64, TooManySolutions> {
     if !is_valid_puzzle(puzzle) {
-        return false;
+        return Ok(0);
     }
 
-    let blanks = blanks(puzzle);
-    if blanks.is_empty() {
-        return true;
+    dlx::count_up_to(puzzle, limit).ok_or(TooManySolutions { limit })
+}
+
+/// Error returned when a puzzle has more solutions than can be counted.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TooManySolutions {
+    /// The number of solutions counted before giving up.
+    pub limit: u64,
+}
+
+impl fmt::Display for TooManySolutions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "more than {} solutions", self.limit)
+    }
+}
+
+impl Error for TooManySolutions {}
+
+/// Algorithm used to search for solutions.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Backend {
+    /// [Backend::Dlx] for puzzles with few givens, such as near-empty grids and puzzles built to
+    /// defeat backtracking, which can take minutes to backtrack through. [Backend::Propagation]
+    /// otherwise, and whenever an option that only backtracking supports is set, or solutions
+    /// are found lazily. Pick another backend to force a specific algorithm.
+    #[default]
+    Auto,
+
+    /// Plain backtracking over the blanks in reading order. Slow, but simple enough to serve as
+    /// the reference implementation.
+    Backtracking,
+
+    /// Propagation of singles, then backtracking on the blank with the fewest candidates.
+    Propagation,
+
+    /// Exact cover with dancing links (Algorithm X). Much faster for counting solutions and for
+    /// near-empty grids.
+    Dlx,
+
+    /// Encoding as boolean satisfiability, solved by a bundled DPLL solver. Slow, but useful for
+    /// research and as an independent check.
+    #[cfg(feature = "sat")]
+    Sat,
+
+    /// Simulated annealing: fills in the blanks at random, then swaps digits around to minimize
+    /// the conflicts, following a [Schedule]. Not guaranteed to find a solution even if there is
+    /// one, and finds at most one, so it cannot tell whether a solution is unique. Useful for
+    /// teaching, and for grids with very few givens.
+    Annealing,
+}
+
+/// Finds all solutions to the given puzzle, if any, using the given backend.
+pub fn solve_with(backend: Backend, puzzle: Puzzle) -> Vec<Solution> {
+    Solver::new()
+        .backend(backend)
+        .solve_up_to(puzzle, usize::MAX)
+}
+
+/// Verifies whether a puzzle has exactly one solution, using the given backend. Always false with
+/// [Backend::Annealing], which cannot tell.
+pub fn has_unique_solution_with(backend: Backend, puzzle: Puzzle) -> bool {
+    matches!(
+        Solver::new().backend(backend).uniqueness(puzzle),
+        Ok(Uniqueness::One(_))
+    )
+}
+
+/// Order in which a [Solver] finds the solutions of a puzzle with more than one.
+///
+/// Both orders are deterministic: the same options give the same order on every run.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SolutionOrder {
+    /// Whatever order the search finds them in. May change between versions as the search
+    /// improves.
+    #[default]
+    Fastest,
+
+    /// Lexicographic order of the digits in reading order, guaranteed to stay the same across
+    /// versions, so that outputs can be diffed. The search branches on blanks in reading order,
+    /// so this can be slower.
+    Lexicographic,
+}
+
+/// Solves a puzzle with two backends, and checks that they find the same solutions, e.g. to test
+/// a new algorithm against [Backend::Backtracking]. Returns the solutions in lexicographic order.
+///
+/// Every solution is found, so only use this with puzzles that have a few.
+///
+/// # Panics
+///
+/// Panics if the backends disagree.
+pub fn compare(first: Backend, second: Backend, puzzle: Puzzle) -> Vec<Solution> {
+    let mut first_solutions = solve_with(first, puzzle);
+    let mut second_solutions = solve_with(second, puzzle);
+    first_solutions.sort();
+    second_solutions.sort();
+
+    assert_eq!(
+        first_solutions, second_solutions,
+        "{first:?} and {second:?} backends disagree"
+    );
+
+    first_solutions
+}
+
+/// A solver with configurable options. The free functions are shortcuts for common
+/// configurations.
+///
+/// The timeout, node limit, seed, digit order, heuristics and cancel token only apply to the
+/// backtracking backends, [Backend::Propagation] and [Backend::Backtracking], and make
+/// [Backend::Auto] backtrack. The heuristics are always off with [Backend::Backtracking].
+/// [Backend::Annealing] uses the seed and the schedule.
+#[derive(Clone, Debug)]
+pub struct Solver {
+    backend: Backend,
+    order: DigitOrder,
+    heuristics: bool,
+    timeout: Option<Duration>,
+    max_nodes: Option<usize>,
+    seed: Option<u64>,
+    cancel: Option<CancelToken>,
+    solution_order: SolutionOrder,
+    forward_checking: bool,
+    schedule: Schedule,
+}
+
+impl Solver {
+    /// Sets up a solver with the default options: [Backend::Auto], with heuristics, digits in
+    /// ascending order, and no limits.
+    pub fn new() -> Solver {
+        Solver {
+            backend: Backend::Auto,
+            order: DigitOrder::Ascending,
+            heuristics: true,
+            timeout: None,
+            max_nodes: None,
+            seed: None,
+            cancel: None,
+            solution_order: SolutionOrder::Fastest,
+            forward_checking: false,
+            schedule: Schedule::default(),
+        }
+    }
+
+    pub fn backend(self, backend: Backend) -> Solver {
+        Solver { backend, ..self }
+    }
+
+    pub fn order(self, order: DigitOrder) -> Solver {
+        Solver { order, ..self }
+    }
+
+    /// Turns filling in singles and branching on the blank with the fewest candidates on or off.
+    /// Without them, blanks are simply tried in reading order.
+    pub fn heuristics(self, heuristics: bool) -> Solver {
+        Solver { heuristics, ..self }
+    }
+
+    /// Gives up once this much time has passed since the start of a search.
+    pub fn timeout(self, timeout: Duration) -> Solver {
+        Solver {
+            timeout: Some(timeout),
+            ..self
+        }
+    }
+
+    /// Gives up once a search has visited this many grids.
+    pub fn max_nodes(self, max_nodes: usize) -> Solver {
+        Solver {
+            max_nodes: Some(max_nodes),
+            ..self
+        }
+    }
+
+    /// Tries the digits of each blank in an order shuffled by a random number generator with this
+    /// seed, instead of the digit order.
+    pub fn seed(self, seed: u64) -> Solver {
+        Solver {
+            seed: Some(seed),
+            ..self
+        }
+    }
+
+    /// Sets the order in which solutions are found. [SolutionOrder::Lexicographic] overrides the
+    /// digit order and the seed.
+    pub fn solution_order(self, solution_order: SolutionOrder) -> Solver {
+        Solver {
+            solution_order,
+            ..self
+        }
+    }
+
+    /// Turns forward checking on or off: after each guess, checks whether any peer of the guessed
+    /// blank is left without candidates, and if so, drops the guess straight away. This is a
+    /// cheaper check than propagation, so it mostly helps when the heuristics are off.
+    pub fn forward_checking(self, forward_checking: bool) -> Solver {
+        Solver {
+            forward_checking,
+            ..self
+        }
+    }
+
+    /// Sets the temperature schedule of [Backend::Annealing].
+    pub fn schedule(self, schedule: Schedule) -> Solver {
+        Solver { schedule, ..self }
+    }
+
+    /// Gives up once this token is cancelled.
+    pub fn cancel_token(self, token: CancelToken) -> Solver {
+        Solver {
+            cancel: Some(token),
+            ..self
+        }
+    }
+
+    /// Finds a solution to the given puzzle, if any.
+    pub fn solve(&self, puzzle: Puzzle) -> SolveOutcome {
+        if self.backend == Backend::Auto {
+            return self.resolve(puzzle).solve(puzzle);
+        }
+
+        self.solve_with_stats(puzzle).0
+    }
+
+    /// Finds a solution to the given puzzle, if any, together with statistics of the search.
+    ///
+    /// Backends other than the backtracking ones only report the elapsed time, so
+    /// [Backend::Auto] always backtracks here, with [Backend::Propagation].
+    pub fn solve_with_stats(&self, puzzle: Puzzle) -> (SolveOutcome, SolveStats) {
+        if self.backend == Backend::Auto {
+            return self
+                .clone()
+                .backend(Backend::Propagation)
+                .solve_with_stats(puzzle);
+        }
+
+        let start = Instant::now();
+        if !self.backtracks() {
+            let outcome = match self.solve_up_to(puzzle, 1)[..] {
+                [solution] => SolveOutcome::Solved(solution),
+                _ if self.gives_up(puzzle) => SolveOutcome::GaveUp,
+                _ => SolveOutcome::NoSolution,
+            };
+            let stats = SolveStats {
+                elapsed: start.elapsed(),
+                ..SolveStats::default()
+            };
+            return (outcome, stats);
+        }
+
+        let Some(mut search) = self.search(puzzle) else {
+            return (SolveOutcome::NoSolution, SolveStats::default());
+        };
+
+        let outcome = match search.next() {
+            Some(solution) => SolveOutcome::Solved(solution),
+            None => search
+                .interrupted
+                .map_or(SolveOutcome::NoSolution, SolveOutcome::from),
+        };
+        let stats = SolveStats {
+            elapsed: start.elapsed(),
+            ..search.stats
+        };
+        (outcome, stats)
+    }
+
+    /// Counts the solutions to the given puzzle, stopping early once limit is reached, if given.
+    ///
+    /// [Backend::Annealing] finds at most one solution, so gives [Interruption::GaveUp] unless it
+    /// reaches the limit.
+    pub fn count(&self, puzzle: Puzzle, limit: Option<usize>) -> Result<usize, Interruption> {
+        if self.backend == Backend::Auto {
+            return self.resolve(puzzle).count(puzzle, limit);
+        }
+
+        let limit = limit.unwrap_or(usize::MAX);
+        if !self.backtracks() {
+            let count = self.solve_up_to(puzzle, limit).len();
+            if count < limit && self.gives_up(puzzle) {
+                return Err(Interruption::GaveUp);
+            }
+            return Ok(count);
+        }
+
+        let Some(mut search) = self.search(puzzle) else {
+            return Ok(0);
+        };
+
+        let count = search.by_ref().take(limit).count();
+        match search.interrupted {
+            Some(interruption) => Err(interruption),
+            None => Ok(count),
+        }
+    }
+
+    /// Checks whether the given puzzle has exactly one solution, and gets it if so. The search
+    /// stops as soon as a second solution turns up.
+    ///
+    /// [Backend::Annealing] finds at most one solution, so gives [Interruption::GaveUp] for any
+    /// valid puzzle.
+    pub fn uniqueness(&self, puzzle: Puzzle) -> Result<Uniqueness, Interruption> {
+        if self.backend == Backend::Auto {
+            return self.resolve(puzzle).uniqueness(puzzle);
+        }
+        if self.gives_up(puzzle) {
+            return Err(Interruption::GaveUp);
+        }
+
+        let solutions = if self.backtracks() {
+            let Some(mut search) = self.search(puzzle) else {
+                return Ok(Uniqueness::Zero);
+            };
+
+            let solutions = search.by_ref().take(2).collect::<Vec<_>>();
+            if let Some(interruption) = search.interrupted {
+                return Err(interruption);
+            }
+            solutions
+        } else {
+            self.solve_up_to(puzzle, 2)
+        };
+
+        Ok(match solutions[..] {
+            [] => Uniqueness::Zero,
+            [solution] => Uniqueness::One(solution),
+            _ => Uniqueness::Multiple,
+        })
+    }
+
+    /// Lazily finds the solutions to the given puzzle, one at a time. Stops early if the search
+    /// gives up.
+    ///
+    /// Backends other than the backtracking ones find all the solutions up front.
+    /// [Backend::Auto] always backtracks here.
+    pub fn iter(&self, puzzle: Puzzle) -> Box<dyn Iterator<Item = Solution>> {
+        if !self.backtracks() {
+            return Box::new(self.solve_up_to(puzzle, usize::MAX).into_iter());
+        }
+
+        Box::new(self.search(puzzle).into_iter().flatten())
+    }
+
+    /// Finds up to limit solutions to the given puzzle.
+    fn solve_up_to(&self, puzzle: Puzzle, limit: usize) -> Vec<Solution> {
+        match self.backend {
+            Backend::Auto => self.resolve(puzzle).solve_up_to(puzzle, limit),
+            Backend::Backtracking | Backend::Propagation => self
+                .search(puzzle)
+                .into_iter()
+                .flatten()
+                .take(limit)
+                .collect(),
+            Backend::Dlx if is_valid_puzzle(puzzle) => dlx::solve_up_to(puzzle, limit),
+            #[cfg(feature = "sat")]
+            Backend::Sat => sat::solve_up_to(puzzle, limit),
+            Backend::Annealing if limit > 0 && is_valid_puzzle(puzzle) => {
+                annealing::solve(puzzle, self.schedule, self.seed.unwrap_or(0))
+                    .into_iter()
+                    .collect()
+            }
+            Backend::Dlx | Backend::Annealing => vec![],
+        }
+    }
+
+    /// Checks whether the backend may give up on a puzzle without trying every grid, so that not
+    /// finding a solution does not mean there is none.
+    fn gives_up(&self, puzzle: Puzzle) -> bool {
+        self.backend == Backend::Annealing && is_valid_puzzle(puzzle)
+    }
+
+    /// Checks whether the backend is run by a [Search].
+    fn backtracks(&self) -> bool {
+        matches!(
+            self.backend,
+            Backend::Auto | Backend::Backtracking | Backend::Propagation
+        )
+    }
+
+    /// Gets a copy of the solver with the backend that [Backend::Auto] picks for a puzzle.
+    fn resolve(&self, puzzle: Puzzle) -> Solver {
+        let backtracking_only = self.timeout.is_some()
+            || self.max_nodes.is_some()
+            || self.seed.is_some()
+            || self.cancel.is_some()
+            || self.order != DigitOrder::Ascending
+            || self.solution_order != SolutionOrder::Fastest
+            || self.forward_checking
+            || !self.heuristics;
+        let givens = puzzle.iter().flatten().filter(|&&digit| digit != 0).count();
+
+        let backend = if !backtracking_only && givens < FEW_GIVENS {
+            Backend::Dlx
+        } else {
+            Backend::Propagation
+        };
+        self.clone().backend(backend)
+    }
+
+    /// Sets up a backtracking search for a puzzle. Returns None if the puzzle is invalid.
+    fn search(&self, puzzle: Puzzle) -> Option<Search> {
+        let board = Board::new(puzzle)?;
+
+        let lexicographic = self.solution_order == SolutionOrder::Lexicographic;
+
+        Some(Search {
+            order: if lexicographic {
+                DigitOrder::Ascending
+            } else {
+                self.order
+            },
+            heuristics: self.heuristics && self.backend != Backend::Backtracking,
+            reading_order: lexicographic,
+            forward_checking: self.forward_checking,
+            rng: self.seed.filter(|_| !lexicographic).map(Rng::new),
+            deadline: self
+                .timeout
+                .and_then(|timeout| Instant::now().checked_add(timeout)),
+            max_nodes: self.max_nodes,
+            cancel: self.cancel.clone(),
+            ..Search::new(board, DigitOrder::Ascending)
+        })
+    }
+}
+
+impl Default for Solver {
+    fn default() -> Self {
+        Solver::new()
+    }
+}
+
+/// What a puzzle's givens lead to.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Classification {
+    /// The givens already break the rules.
+    InvalidGivens(Vec<Conflict>),
+
+    /// The givens are valid, but cannot be completed.
+    NoSolution,
+
+    Unique(Solution),
+
+    /// The number of solutions found before reaching the limit (so at least 2).
+    Multiple(usize),
+}
+
+/// Classifies a puzzle by its number of solutions, stopping as soon as a second one is found.
+pub fn classify(puzzle: Puzzle) -> Classification {
+    classify_with_limit(puzzle, 2)
+}
+
+/// Classifies a puzzle by its number of solutions, counting up to limit (at least 2) solutions.
+pub fn classify_with_limit(puzzle: Puzzle, limit: usize) -> Classification {
+    let conflicts = find_conflicts(puzzle);
+    if !conflicts.is_empty() {
+        return Classification::InvalidGivens(conflicts);
+    }
+
+    let solutions = solve_up_to(puzzle, limit.max(2));
+    match solutions[..] {
+        [] => Classification::NoSolution,
+        [solution] => Classification::Unique(solution),
+        _ => Classification::Multiple(solutions.len()),
     }
+}
+
+/// Finds up to limit solutions to the given puzzle.
+pub(crate) fn solve_up_to(puzzle: Puzzle, limit: usize) -> Vec<Solution> {
+    let Some(board) = Board::new(puzzle) else {
+        return vec![];
+    };
 
-    let mut count_cache = 0;
-    count_solutions(puzzle, 0, &blanks, &mut count_cache);
-    count_cache == 1
+    let mut solutions = Vec::new();
+    find_solutions(board, DigitOrder::Ascending, limit, &mut solutions);
+    solutions
+}
+
+/// Verifies whether a candidate is a solution to a puzzle, e.g. to grade a submission: every cell
+/// is filled in, no digit repeats in a unit, and every given of the puzzle is kept.
+///
+/// The candidate need not be the solution found by the solver, so this also accepts any one of the
+/// solutions of a puzzle with more than one.
+pub fn verify_solution(puzzle: Puzzle, candidate: Solution) -> bool {
+    let complete = candidate
+        .iter()
+        .flatten()
+        .all(|digit| (1..=9).contains(digit));
+    let keeps_givens = puzzle
+        .iter()
+        .flatten()
+        .zip(candidate.iter().flatten())
+        .all(|(&given, &digit)| given == 0 || given == digit);
+
+    complete && keeps_givens && is_valid_puzzle(candidate)
 }
 
 /// Verifies whether a puzzle is valid -- all digits are in legal positions.
-fn is_valid_puzzle(puzzle: Puzzle) -> bool {
+pub(crate) fn is_valid_puzzle(puzzle: Puzzle) -> bool {
+    #[cfg(all(feature = "simd", target_arch = "x86_64"))]
+    return simd::is_valid_puzzle(puzzle);
+
+    #[cfg(not(all(feature = "simd", target_arch = "x86_64")))]
+    slices_are_valid(puzzle)
+}
+
+/// Verifies whether a puzzle is valid one row, col and square at a time.
+#[cfg_attr(all(feature = "simd", target_arch = "x86_64"), allow(dead_code))]
+fn slices_are_valid(puzzle: Puzzle) -> bool {
     (0..9).all(|index| {
         slice_has_unique_digits(horizontal_slice(puzzle, index))
             && slice_has_unique_digits(vertical_slice(puzzle, index))
@@ -62,6 +808,104 @@ fn is_valid_puzzle(puzzle: Puzzle) -> bool {
     })
 }
 
+/// A row, col or square -- 9 cells that must hold unique digits.
+///
+/// Rows and cols are indexed 0-8 from the top and left respectively. Squares are indexed 0-8 in
+/// reading order.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Unit {
+    Row(usize),
+    Col(usize),
+    Square(usize),
+}
+
+impl Unit {
+    /// All 27 units: rows, then cols, then squares.
+    pub fn all() -> impl Iterator<Item = Unit> {
+        (0..9)
+            .map(Unit::Row)
+            .chain((0..9).map(Unit::Col))
+            .chain((0..9).map(Unit::Square))
+    }
+
+    /// Gets the positions of the cells in this unit, in reading order.
+    pub fn positions(self) -> [GridPos; 9] {
+        match self {
+            Unit::Row(row) => array::from_fn(|col| (row, col)),
+            Unit::Col(col) => array::from_fn(|row| (row, col)),
+            Unit::Square(square) => SQUARES[square],
+        }
+    }
+}
+
+/// A digit that appears more than once in a unit.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Conflict {
+    pub digit: u8,
+    pub unit: Unit,
+
+    /// Every cell in the unit that holds the digit, in reading order.
+    pub positions: Vec<GridPos>,
+}
+
+/// Finds the givens that break the rules, i.e. the reasons why a puzzle has no solution at all.
+///
+/// A pair of cells that clash in both a row (or col) and a square is reported once for each unit.
+pub fn find_conflicts(puzzle: Puzzle) -> Vec<Conflict> {
+    Unit::all()
+        .flat_map(|unit| {
+            (1..=9).filter_map(move |digit| {
+                let positions = unit
+                    .positions()
+                    .into_iter()
+                    .filter(|&(row, col)| puzzle[row][col] == digit)
+                    .collect::<Vec<_>>();
+
+                if positions.len() > 1 {
+                    Some(Conflict {
+                        digit,
+                        unit,
+                        positions,
+                    })
+                } else {
+                    None
+                }
+            })
+        })
+        .collect()
+}
+
+/// Finds each pair of cells that share a unit and hold the same digit, so that the offending
+/// cells can be pointed out. Unlike [find_conflicts], a pair that clashes in more than one unit is
+/// reported once.
+///
+/// Pairs are in reading order of their first cell, then of their second cell.
+pub fn find_conflicting_pairs(puzzle: Puzzle) -> Vec<(GridPos, GridPos, u8)> {
+    let mut pairs = Vec::new();
+    for info in &CELLS {
+        let digit = puzzle[info.row][info.col];
+        if digit == 0 {
+            continue;
+        }
+
+        let mut clashes = info
+            .peers
+            .iter()
+            .copied()
+            .filter(|&pos| pos > (info.row, info.col) && puzzle[pos.0][pos.1] == digit)
+            .collect::<Vec<_>>();
+        clashes.sort();
+
+        pairs.extend(
+            clashes
+                .into_iter()
+                .map(|pos| ((info.row, info.col), pos, digit)),
+        );
+    }
+
+    pairs
+}
+
 /// Verifies whether a slice has all unique digits, except 0, which is ignored.
 fn slice_has_unique_digits(slice: [u8; 9]) -> bool {
     let mut unique_digits = [false; 9];
@@ -83,10 +927,10 @@ fn slice_has_unique_digits(slice: [u8; 9]) -> bool {
 }
 
 /// (row, col)
-type GridPos = (usize, usize);
+pub type GridPos = (usize, usize);
 
 /// Finds all the blank positions in a [Puzzle] that need to be filled in to form a [Solution].
-fn blanks(puzzle: Puzzle) -> Vec<GridPos> {
+pub(crate) fn blanks(puzzle: Puzzle) -> Vec<GridPos> {
     puzzle
         .iter()
         .enumerate()
@@ -100,6 +944,109 @@ fn blanks(puzzle: Puzzle) -> Vec<GridPos> {
         .collect()
 }
 
+/// Set of digits, with bit (digit - 1) set for each digit in the set.
+pub(crate) type DigitSet = u16;
+
+/// Precomputed facts about a cell.
+pub(crate) struct CellInfo {
+    pub(crate) row: usize,
+    pub(crate) col: usize,
+    pub(crate) square: usize,
+
+    /// The 20 other cells that share a row, col or square with this cell, in reading order.
+    pub(crate) peers: [GridPos; 20],
+}
+
+/// Facts about each cell, indexed by row * 9 + col.
+pub(crate) static CELLS: [CellInfo; 81] = cell_table();
+
+/// The cells of each square, in reading order.
+pub(crate) static SQUARES: [[GridPos; 9]; 9] = square_table();
+
+/// Gets the facts about a cell.
+pub(crate) fn cell((row, col): GridPos) -> &'static CellInfo {
+    &CELLS[row * 9 + col]
+}
+
+const fn cell_table() -> [CellInfo; 81] {
+    let mut cells = [const {
+        CellInfo {
+            row: 0,
+            col: 0,
+            square: 0,
+            peers: [(0, 0); 20],
+        }
+    }; 81];
+
+    let mut index = 0;
+    while index < 81 {
+        let (row, col) = (index / 9, index % 9);
+        cells[index].row = row;
+        cells[index].col = col;
+        cells[index].square = row / 3 * 3 + col / 3;
+
+        let mut count = 0;
+        let mut other = 0;
+        while other < 81 {
+            let (other_row, other_col) = (other / 9, other % 9);
+            let same_square = other_row / 3 == row / 3 && other_col / 3 == col / 3;
+            if other != index && (other_row == row || other_col == col || same_square) {
+                cells[index].peers[count] = (other_row, other_col);
+                count += 1;
+            }
+
+            other += 1;
+        }
+
+        index += 1;
+    }
+
+    cells
+}
+
+const fn square_table() -> [[GridPos; 9]; 9] {
+    let mut squares = [[(0, 0); 9]; 9];
+
+    let mut square = 0;
+    while square < 9 {
+        let mut index = 0;
+        while index < 9 {
+            squares[square][index] = (square / 3 * 3 + index / 3, square % 3 * 3 + index % 3);
+            index += 1;
+        }
+
+        square += 1;
+    }
+
+    squares
+}
+
+/// Finds the digits that can legally go in each blank of a [Puzzle], e.g. to fill in pencil marks,
+/// with bit (digit - 1) set for each digit. Givens have no candidates.
+pub fn candidates(puzzle: Puzzle) -> [[DigitSet; 9]; 9] {
+    let mut used = [[0; 9]; 3];
+    for info in &CELLS {
+        let digit = puzzle[info.row][info.col];
+        if digit != 0 {
+            let bit = 1 << (digit - 1);
+            used[0][info.row] |= bit;
+            used[1][info.col] |= bit;
+            used[2][info.square] |= bit;
+        }
+    }
+
+    array::from_fn(|row| {
+        array::from_fn(|col| {
+            let info = cell((row, col));
+            if puzzle[row][col] != 0 {
+                0
+            } else {
+                0x1ff & !(used[0][row] | used[1][col] | used[2][info.square])
+            }
+        })
+    })
+}
+
 /// Gets a view of a row in a [Puzzle].
 fn horizontal_slice(puzzle: Puzzle, row: usize) -> [u8; 9] {
     if !(0..9).contains(&row) {
@@ -141,183 +1088,762 @@ fn vertical_slice(puzzle: Puzzle, col: usize) -> [u8; 9] {
 /// +---+
 /// ```
 fn square_slice(puzzle: Puzzle, square: usize) -> [u8; 9] {
-    match square {
-        0 => [
-            puzzle[0][0],
-            puzzle[0][1],
-            puzzle[0][2],
-            puzzle[1][0],
-            puzzle[1][1],
-            puzzle[1][2],
-            puzzle[2][0],
-            puzzle[2][1],
-            puzzle[2][2],
-        ],
-        1 => [
-            puzzle[0][3],
-            puzzle[0][4],
-            puzzle[0][5],
-            puzzle[1][3],
-            puzzle[1][4],
-            puzzle[1][5],
-            puzzle[2][3],
-            puzzle[2][4],
-            puzzle[2][5],
-        ],
-        2 => [
-            puzzle[0][6],
-            puzzle[0][7],
-            puzzle[0][8],
-            puzzle[1][6],
-            puzzle[1][7],
-            puzzle[1][8],
-            puzzle[2][6],
-            puzzle[2][7],
-            puzzle[2][8],
-        ],
-        3 => [
-            puzzle[3][0],
-            puzzle[3][1],
-            puzzle[3][2],
-            puzzle[4][0],
-            puzzle[4][1],
-            puzzle[4][2],
-            puzzle[5][0],
-            puzzle[5][1],
-            puzzle[5][2],
-        ],
-        4 => [
-            puzzle[3][3],
-            puzzle[3][4],
-            puzzle[3][5],
-            puzzle[4][3],
-            puzzle[4][4],
-            puzzle[4][5],
-            puzzle[5][3],
-            puzzle[5][4],
-            puzzle[5][5],
-        ],
-        5 => [
-            puzzle[3][6],
-            puzzle[3][7],
-            puzzle[3][8],
-            puzzle[4][6],
-            puzzle[4][7],
-            puzzle[4][8],
-            puzzle[5][6],
-            puzzle[5][7],
-            puzzle[5][8],
-        ],
-        6 => [
-            puzzle[6][0],
-            puzzle[6][1],
-            puzzle[6][2],
-            puzzle[7][0],
-            puzzle[7][1],
-            puzzle[7][2],
-            puzzle[8][0],
-            puzzle[8][1],
-            puzzle[8][2],
-        ],
-        7 => [
-            puzzle[6][3],
-            puzzle[6][4],
-            puzzle[6][5],
-            puzzle[7][3],
-            puzzle[7][4],
-            puzzle[7][5],
-            puzzle[8][3],
-            puzzle[8][4],
-            puzzle[8][5],
-        ],
-        8 => [
-            puzzle[6][6],
-            puzzle[6][7],
-            puzzle[6][8],
-            puzzle[7][6],
-            puzzle[7][7],
-            puzzle[7][8],
-            puzzle[8][6],
-            puzzle[8][7],
-            puzzle[8][8],
-        ],
-        _ => panic!("Invalid square index: {square}"),
-    }
-}
-
-/// Finds a [Solution] to a [Puzzle] by backtracking.
-fn find_solution(mut puzzle: Puzzle, blank: usize, blanks: &[GridPos]) -> Option<Solution> {
-    if blank == blanks.len() {
-        return Some(puzzle);
-    }
-
-    let (row, col) = blanks[blank];
-
-    for digit in 1..=9 {
-        puzzle[row][col] = digit;
-
-        if !is_valid_puzzle(puzzle) {
-            continue;
+    if !(0..9).contains(&square) {
+        panic!("Invalid square index: {square}");
+    }
+
+    SQUARES[square].map(|(row, col)| puzzle[row][col])
+}
+
+/// Compact grid for the solver: the digits packed in reading order, together with bitmasks of the
+/// digits used in each row, col and square, so that checking whether a digit fits is O(1).
+#[derive(Clone, Copy)]
+struct BitGrid {
+    /// Digits indexed by row * 9 + col, 0 for blanks.
+    digits: [u8; 81],
+
+    rows: [DigitSet; 9],
+    cols: [DigitSet; 9],
+    squares: [DigitSet; 9],
+}
+
+impl BitGrid {
+    /// Packs a puzzle. Returns None if the puzzle is invalid.
+    fn from_puzzle(puzzle: Puzzle) -> Option<BitGrid> {
+        let mut bits = BitGrid {
+            digits: [0; 81],
+            rows: [0; 9],
+            cols: [0; 9],
+            squares: [0; 9],
+        };
+
+        for info in &CELLS {
+            let digit = puzzle[info.row][info.col];
+            if digit == 0 {
+                continue;
+            }
+
+            if bits.used(info) & (1 << (digit - 1)) != 0 {
+                return None;
+            }
+
+            bits.set(info, digit);
+        }
+
+        Some(bits)
+    }
+
+    /// Unpacks the grid.
+    fn to_puzzle(self) -> Puzzle {
+        array::from_fn(|row| array::from_fn(|col| self.digit(row, col)))
+    }
+
+    fn digit(&self, row: usize, col: usize) -> u8 {
+        self.digits[row * 9 + col]
+    }
+
+    /// Gets the digits already used in the row, col and square of a cell.
+    fn used(&self, info: &CellInfo) -> DigitSet {
+        self.rows[info.row] | self.cols[info.col] | self.squares[info.square]
+    }
+
+    /// Puts a digit in a blank.
+    fn set(&mut self, info: &CellInfo, digit: u8) {
+        let bit = 1 << (digit - 1);
+
+        self.digits[info.row * 9 + info.col] = digit;
+        self.rows[info.row] |= bit;
+        self.cols[info.col] |= bit;
+        self.squares[info.square] |= bit;
+    }
+
+    /// Undoes [BitGrid::set], blanking a cell again.
+    fn clear(&mut self, info: &CellInfo) {
+        let bit = !(1 << (self.digits[info.row * 9 + info.col] - 1));
+
+        self.digits[info.row * 9 + info.col] = 0;
+        self.rows[info.row] &= bit;
+        self.cols[info.col] &= bit;
+        self.squares[info.square] &= bit;
+    }
+}
+
+/// Backtracks in place for [solve_in_place], always on the blank with the fewest candidates, and
+/// undoing each guess on the way back. Returns whether the grid was filled in.
+fn fill_in_place(bits: &mut BitGrid) -> bool {
+    let mut best = None;
+    let mut fewest = u32::MAX;
+    for info in CELLS
+        .iter()
+        .filter(|info| bits.digit(info.row, info.col) == 0)
+    {
+        let count = (!bits.used(info) & 0x1ff).count_ones();
+        if count < fewest {
+            best = Some(info);
+            fewest = count;
+            if count <= 1 {
+                break;
+            }
+        }
+    }
+
+    let Some(info) = best else {
+        return true;
+    };
+
+    let candidates = !bits.used(info) & 0x1ff;
+    for digit in (1..=9).filter(|digit| candidates & (1 << (digit - 1)) != 0) {
+        bits.set(info, digit);
+        if fill_in_place(bits) {
+            return true;
+        }
+        bits.clear(info);
+    }
+
+    false
+}
+
+/// Solver state: a [BitGrid] together with the candidates left in each cell. Checking or counting
+/// the candidates of a cell is O(1).
+#[derive(Clone, Copy)]
+struct Board {
+    bits: BitGrid,
+
+    /// Candidates of each cell. Filled cells have none.
+    cells: [[DigitSet; 9]; 9],
+
+    /// Number of filled cells.
+    filled: usize,
+}
+
+impl Board {
+    /// Sets up a board for a puzzle. Returns None if the puzzle is invalid.
+    fn new(puzzle: Puzzle) -> Option<Board> {
+        let bits = BitGrid::from_puzzle(puzzle)?;
+
+        let mut board = Board {
+            bits,
+            cells: [[0; 9]; 9],
+            filled: 0,
+        };
+        for info in &CELLS {
+            if bits.digit(info.row, info.col) == 0 {
+                board.cells[info.row][info.col] = 0x1ff & !bits.used(info);
+            } else {
+                board.filled += 1;
+            }
+        }
+
+        Some(board)
+    }
+
+    /// Gets the solution once there are no blanks left.
+    fn grid(&self) -> Grid {
+        self.bits.to_puzzle()
+    }
+
+    /// Gets the digits that can go in a cell. A filled cell has no candidates.
+    fn candidates(&self, row: usize, col: usize) -> DigitSet {
+        self.cells[row][col]
+    }
+
+    /// Counts the digits that can go in a cell.
+    fn candidate_count(&self, row: usize, col: usize) -> u32 {
+        self.cells[row][col].count_ones()
+    }
+
+    /// Puts a digit in a blank, removing it from the candidates of the cell's peers. The digit
+    /// must fit.
+    fn place(&mut self, row: usize, col: usize, digit: u8) {
+        let bit = 1 << (digit - 1);
+        let info = cell((row, col));
+
+        self.bits.set(info, digit);
+        self.filled += 1;
+
+        self.cells[row][col] = 0;
+        for &(peer_row, peer_col) in &info.peers {
+            self.cells[peer_row][peer_col] &= !bit;
+        }
+    }
+
+    /// Checks whether a peer of a cell is a blank without candidates.
+    fn wiped_out(&self, pos: GridPos) -> bool {
+        cell(pos)
+            .peers
+            .iter()
+            .any(|&(row, col)| self.bits.digit(row, col) == 0 && self.cells[row][col] == 0)
+    }
+
+    /// Finds the first blank in reading order, if any.
+    fn first_blank(&self) -> Option<GridPos> {
+        (0..81)
+            .map(|cell| (cell / 9, cell % 9))
+            .find(|&(row, col)| self.bits.digit(row, col) == 0)
+    }
+
+    /// Finds the blank to branch on next: the first blank in reading order with the fewest
+    /// candidates (minimum remaining values). Returns None if there are no blanks left.
+    fn most_constrained_blank(&self) -> Option<GridPos> {
+        let mut best = None;
+        let mut fewest = u32::MAX;
+        for row in 0..9 {
+            for col in 0..9 {
+                if self.bits.digit(row, col) != 0 {
+                    continue;
+                }
+
+                let count = self.candidate_count(row, col);
+                if count < fewest {
+                    best = Some((row, col));
+                    fewest = count;
+
+                    if count <= 1 {
+                        return best;
+                    }
+                }
+            }
+        }
+
+        best
+    }
+
+    /// Gets the candidates of a cell in the order they should be tried.
+    fn ordered_candidates(&self, row: usize, col: usize, order: DigitOrder) -> Digits {
+        let candidates = self.candidates(row, col);
+        let mut digits = Digits::default();
+        for digit in (1..=9).filter(|digit| candidates & (1 << (digit - 1)) != 0) {
+            digits.push(digit);
+        }
+
+        if order == DigitOrder::LeastConstraining {
+            digits
+                .as_mut_slice()
+                .sort_by_key(|&digit| self.constrained_peers(row, col, digit));
+        }
+
+        digits
+    }
+
+    /// Counts the peers of a cell that have a digit as a candidate -- the eliminations caused by
+    /// placing the digit in the cell.
+    fn constrained_peers(&self, row: usize, col: usize, digit: u8) -> usize {
+        cell((row, col))
+            .peers
+            .iter()
+            .filter(|&&(peer_row, peer_col)| {
+                self.cells[peer_row][peer_col] & (1 << (digit - 1)) != 0
+            })
+            .count()
+    }
+
+    /// Fills in naked singles -- blanks with only one candidate -- one at a time in reading order,
+    /// until there are none left.
+    ///
+    /// Returns the first blank found without any candidates, in which case there is no solution.
+    fn fill_naked_singles(&mut self) -> Result<(), GridPos> {
+        'fill: loop {
+            for row in 0..9 {
+                for col in 0..9 {
+                    if self.bits.digit(row, col) != 0 {
+                        continue;
+                    }
+
+                    let candidates = self.candidates(row, col);
+                    match self.candidate_count(row, col) {
+                        0 => return Err((row, col)),
+                        1 => {
+                            self.place(row, col, candidates.trailing_zeros() as u8 + 1);
+                            continue 'fill;
+                        }
+                        _ => {}
+                    }
+                }
+            }
+
+            return Ok(());
+        }
+    }
+
+    /// Fills in singles until there are none left: naked singles (blanks with only one
+    /// candidate), then hidden singles (digits that fit in only one cell of a unit).
+    ///
+    /// Returns false if a dead end is found -- a blank without candidates, or a digit that fits
+    /// nowhere in a unit -- in which case there is no solution.
+    fn fill_singles(&mut self) -> bool {
+        loop {
+            if self.fill_naked_singles().is_err() {
+                return false;
+            }
+
+            let candidates = self.cells;
+
+            for unit in Unit::all() {
+                let placed = match unit {
+                    Unit::Row(row) => self.bits.rows[row],
+                    Unit::Col(col) => self.bits.cols[col],
+                    Unit::Square(square) => self.bits.squares[square],
+                };
+                let possible = unit
+                    .positions()
+                    .iter()
+                    .fold(0, |set, &(row, col)| set | candidates[row][col]);
+
+                if placed | possible != 0x1ff {
+                    return false;
+                }
+            }
+
+            let Some(((row, col), digit)) =
+                Unit::all().find_map(|unit| hidden_single(&candidates, unit))
+            else {
+                return true;
+            };
+
+            self.place(row, col, digit);
+        }
+    }
+}
+
+/// Fills in naked singles in a [Puzzle]. See [Board::fill_naked_singles].
+///
+/// The puzzle must be valid.
+pub(crate) fn fill_naked_singles(puzzle: &mut Puzzle) -> Result<(), GridPos> {
+    let mut board = Board::new(*puzzle).expect("puzzle should be valid");
+    let filled = board.fill_naked_singles();
+    *puzzle = board.grid();
+
+    filled
+}
+
+/// Fills in naked and hidden singles in a [Puzzle]. See [Board::fill_singles].
+///
+/// The puzzle must be valid.
+pub(crate) fn fill_singles(puzzle: &mut Puzzle) -> bool {
+    let mut board = Board::new(*puzzle).expect("puzzle should be valid");
+    let filled = board.fill_singles();
+    *puzzle = board.grid();
+
+    filled
+}
+
+/// Finds a digit that fits in only one cell of a unit.
+pub(crate) fn hidden_single(candidates: &[[DigitSet; 9]; 9], unit: Unit) -> Option<(GridPos, u8)> {
+    (1..=9).find_map(|digit| {
+        let mut cells = unit
+            .positions()
+            .into_iter()
+            .filter(|&(row, col)| candidates[row][col] & (1 << (digit - 1)) != 0);
+
+        match (cells.next(), cells.next()) {
+            (Some(pos), None) => Some((pos, digit)),
+            _ => None,
+        }
+    })
+}
+
+/// Finds all [Solution]s to a [Puzzle], stopping once there are limit solutions.
+fn find_solutions(board: Board, order: DigitOrder, limit: usize, solutions: &mut Vec<Solution>) {
+    solutions.extend(Search::new(board, order).take(limit));
+}
+
+/// A backtracking search with an explicit stack instead of recursion, so that it can be paused
+/// after each solution and resumed later. Iterates over the solutions found.
+///
+/// Each node fills in singles, then branches on the blank with the fewest candidates.
+struct Search {
+    /// The branch points on the path to the current node, innermost last.
+    stack: Vec<Branch>,
+
+    /// A node that has yet to be filled in and expanded.
+    pending: Option<Board>,
+
+    order: DigitOrder,
+
+    /// Whether to fill in singles and branch on the blank with the fewest candidates, rather
+    /// than on the first blank.
+    heuristics: bool,
+
+    /// Whether to always branch on the first blank, even with heuristics.
+    reading_order: bool,
+
+    /// Whether to drop a guess straight away if it leaves a peer without candidates.
+    forward_checking: bool,
+
+    /// If set, the digits of each blank are tried in a random order instead.
+    rng: Option<Rng>,
+
+    /// If set, the search gives up once this time has passed.
+    deadline: Option<Instant>,
+
+    /// If set, the search gives up once it has visited this many grids.
+    max_nodes: Option<usize>,
+
+    /// If set, the search gives up once this is cancelled.
+    cancel: Option<CancelToken>,
+
+    /// Why the search gave up before finishing, if it did.
+    interrupted: Option<Interruption>,
+
+    stats: SolveStats,
+}
+
+/// A blank being branched on, with the digits still to try.
+struct Branch {
+    board: Board,
+    pos: GridPos,
+
+    /// The untried digits, in reverse order of trying.
+    digits: Digits,
+}
+
+/// Up to 9 digits in a given order, kept inline so that a search node allocates nothing.
+#[derive(Clone, Copy, Debug, Default)]
+struct Digits {
+    digits: [u8; 9],
+    len: usize,
+}
+
+impl Digits {
+    fn push(&mut self, digit: u8) {
+        self.digits[self.len] = digit;
+        self.len += 1;
+    }
+
+    fn pop(&mut self) -> Option<u8> {
+        self.len = self.len.checked_sub(1)?;
+        Some(self.digits[self.len])
+    }
+
+    fn as_mut_slice(&mut self) -> &mut [u8] {
+        &mut self.digits[..self.len]
+    }
+}
+
+impl Search {
+    fn new(board: Board, order: DigitOrder) -> Search {
+        Search {
+            stack: Vec::new(),
+            pending: Some(board),
+            order,
+            heuristics: true,
+            reading_order: false,
+            forward_checking: false,
+            rng: None,
+            deadline: None,
+            max_nodes: None,
+            cancel: None,
+            interrupted: None,
+            stats: SolveStats::default(),
+        }
+    }
+
+    /// Checks whether the search should give up, and why.
+    fn should_stop(&self) -> Option<Interruption> {
+        if self
+            .deadline
+            .is_some_and(|deadline| Instant::now() >= deadline)
+        {
+            Some(Interruption::TimedOut)
+        } else if self.cancel.as_ref().is_some_and(CancelToken::is_cancelled) {
+            Some(Interruption::Cancelled)
+        } else if self.max_nodes.is_some_and(|max| self.stats.nodes >= max) {
+            Some(Interruption::NodeLimit)
+        } else {
+            None
+        }
+    }
+}
+
+impl Iterator for Search {
+    type Item = Solution;
+
+    fn next(&mut self) -> Option<Solution> {
+        loop {
+            if self.pending.is_some()
+                && let Some(interruption) = self.should_stop()
+            {
+                self.interrupted = Some(interruption);
+                self.pending = None;
+                self.stack.clear();
+            }
+
+            if let Some(mut board) = self.pending.take() {
+                self.stats.nodes += 1;
+
+                let filled = board.filled;
+                let consistent = !self.heuristics || board.fill_singles();
+                self.stats.propagated += board.filled - filled;
+
+                if !consistent {
+                    self.stats.backtracks += 1;
+                    continue;
+                }
+
+                let blank = if self.heuristics && !self.reading_order {
+                    board.most_constrained_blank()
+                } else {
+                    board.first_blank()
+                };
+                let Some((row, col)) = blank else {
+                    return Some(board.grid());
+                };
+
+                let mut digits = board.ordered_candidates(row, col, self.order);
+                match &mut self.rng {
+                    Some(rng) => rng.shuffle(digits.as_mut_slice()),
+                    None => digits.as_mut_slice().reverse(),
+                }
+                self.stack.push(Branch {
+                    board,
+                    pos: (row, col),
+                    digits,
+                });
+                self.stats.max_depth = self.stats.max_depth.max(self.stack.len());
+            }
+
+            let branch = self.stack.last_mut()?;
+            match branch.digits.pop() {
+                Some(digit) => {
+                    let mut next = branch.board;
+                    next.place(branch.pos.0, branch.pos.1, digit);
+
+                    if self.forward_checking && next.wiped_out(branch.pos) {
+                        self.stats.backtracks += 1;
+                    } else {
+                        self.pending = Some(next);
+                    }
+                }
+                None => {
+                    self.stack.pop();
+                }
+            }
+        }
+    }
+}
+
+/// A grid part way through a search, with blanks still to be filled in.
+pub type PartialGrid = Grid;
+
+/// Outcome of advancing a [StepSolver].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StepStatus {
+    /// The search has not finished yet.
+    Running,
+    Solved(Solution),
+    NoSolution,
+}
+
+/// A view of a [StepSolver] between steps.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Snapshot {
+    /// The digits placed so far.
+    pub grid: PartialGrid,
+
+    /// Estimated share of the search space already explored, from 0 to 100.
+    pub progress: f32,
+}
+
+/// A backtracking search that is advanced a few steps at a time, so that a long solve can be
+/// interleaved with other work (e.g. UI updates) on a single thread.
+///
+/// Each step tries one digit in one blank, so the time spent per call to [StepSolver::step_n] is
+/// bounded.
+pub struct StepSolver {
+    grid: PartialGrid,
+    blanks: Vec<GridPos>,
+
+    /// Index into blanks of the blank currently being filled in.
+    depth: usize,
+
+    /// Last digit tried in each blank, or 0 if none yet. Only digits that fit reach the grid.
+    tried: Vec<u8>,
+
+    status: StepStatus,
+}
+
+impl StepSolver {
+    pub fn new(puzzle: Puzzle) -> StepSolver {
+        let blanks = blanks(puzzle);
+        let status = if !is_valid_puzzle(puzzle) {
+            StepStatus::NoSolution
+        } else if blanks.is_empty() {
+            StepStatus::Solved(puzzle)
+        } else {
+            StepStatus::Running
+        };
+
+        StepSolver {
+            grid: puzzle,
+            tried: vec![0; blanks.len()],
+            blanks,
+            depth: 0,
+            status,
+        }
+    }
+
+    /// Advances the search by one step.
+    pub fn step(&mut self) -> StepStatus {
+        if self.status != StepStatus::Running {
+            return self.status;
+        }
+
+        let (row, col) = self.blanks[self.depth];
+        let digit = self.tried[self.depth] + 1;
+        self.grid[row][col] = 0;
+
+        if digit > 9 {
+            // Exhausted this blank, so backtrack.
+            self.tried[self.depth] = 0;
+
+            if self.depth == 0 {
+                self.status = StepStatus::NoSolution;
+            } else {
+                self.depth -= 1;
+            }
+
+            return self.status;
+        }
+
+        self.tried[self.depth] = digit;
+        let peers = &cell((row, col)).peers;
+
+        if !peers.iter().any(|&(row, col)| self.grid[row][col] == digit) {
+            self.grid[row][col] = digit;
+            self.depth += 1;
+
+            if self.depth == self.blanks.len() {
+                self.status = StepStatus::Solved(self.grid);
+            }
+        }
+
+        self.status
+    }
+
+    /// Advances the search by up to steps steps, stopping early if the search finishes.
+    pub fn step_n(&mut self, steps: usize) -> StepStatus {
+        for _ in 0..steps {
+            if self.step() != StepStatus::Running {
+                break;
+            }
         }
 
-        if let Some(solution) = find_solution(puzzle, blank + 1, blanks) {
-            return Some(solution);
+        self.status
+    }
+
+    /// Gets the current state of the search.
+    pub fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            grid: self.grid,
+            progress: self.progress(),
         }
     }
 
-    None
+    /// Estimates the explored share of the search space, treating each blank as a 9-way branch.
+    fn progress(&self) -> f32 {
+        if self.status != StepStatus::Running {
+            return 100.0;
+        }
+
+        // Blanks on the current path have explored every digit before the placed one. The blank
+        // being filled in has also explored the last digit tried, which failed or was exhausted.
+        let mut explored = 0.0;
+        let mut branch_size = 1.0;
+        for (index, &digit) in self.tried[..=self.depth].iter().enumerate() {
+            branch_size /= 9.0;
+
+            let done = if index == self.depth {
+                digit
+            } else {
+                digit - 1
+            };
+            explored += done as f64 * branch_size;
+        }
+
+        (explored * 100.0) as f32
+    }
+}
+
+/// Finds a solution to each puzzle, if any, spreading the puzzles over all available cores. The
+/// solutions are in the same order as the puzzles.
+pub fn solve_batch(puzzles: &[Puzzle]) -> Vec<Option<Solution>> {
+    let threads = thread::available_parallelism().map_or(1, NonZeroUsize::get);
+    let chunk_size = puzzles.len().div_ceil(threads).max(1);
+
+    thread::scope(|scope| {
+        let handles = puzzles
+            .chunks(chunk_size)
+            .map(|chunk| scope.spawn(|| chunk.iter().map(|&puzzle| solve_any(puzzle)).collect()))
+            .collect::<Vec<_>>();
+
+        handles
+            .into_iter()
+            .flat_map(|handle| -> Vec<_> { handle.join().unwrap() })
+            .collect()
+    })
 }
 
-/// Finds all [Solution]s to a [Puzzle].
-fn find_solutions(
-    mut puzzle: Puzzle,
-    blank: usize,
-    blanks: &[GridPos],
-    solutions: &mut Vec<Solution>,
-) {
-    if blank == blanks.len() {
-        solutions.push(puzzle);
+/// Scratch space for solving, set up once and then shared between solves.
+///
+/// Solves like [Backend::Propagation], reusing the search stacks of earlier solves, so that once
+/// warmed up a solve allocates nothing but the solutions it returns, e.g. in a server or a batch
+/// job that solves continuously. The free functions pick [Backend::Dlx] for puzzles with few
+/// givens, so the solutions of those may come in a different order. A context is Send + Sync, so
+/// one instance can serve all threads.
+pub struct SolverContext {
+    /// Spare search stacks, emptied after each solve.
+    stacks: Mutex<Vec<Vec<Branch>>>,
+}
 
-        return;
+impl SolverContext {
+    pub fn new() -> SolverContext {
+        SolverContext {
+            stacks: Mutex::new(Vec::new()),
+        }
     }
 
-    let (row, col) = blanks[blank];
-
-    for digit in 1..=9 {
-        puzzle[row][col] = digit;
-
-        if !is_valid_puzzle(puzzle) {
-            continue;
-        }
+    /// Finds all solutions to the given puzzle, if any, like [solve].
+    pub fn solve(&self, puzzle: Puzzle) -> Vec<Solution> {
+        self.search(puzzle, |search| search.collect())
+    }
 
-        find_solutions(puzzle, blank + 1, blanks, solutions);
+    /// Finds a solution to the given puzzle, if any, like [solve_any].
+    pub fn solve_any(&self, puzzle: Puzzle) -> Option<Solution> {
+        self.search(puzzle, Search::next)
     }
-}
 
-/// Checks whether a [Puzzle] has 0, 1 or 2 or more [Solution]s.
-///
-/// Returns the number of solutions (0, 1, or 2) in count_cache. If the puzzle has two or more
-/// solutions, count_cache will be 2. count_cache must be initialized as 0 by the caller.
-fn count_solutions(mut puzzle: Puzzle, blank: usize, blanks: &[GridPos], count_cache: &mut u8) {
-    if blank == blanks.len() {
-        *count_cache += 1;
+    /// Verifies whether a puzzle has exactly one solution, like [has_unique_solution].
+    pub fn has_unique_solution(&self, puzzle: Puzzle) -> bool {
+        self.search(puzzle, |search| search.take(2).count() == 1)
+    }
 
-        return;
+    /// Counts the solutions to the given puzzle, stopping once limit is reached. A limit of 0
+    /// always counts 0.
+    pub fn count_solutions(&self, puzzle: Puzzle, limit: usize) -> usize {
+        self.search(puzzle, |search| search.take(limit).count())
     }
 
-    let (row, col) = blanks[blank];
+    /// Runs a backtracking search on a spare stack, then puts the stack back. Gets the default
+    /// value of the result if the puzzle is invalid.
+    fn search<T: Default>(&self, puzzle: Puzzle, run: impl FnOnce(&mut Search) -> T) -> T {
+        let Some(board) = Board::new(puzzle) else {
+            return T::default();
+        };
 
-    for digit in 1..=9 {
-        puzzle[row][col] = digit;
+        let mut search = Search::new(board, DigitOrder::Ascending);
+        search.stack = self.stacks.lock().unwrap().pop().unwrap_or_default();
+        let result = run(&mut search);
 
-        if !is_valid_puzzle(puzzle) {
-            continue;
-        }
+        search.stack.clear();
+        self.stacks.lock().unwrap().push(search.stack);
 
-        count_solutions(puzzle, blank + 1, blanks, count_cache);
-        if *count_cache > 1 {
-            return;
-        }
+        result
+    }
+}
+
+impl Default for SolverContext {
+    fn default() -> Self {
+        SolverContext::new()
     }
 }
 
@@ -484,4 +2010,809 @@ mod tests {
 
         assert!(slice_has_unique_digits([0; 9]));
     }
+
+    const PUZZLE: Puzzle = [
+        [5, 3, 0, 0, 7, 0, 0, 0, 0],
+        [6, 0, 0, 1, 9, 5, 0, 0, 0],
+        [9, 8, 0, 0, 0, 0, 0, 6, 0],
+        [8, 0, 0, 0, 6, 0, 0, 0, 3],
+        [4, 0, 0, 8, 0, 3, 0, 0, 1],
+        [7, 0, 0, 0, 2, 0, 0, 0, 6],
+        [0, 6, 0, 0, 0, 0, 2, 8, 0],
+        [0, 0, 0, 4, 1, 9, 0, 0, 5],
+        [0, 0, 0, 0, 8, 0, 0, 7, 9],
+    ];
+
+    #[test]
+    fn check_step_solver() {
+        let mut solver = StepSolver::new(PUZZLE);
+
+        let mut last_progress = 0.0;
+        let status = loop {
+            let status = solver.step_n(100);
+            if status != StepStatus::Running {
+                break status;
+            }
+
+            let snapshot = solver.snapshot();
+            assert!(snapshot.progress >= last_progress);
+            assert!(is_valid_puzzle(snapshot.grid));
+            last_progress = snapshot.progress;
+        };
+
+        assert_eq!(status, StepStatus::Solved(solve_any(PUZZLE).unwrap()));
+        assert_eq!(solver.snapshot().progress, 100.0);
+    }
+
+    #[test]
+    fn check_step_solver_no_solution() {
+        let mut puzzle = PUZZLE;
+        puzzle[0][2] = 5;
+        assert_eq!(StepSolver::new(puzzle).step(), StepStatus::NoSolution);
+
+        // Valid givens, but r0c2 has no legal digit left.
+        let mut puzzle = PUZZLE;
+        puzzle[0][7] = 1;
+        puzzle[0][8] = 2;
+        puzzle[6][2] = 4;
+        assert!(is_valid_puzzle(puzzle));
+        assert_eq!(
+            StepSolver::new(puzzle).step_n(usize::MAX),
+            StepStatus::NoSolution
+        );
+    }
+
+    #[test]
+    fn check_cell_table() {
+        assert!(CELLS.iter().all(|info| {
+            let mut peers = info.peers.to_vec();
+            peers.sort();
+            peers.dedup();
+            peers.len() == 20 && !peers.contains(&(info.row, info.col))
+        }));
+
+        let center = cell((4, 4));
+        assert_eq!((center.row, center.col, center.square), (4, 4, 4));
+        assert!(center.peers.contains(&(3, 5)));
+        assert!(center.peers.contains(&(2, 4)));
+        assert!(!center.peers.contains(&(0, 0)));
+        assert_eq!(cell((7, 2)).square, 6);
+
+        assert_eq!(SQUARES[5][0], (3, 6));
+        assert_eq!(SQUARES[5][8], (5, 8));
+    }
+
+    #[test]
+    fn check_solver_context() {
+        let context = SolverContext::new();
+
+        let solution = solve_any(PUZZLE);
+        std::thread::scope(|scope| {
+            for _ in 0..4 {
+                scope.spawn(|| {
+                    assert_eq!(context.solve_any(PUZZLE), solution);
+                    assert_eq!(context.solve(PUZZLE), solve(PUZZLE));
+                    assert!(context.has_unique_solution(PUZZLE));
+                });
+            }
+        });
+
+        let mut puzzle = PUZZLE;
+        puzzle[0][2] = 5;
+        assert_eq!(context.solve_any(puzzle), None);
+
+        let mut puzzle = PUZZLE;
+        puzzle[0][0] = 0;
+        puzzle[0][1] = 0;
+        puzzle[1][0] = 0;
+        assert_eq!(
+            context.has_unique_solution(puzzle),
+            has_unique_solution(puzzle)
+        );
+    }
+
+    #[test]
+    fn check_unit_positions() {
+        for index in 0..9 {
+            assert_eq!(
+                Unit::Square(index)
+                    .positions()
+                    .map(|(row, col)| SLICE_TEST_1[row][col]),
+                square_slice(SLICE_TEST_1, index)
+            );
+            assert_eq!(
+                Unit::Col(index)
+                    .positions()
+                    .map(|(row, col)| SLICE_TEST_1[row][col]),
+                vertical_slice(SLICE_TEST_1, index)
+            );
+        }
+
+        assert_eq!(Unit::all().count(), 27);
+    }
+
+    #[test]
+    fn check_find_conflicts() {
+        assert!(find_conflicts(PUZZLE).is_empty());
+
+        let mut puzzle = PUZZLE;
+        puzzle[0][2] = 5;
+        assert_eq!(
+            find_conflicts(puzzle),
+            vec![
+                Conflict {
+                    digit: 5,
+                    unit: Unit::Row(0),
+                    positions: vec![(0, 0), (0, 2)],
+                },
+                Conflict {
+                    digit: 5,
+                    unit: Unit::Square(0),
+                    positions: vec![(0, 0), (0, 2)],
+                },
+            ]
+        );
+
+        let mut puzzle = PUZZLE;
+        puzzle[8][0] = 7;
+        puzzle[8][1] = 7;
+        assert_eq!(
+            find_conflicts(puzzle)
+                .iter()
+                .map(|conflict| conflict.unit)
+                .collect::<Vec<_>>(),
+            vec![Unit::Row(8), Unit::Col(0), Unit::Square(6)]
+        );
+    }
+
+    #[test]
+    fn check_find_conflicting_pairs() {
+        assert!(find_conflicting_pairs(PUZZLE).is_empty());
+
+        let mut puzzle = PUZZLE;
+        puzzle[0][2] = 5;
+        assert_eq!(find_conflicting_pairs(puzzle), vec![((0, 0), (0, 2), 5)]);
+
+        let mut puzzle = PUZZLE;
+        puzzle[8][0] = 7;
+        puzzle[8][1] = 7;
+        assert_eq!(
+            find_conflicting_pairs(puzzle),
+            vec![
+                ((5, 0), (8, 0), 7),
+                ((8, 0), (8, 1), 7),
+                ((8, 0), (8, 7), 7),
+                ((8, 1), (8, 7), 7)
+            ]
+        );
+    }
+
+    #[test]
+    fn check_verify_solution() {
+        let solution = solve_any(PUZZLE).unwrap();
+        assert!(verify_solution(PUZZLE, solution));
+        assert!(verify_solution(solution, solution));
+
+        // A given that was changed.
+        let mut changed = PUZZLE;
+        changed[0][2] = 1;
+        assert!(!verify_solution(changed, solution));
+
+        // Either solution of an unavoidable rectangle.
+        let mut puzzle = solution;
+        for (row, col) in [(3, 5), (3, 8), (4, 5), (4, 8)] {
+            puzzle[row][col] = 0;
+        }
+        for candidate in solve(puzzle) {
+            assert!(verify_solution(puzzle, candidate));
+        }
+
+        let mut incomplete = solution;
+        incomplete[8][8] = 0;
+        assert!(!verify_solution(PUZZLE, incomplete));
+
+        let mut invalid = solution;
+        invalid[8].swap(7, 8);
+        assert!(!verify_solution([[0; 9]; 9], invalid));
+
+        let mut out_of_range = solution;
+        out_of_range[8][8] = 10;
+        assert!(!verify_solution(PUZZLE, out_of_range));
+    }
+
+    #[test]
+    fn check_classify() {
+        assert_eq!(
+            classify(PUZZLE),
+            Classification::Unique(solve_any(PUZZLE).unwrap())
+        );
+
+        let mut puzzle = PUZZLE;
+        puzzle[0][2] = 5;
+        assert!(matches!(
+            classify(puzzle),
+            Classification::InvalidGivens(conflicts) if conflicts.len() == 2
+        ));
+
+        let mut puzzle = PUZZLE;
+        puzzle[0][7] = 1;
+        puzzle[0][8] = 2;
+        puzzle[6][2] = 4;
+        assert_eq!(classify(puzzle), Classification::NoSolution);
+
+        let mut puzzle = PUZZLE;
+        puzzle[0] = [0; 9];
+        assert_eq!(classify(puzzle), Classification::Multiple(2));
+        assert!(matches!(
+            classify_with_limit(puzzle, 1000),
+            Classification::Multiple(count) if count == solve(puzzle).len()
+        ));
+    }
+
+    #[test]
+    fn check_fill_naked_singles() {
+        // The puzzle falls to naked singles alone, so no search is needed.
+        let mut puzzle = PUZZLE;
+        assert_eq!(fill_naked_singles(&mut puzzle), Ok(()));
+        assert!(blanks(puzzle).is_empty());
+        assert_eq!(Some(puzzle), solve_any(PUZZLE));
+
+        let mut puzzle = PUZZLE;
+        puzzle[0][7] = 1;
+        puzzle[0][8] = 2;
+        puzzle[6][2] = 4;
+        assert_eq!(fill_naked_singles(&mut puzzle), Err((0, 2)));
+    }
+
+    #[test]
+    fn check_fill_singles() {
+        // Naked singles alone stall on this 17-clue puzzle, but hidden singles finish it.
+        let sparse = [
+            [0, 0, 0, 0, 0, 0, 0, 1, 0],
+            [4, 0, 0, 0, 0, 0, 0, 0, 0],
+            [0, 2, 0, 0, 0, 0, 0, 0, 0],
+            [0, 0, 0, 0, 5, 0, 4, 0, 7],
+            [0, 0, 8, 0, 0, 0, 3, 0, 0],
+            [0, 0, 1, 0, 9, 0, 0, 0, 0],
+            [3, 0, 0, 4, 0, 0, 2, 0, 0],
+            [0, 5, 0, 1, 0, 0, 0, 0, 0],
+            [0, 0, 0, 8, 0, 6, 0, 0, 0],
+        ];
+
+        let mut puzzle = sparse;
+        assert_eq!(fill_naked_singles(&mut puzzle), Ok(()));
+        assert!(!blanks(puzzle).is_empty());
+
+        let mut board = Board::new(sparse).unwrap();
+        assert!(board.fill_singles());
+        assert!(blanks(board.grid()).is_empty());
+        assert!(is_valid_puzzle(board.grid()));
+
+        let mut puzzle = PUZZLE;
+        puzzle[0][7] = 1;
+        puzzle[0][8] = 2;
+        puzzle[6][2] = 4;
+        assert!(!Board::new(puzzle).unwrap().fill_singles());
+    }
+
+    #[test]
+    fn check_digit_order() {
+        let solution = solve_any(PUZZLE).unwrap();
+        assert_eq!(
+            solve_any_with_order(PUZZLE, DigitOrder::LeastConstraining),
+            Some(solution)
+        );
+
+        let mut puzzle = solution;
+        for (row, col) in [(3, 5), (3, 8), (4, 5), (4, 8)] {
+            puzzle[row][col] = 0;
+        }
+        let mut solutions = solve_with_order(puzzle, DigitOrder::LeastConstraining);
+        solutions.sort();
+        assert_eq!(solutions, solve(puzzle));
+    }
+
+    #[test]
+    fn check_auto_backend() {
+        // Near-empty grids go to dancing links, except when asked for stats, which only
+        // backtracking reports.
+        let empty = [[0; 9]; 9];
+        assert!(matches!(
+            Solver::new().solve(empty),
+            SolveOutcome::Solved(_)
+        ));
+        let (outcome, stats) = Solver::new().solve_with_stats(empty);
+        assert!(matches!(outcome, SolveOutcome::Solved(_)));
+        assert!(stats.nodes > 0);
+        assert!(stats.max_depth > 0);
+        assert_eq!(Solver::new().count(empty, Some(3)), Ok(3));
+
+        // Forcing a backend, or setting a backtracking option, backtracks.
+        let forced = Solver::new().backend(Backend::Propagation);
+        assert!(forced.solve_with_stats(empty).1.nodes > 0);
+        let limited = Solver::new().max_nodes(1000);
+        assert!(limited.solve_with_stats(empty).1.nodes > 0);
+
+        let (outcome, stats) = Solver::new().solve_with_stats(PUZZLE);
+        assert_eq!(outcome, SolveOutcome::Solved(solve_any(PUZZLE).unwrap()));
+        assert!(stats.nodes > 0);
+    }
+
+    #[test]
+    fn check_backend() {
+        /// Arto Inkala's "world's hardest sudoku".
+        const HARD: Puzzle = [
+            [8, 0, 0, 0, 0, 0, 0, 0, 0],
+            [0, 0, 3, 6, 0, 0, 0, 0, 0],
+            [0, 7, 0, 0, 9, 0, 2, 0, 0],
+            [0, 5, 0, 0, 0, 7, 0, 0, 0],
+            [0, 0, 0, 0, 4, 5, 7, 0, 0],
+            [0, 0, 0, 1, 0, 0, 0, 3, 0],
+            [0, 0, 1, 0, 0, 0, 0, 6, 8],
+            [0, 0, 8, 5, 0, 0, 0, 1, 0],
+            [0, 9, 0, 0, 0, 0, 4, 0, 0],
+        ];
+
+        for puzzle in [PUZZLE, HARD] {
+            assert_eq!(solve_with(Backend::Dlx, puzzle), solve(puzzle));
+            assert!(has_unique_solution_with(Backend::Dlx, puzzle));
+        }
+
+        let solution = solve_any(PUZZLE).unwrap();
+        assert_eq!(solve_with(Backend::Dlx, solution), vec![solution]);
+
+        let mut puzzle = solution;
+        for (row, col) in [(3, 5), (3, 8), (4, 5), (4, 8)] {
+            puzzle[row][col] = 0;
+        }
+        let mut solutions = solve_with(Backend::Dlx, puzzle);
+        solutions.sort();
+        assert_eq!(solutions.len(), 2);
+        assert_eq!(solutions, solve_with(Backend::Backtracking, puzzle));
+        assert_eq!(
+            compare(Backend::Dlx, Backend::Propagation, puzzle),
+            solutions
+        );
+        assert!(!has_unique_solution_with(Backend::Dlx, puzzle));
+
+        let mut puzzle = PUZZLE;
+        puzzle[0][2] = 5;
+        assert!(solve_with(Backend::Dlx, puzzle).is_empty());
+        puzzle[0][2] = 0;
+        puzzle[0][7] = 1;
+        puzzle[0][8] = 2;
+        puzzle[6][2] = 4;
+        assert!(solve_with(Backend::Dlx, puzzle).is_empty());
+
+        assert_eq!(dlx::solve_up_to([[0; 9]; 9], 1000).len(), 1000);
+    }
+
+    #[test]
+    fn check_annealing_backend() {
+        let solution = solve_any(PUZZLE).unwrap();
+        assert_eq!(solve_with(Backend::Annealing, PUZZLE), vec![solution]);
+
+        let empty = Solver::new().backend(Backend::Annealing).seed(7);
+        let SolveOutcome::Solved(grid) = empty.solve([[0; 9]; 9]) else {
+            panic!("empty grid should be filled in");
+        };
+        assert!(is_valid_puzzle(grid));
+        assert!(grid.iter().flatten().all(|&digit| digit != 0));
+
+        // Too short a schedule gives up.
+        let schedule = Schedule {
+            steps: 0,
+            ..Schedule::default()
+        };
+        let short = empty.clone().schedule(schedule);
+        assert_eq!(short.solve([[0; 9]; 9]), SolveOutcome::GaveUp);
+        assert_eq!(short.count([[0; 9]; 9], Some(1)), Err(Interruption::GaveUp));
+
+        // Annealing cannot tell whether a solution is unique.
+        assert_eq!(empty.uniqueness(PUZZLE), Err(Interruption::GaveUp));
+        assert_eq!(empty.count([[0; 9]; 9], Some(1)), Ok(1));
+        assert_eq!(empty.count([[0; 9]; 9], Some(2)), Err(Interruption::GaveUp));
+        assert!(!has_unique_solution_with(Backend::Annealing, [[0; 9]; 9]));
+        assert!(!has_unique_solution_with(Backend::Annealing, PUZZLE));
+
+        let mut puzzle = PUZZLE;
+        puzzle[0][2] = 5;
+        assert!(solve_with(Backend::Annealing, puzzle).is_empty());
+        assert_eq!(empty.solve(puzzle), SolveOutcome::NoSolution);
+        assert_eq!(empty.uniqueness(puzzle), Ok(Uniqueness::Zero));
+        puzzle[0][2] = 0;
+        puzzle[0][7] = 1;
+        puzzle[0][8] = 2;
+        puzzle[6][2] = 4;
+        assert!(solve_with(Backend::Annealing, puzzle).is_empty());
+    }
+
+    #[cfg(feature = "sat")]
+    #[test]
+    fn check_sat_backend() {
+        let solution = solve_any(PUZZLE).unwrap();
+        assert_eq!(solve_with(Backend::Sat, PUZZLE), vec![solution]);
+        assert!(has_unique_solution_with(Backend::Sat, PUZZLE));
+        assert!(!has_unique_solution_with(Backend::Sat, [[0; 9]; 9]));
+
+        let mut puzzle = solution;
+        for (row, col) in [(3, 5), (3, 8), (4, 5), (4, 8)] {
+            puzzle[row][col] = 0;
+        }
+        let mut solutions = solve_with(Backend::Sat, puzzle);
+        solutions.sort();
+        assert_eq!(solutions, solve(puzzle));
+        assert!(!has_unique_solution_with(Backend::Sat, puzzle));
+
+        let mut puzzle = PUZZLE;
+        puzzle[0][2] = 5;
+        assert!(solve_with(Backend::Sat, puzzle).is_empty());
+        puzzle[0][2] = 0;
+        puzzle[0][7] = 1;
+        puzzle[0][8] = 2;
+        puzzle[6][2] = 4;
+        assert!(solve_with(Backend::Sat, puzzle).is_empty());
+    }
+
+    #[test]
+    fn check_search() {
+        let solution = solve_any(PUZZLE).unwrap();
+        let mut puzzle = solution;
+        for (row, col) in [(3, 5), (3, 8), (4, 5), (4, 8)] {
+            puzzle[row][col] = 0;
+        }
+
+        let mut search = Search::new(Board::new(puzzle).unwrap(), DigitOrder::Ascending);
+        let first = search.next().unwrap();
+        let second = search.next().unwrap();
+        assert_ne!(first, second);
+        assert!([first, second].contains(&solution));
+        assert_eq!(search.next(), None);
+        assert_eq!(search.next(), None);
+    }
+
+    #[test]
+    fn check_solve_iter() {
+        assert_eq!(solve_iter(PUZZLE).collect::<Vec<_>>(), solve(PUZZLE));
+        assert_eq!(solve_iter([[0; 9]; 9]).take(3).count(), 3);
+
+        let mut puzzle = PUZZLE;
+        puzzle[0][2] = 5;
+        assert_eq!(solve_iter(puzzle).next(), None);
+    }
+
+    #[test]
+    fn check_count_solutions() {
+        assert_eq!(count_solutions(PUZZLE, None), 1);
+        assert_eq!(count_solutions(PUZZLE, Some(0)), 0);
+
+        let mut puzzle = solve_any(PUZZLE).unwrap();
+        for (row, col) in [(3, 5), (3, 8), (4, 5), (4, 8)] {
+            puzzle[row][col] = 0;
+        }
+        assert_eq!(count_solutions(puzzle, None), 2);
+        assert_eq!(count_solutions(puzzle, Some(1)), 1);
+
+        assert_eq!(count_solutions([[0; 9]; 9], Some(5)), 5);
+
+        puzzle[0][0] = puzzle[0][1];
+        assert_eq!(count_solutions(puzzle, None), 0);
+    }
+
+    #[test]
+    fn check_count_solutions_exact() {
+        assert_eq!(count_solutions_exact(PUZZLE), Ok(1));
+
+        let mut puzzle = solve_any(PUZZLE).unwrap();
+        for (row, col) in [(3, 5), (3, 8), (4, 5), (4, 8)] {
+            puzzle[row][col] = 0;
+        }
+        assert_eq!(count_solutions_exact(puzzle), Ok(2));
+
+        let mut puzzle = PUZZLE;
+        puzzle[0] = [0; 9];
+        puzzle[8] = [0; 9];
+        let count = count_solutions(puzzle, None) as u64;
+        assert_eq!(count_solutions_exact(puzzle), Ok(count));
+        assert_eq!(count_solutions_exact_with_limit(puzzle, count), Ok(count));
+        assert_eq!(
+            count_solutions_exact_with_limit(puzzle, count - 1),
+            Err(TooManySolutions { limit: count - 1 })
+        );
+
+        assert_eq!(
+            count_solutions_exact_with_limit([[0; 9]; 9], 1000),
+            Err(TooManySolutions { limit: 1000 })
+        );
+
+        let mut puzzle = PUZZLE;
+        puzzle[0][2] = 5;
+        assert_eq!(count_solutions_exact(puzzle), Ok(0));
+    }
+
+    #[test]
+    fn check_uniqueness() {
+        assert_eq!(
+            uniqueness(PUZZLE),
+            Uniqueness::One(solve_any(PUZZLE).unwrap())
+        );
+        assert_eq!(uniqueness([[0; 9]; 9]), Uniqueness::Multiple);
+
+        let mut puzzle = PUZZLE;
+        puzzle[0][2] = 5;
+        assert_eq!(uniqueness(puzzle), Uniqueness::Zero);
+        puzzle[0][2] = 0;
+        puzzle[0][7] = 1;
+        puzzle[0][8] = 2;
+        puzzle[6][2] = 4;
+        assert_eq!(uniqueness(puzzle), Uniqueness::Zero);
+
+        for backend in [Backend::Backtracking, Backend::Dlx] {
+            let solver = Solver::new().backend(backend);
+            assert_eq!(solver.uniqueness(PUZZLE), Ok(uniqueness(PUZZLE)));
+        }
+
+        let limited = Solver::new().max_nodes(1);
+        assert_eq!(
+            limited.uniqueness([[0; 9]; 9]),
+            Err(Interruption::NodeLimit)
+        );
+    }
+
+    #[test]
+    fn check_solve_in_place() {
+        let mut puzzle = PUZZLE;
+        assert!(solve_in_place(&mut puzzle));
+        assert_eq!(Some(puzzle), solve_any(PUZZLE));
+
+        let mut empty = [[0; 9]; 9];
+        assert!(solve_in_place(&mut empty));
+        assert!(is_valid_puzzle(empty));
+        assert!(empty.iter().flatten().all(|&digit| digit != 0));
+
+        let mut invalid = PUZZLE;
+        invalid[0][2] = 5;
+        let before = invalid;
+        assert!(!solve_in_place(&mut invalid));
+        assert_eq!(invalid, before);
+
+        let mut unsolvable = PUZZLE;
+        unsolvable[0][7] = 1;
+        unsolvable[0][8] = 2;
+        unsolvable[6][2] = 4;
+        let before = unsolvable;
+        assert!(!solve_in_place(&mut unsolvable));
+        assert_eq!(unsolvable, before);
+    }
+
+    #[test]
+    fn check_solve_random() {
+        let solution = solve_any(PUZZLE).unwrap();
+        assert_eq!(solve_random(PUZZLE, 1), Some(solution));
+
+        let solutions = (0..10)
+            .map(|seed| solve_random([[0; 9]; 9], seed).unwrap())
+            .collect::<Vec<_>>();
+        assert!(solutions.iter().all(|solution| is_valid_puzzle(*solution)));
+        assert!(solutions.iter().any(|solution| *solution != solutions[0]));
+        assert_eq!(solve_random([[0; 9]; 9], 3), Some(solutions[3]));
+
+        let mut puzzle = PUZZLE;
+        puzzle[0][2] = 5;
+        assert_eq!(solve_random(puzzle, 1), None);
+    }
+
+    #[test]
+    fn check_solve_with_timeout() {
+        let solution = solve_any(PUZZLE).unwrap();
+        assert_eq!(
+            solve_with_timeout(PUZZLE, Duration::from_secs(60)),
+            SolveOutcome::Solved(solution)
+        );
+        assert_eq!(
+            solve_with_timeout(PUZZLE, Duration::ZERO),
+            SolveOutcome::TimedOut
+        );
+        assert_eq!(
+            solve_with_timeout(PUZZLE, Duration::MAX),
+            SolveOutcome::Solved(solution)
+        );
+
+        let mut puzzle = PUZZLE;
+        puzzle[0][7] = 1;
+        puzzle[0][8] = 2;
+        puzzle[6][2] = 4;
+        assert_eq!(
+            solve_with_timeout(puzzle, Duration::from_secs(60)),
+            SolveOutcome::NoSolution
+        );
+    }
+
+    #[test]
+    fn check_cancel_token() {
+        let token = CancelToken::new();
+        let solution = solve_any(PUZZLE).unwrap();
+        assert_eq!(
+            solve_cancellable(PUZZLE, &token),
+            SolveOutcome::Solved(solution)
+        );
+        assert_eq!(
+            count_solutions_cancellable([[0; 9]; 9], Some(3), &token),
+            Ok(3)
+        );
+
+        token.clone().cancel();
+        assert!(token.is_cancelled());
+        assert_eq!(solve_cancellable(PUZZLE, &token), SolveOutcome::Cancelled);
+        assert_eq!(
+            count_solutions_cancellable([[0; 9]; 9], None, &token),
+            Err(Cancelled)
+        );
+
+        // The givens are checked before searching.
+        let mut puzzle = PUZZLE;
+        puzzle[0][2] = 5;
+        assert_eq!(solve_cancellable(puzzle, &token), SolveOutcome::NoSolution);
+    }
+
+    #[test]
+    fn check_solve_with_stats() {
+        let (solution, stats) = solve_with_stats(PUZZLE);
+        assert_eq!(solution, solve_any(PUZZLE));
+        assert_eq!(stats.nodes, 1);
+        assert_eq!(stats.backtracks, 0);
+        assert_eq!(stats.max_depth, 0);
+        assert_eq!(stats.propagated, blanks(PUZZLE).len());
+
+        let mut puzzle = solution.unwrap();
+        for (row, col) in [(3, 5), (3, 8), (4, 5), (4, 8)] {
+            puzzle[row][col] = 0;
+        }
+        let (_, stats) = solve_with_stats(puzzle);
+        assert_eq!(stats.nodes, 2);
+        assert_eq!(stats.max_depth, 1);
+        assert_eq!(stats.propagated, 3);
+
+        let mut puzzle = PUZZLE;
+        puzzle[0][7] = 1;
+        puzzle[0][8] = 2;
+        puzzle[6][2] = 4;
+        let (solution, stats) = solve_with_stats(puzzle);
+        assert_eq!(solution, None);
+        assert_eq!(stats.backtracks, 1);
+    }
+
+    #[test]
+    fn check_solver() {
+        let solution = solve_any(PUZZLE).unwrap();
+        for solver in [
+            Solver::new(),
+            Solver::new().heuristics(false),
+            Solver::new().order(DigitOrder::LeastConstraining),
+            Solver::new().seed(7),
+            Solver::new().backend(Backend::Backtracking),
+            Solver::new().backend(Backend::Dlx),
+        ] {
+            assert_eq!(solver.solve(PUZZLE), SolveOutcome::Solved(solution));
+            assert_eq!(solver.count(PUZZLE, None), Ok(1));
+            assert_eq!(solver.iter(PUZZLE).collect::<Vec<_>>(), vec![solution]);
+        }
+
+        let solver = Solver::new().heuristics(false).max_nodes(10);
+        assert_eq!(solver.solve(PUZZLE), SolveOutcome::NodeLimit);
+        assert_eq!(solver.count(PUZZLE, None), Err(Interruption::NodeLimit));
+        assert_eq!(solver.iter(PUZZLE).count(), 0);
+
+        let solver = Solver::new().max_nodes(1);
+        assert_eq!(solver.solve(PUZZLE), SolveOutcome::Solved(solution));
+
+        let mut puzzle = PUZZLE;
+        puzzle[0][2] = 5;
+        assert_eq!(Solver::new().solve(puzzle), SolveOutcome::NoSolution);
+        assert_eq!(Solver::new().count(puzzle, None), Ok(0));
+    }
+
+    #[test]
+    fn check_bit_grid() {
+        let bits = BitGrid::from_puzzle(PUZZLE).unwrap();
+        assert_eq!(bits.to_puzzle(), PUZZLE);
+        assert_eq!(bits.digit(0, 1), 3);
+        assert_eq!(bits.used(cell((0, 2))), 0b1_1111_0100);
+
+        let mut puzzle = PUZZLE;
+        puzzle[0][2] = 5;
+        assert!(BitGrid::from_puzzle(puzzle).is_none());
+    }
+
+    #[test]
+    fn check_solve_batch() {
+        let mut invalid = PUZZLE;
+        invalid[0][2] = 5;
+        let puzzles = [PUZZLE, invalid, [[0; 9]; 9]]
+            .into_iter()
+            .cycle()
+            .take(50)
+            .collect::<Vec<_>>();
+
+        let solutions = solve_batch(&puzzles);
+        assert_eq!(solutions.len(), puzzles.len());
+        for (puzzle, solution) in puzzles.iter().zip(solutions) {
+            assert_eq!(solution, solve_any(*puzzle));
+        }
+
+        assert!(solve_batch(&[]).is_empty());
+    }
+
+    #[test]
+    fn check_solution_order() {
+        let mut puzzle = PUZZLE;
+        puzzle[0] = [0; 9];
+        puzzle[8] = [0; 9];
+
+        let mut expected = solve(puzzle);
+        expected.sort();
+        assert!(expected.len() > 2);
+
+        let solver = Solver::new().solution_order(SolutionOrder::Lexicographic);
+        assert_eq!(solver.iter(puzzle).collect::<Vec<_>>(), expected);
+
+        let solver = solver.seed(1).order(DigitOrder::LeastConstraining);
+        assert_eq!(solver.iter(puzzle).collect::<Vec<_>>(), expected);
+    }
+
+    #[test]
+    fn check_forward_checking() {
+        let solution = solve_any(PUZZLE).unwrap();
+        let plain = Solver::new().backend(Backend::Backtracking);
+
+        let (outcome, without) = plain.solve_with_stats(PUZZLE);
+        assert_eq!(outcome, SolveOutcome::Solved(solution));
+
+        let (outcome, with) = plain.forward_checking(true).solve_with_stats(PUZZLE);
+        assert_eq!(outcome, SolveOutcome::Solved(solution));
+        assert!(with.nodes < without.nodes);
+    }
+
+    #[test]
+    fn check_board() {
+        let board = Board::new(PUZZLE).unwrap();
+        assert_eq!(board.grid(), PUZZLE);
+        assert_eq!(board.candidates(0, 2), candidates(PUZZLE)[0][2]);
+        assert_eq!(board.candidates(0, 0), 0);
+        assert_ne!(board.candidates(0, 2) & 1 << 3, 0);
+        assert_eq!(board.candidates(0, 2) & 1 << 2, 0);
+        assert_eq!(board.candidate_count(0, 2), 3);
+
+        // Of r0c2's candidates, 1 is a candidate in 7 peers, 4 in 9 and 2 in 11.
+        assert_eq!(
+            board
+                .ordered_candidates(0, 2, DigitOrder::Ascending)
+                .as_mut_slice(),
+            [1, 2, 4]
+        );
+        assert_eq!(
+            board
+                .ordered_candidates(0, 2, DigitOrder::LeastConstraining)
+                .as_mut_slice(),
+            [1, 4, 2]
+        );
+
+        let (row, col) = board.most_constrained_blank().unwrap();
+        assert_eq!(board.candidate_count(row, col), 1);
+        let solved = Board::new(solve_any(PUZZLE).unwrap()).unwrap();
+        assert_eq!(solved.most_constrained_blank(), None);
+
+        // Placing a digit removes it from the candidates of the cell's peers only.
+        let mut placed = board;
+        placed.place(0, 2, 4);
+        assert_eq!(placed.candidates(0, 2), 0);
+        assert_eq!(placed.candidates(0, 3) & 1 << 3, 0);
+        assert_eq!(placed.candidates(2, 2) & 1 << 3, 0);
+        assert_eq!(placed.candidates(1, 1) & 1 << 3, 0);
+        assert_eq!(placed.candidates(8, 0), board.candidates(8, 0));
+
+        let mut puzzle = PUZZLE;
+        puzzle[0][2] = 5;
+        assert!(Board::new(puzzle).is_none());
+    }
 }
@@ -0,0 +1,117 @@
+use crate::rng::Rng;
+
+use super::{Puzzle, SQUARES, Solution, candidates};
+
+/// Temperature schedule for [super::Backend::Annealing].
+///
+/// Each run starts at initial_temperature, and multiplies the temperature by cooling after every
+/// move. A run that has not solved the puzzle after steps moves is abandoned, and a new run
+/// starts from a fresh random grid, up to restarts times.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Schedule {
+    pub initial_temperature: f64,
+    pub cooling: f64,
+    pub steps: usize,
+    pub restarts: usize,
+}
+
+impl Default for Schedule {
+    fn default() -> Self {
+        Schedule {
+            initial_temperature: 0.5,
+            cooling: 0.99999,
+            steps: 200_000,
+            restarts: 20,
+        }
+    }
+}
+
+/// Looks for a solution by simulated annealing: fills each square with its missing digits, then
+/// swaps pairs of blanks within a square to minimize the number of digits repeated in rows and
+/// cols.
+///
+/// Not guaranteed to find a solution even if there is one. The puzzle must be valid.
+pub(super) fn solve(puzzle: Puzzle, schedule: Schedule, seed: u64) -> Option<Solution> {
+    // A blank without candidates can never be filled in.
+    let candidates = candidates(puzzle);
+    if (0..81).any(|cell| puzzle[cell / 9][cell % 9] == 0 && candidates[cell / 9][cell % 9] == 0) {
+        return None;
+    }
+
+    let mut rng = Rng::new(seed);
+    (0..=schedule.restarts).find_map(|_| run(puzzle, schedule, &mut rng))
+}
+
+/// Runs one annealing from a random grid.
+fn run(puzzle: Puzzle, schedule: Schedule, rng: &mut Rng) -> Option<Solution> {
+    let mut grid = puzzle;
+    let mut blanks = Vec::new();
+    for square in &SQUARES {
+        let cells = square
+            .iter()
+            .copied()
+            .filter(|&(row, col)| puzzle[row][col] == 0)
+            .collect::<Vec<_>>();
+
+        let mut missing = (1..=9)
+            .filter(|digit| !square.iter().any(|&(row, col)| puzzle[row][col] == *digit))
+            .collect::<Vec<u8>>();
+        rng.shuffle(&mut missing);
+
+        for (&(row, col), digit) in cells.iter().zip(missing) {
+            grid[row][col] = digit;
+        }
+
+        if cells.len() >= 2 {
+            blanks.push(cells);
+        }
+    }
+
+    let mut cost = conflicts(&grid);
+    let mut temperature = schedule.initial_temperature;
+    for _ in 0..schedule.steps {
+        if cost == 0 {
+            return Some(grid);
+        }
+        if blanks.is_empty() {
+            return None;
+        }
+
+        let cells = &blanks[rng.below(blanks.len())];
+        let first = cells[rng.below(cells.len())];
+        let second = cells[rng.below(cells.len())];
+        if first == second {
+            continue;
+        }
+
+        swap(&mut grid, first, second);
+        let new_cost = conflicts(&grid);
+        let delta = new_cost as f64 - cost as f64;
+        if delta <= 0.0 || rng.next_f64() < (-delta / temperature).exp() {
+            cost = new_cost;
+        } else {
+            swap(&mut grid, first, second);
+        }
+
+        temperature *= schedule.cooling;
+    }
+
+    (cost == 0).then_some(grid)
+}
+
+fn swap(grid: &mut Puzzle, (row, col): (usize, usize), (other_row, other_col): (usize, usize)) {
+    let digit = grid[row][col];
+    grid[row][col] = grid[other_row][other_col];
+    grid[other_row][other_col] = digit;
+}
+
+/// Counts the digits missing from each row and col. Squares always hold every digit.
+fn conflicts(grid: &Puzzle) -> usize {
+    (0..9)
+        .map(|index| {
+            let row = grid[index].iter().fold(0u16, |set, digit| set | 1 << digit);
+            let col = (0..9).fold(0u16, |set, row| set | 1 << grid[row][index]);
+            18 - row.count_ones() as usize - col.count_ones() as usize
+        })
+        .sum()
+}
@@ -0,0 +1,233 @@
+use super::{GridPos, Puzzle, Solution, candidates, cell};
+
+/// Number of exact-cover constraints: each cell holds one digit, and each row, col and square holds
+/// each digit once.
+const CONSTRAINTS: usize = 4 * 81;
+
+/// A digit in a cell.
+type Placement = (GridPos, u8);
+
+/// Finds up to limit solutions to a puzzle by solving it as an exact-cover problem with dancing
+/// links (Knuth's Algorithm X).
+///
+/// The puzzle must be valid.
+pub(super) fn solve_up_to(puzzle: Puzzle, limit: usize) -> Vec<Solution> {
+    let mut solutions = Vec::new();
+    if limit == 0 {
+        return solutions;
+    }
+
+    let mut links = Links::new(puzzle);
+    links.search(&mut Vec::new(), &mut |choices| {
+        let mut solution = puzzle;
+        for &((row, col), digit) in choices {
+            solution[row][col] = digit;
+        }
+
+        solutions.push(solution);
+        solutions.len() < limit
+    });
+
+    solutions
+}
+
+/// Counts the solutions to a puzzle with dancing links, without building them. Returns None once
+/// there turn out to be more than limit.
+///
+/// The puzzle must be valid.
+pub(super) fn count_up_to(puzzle: Puzzle, limit: u64) -> Option<u64> {
+    let mut count = 0u64;
+    let mut links = Links::new(puzzle);
+    links.search(&mut Vec::new(), &mut |_| {
+        count += 1;
+        count <= limit
+    });
+
+    (count <= limit).then_some(count)
+}
+
+/// The exact-cover matrix as a toroidal doubly linked list.
+///
+/// Node 0 is the root, and nodes 1 to [CONSTRAINTS] are the column headers. Each placement that is
+/// still possible adds a row of 4 nodes, one for each constraint it satisfies.
+struct Links {
+    left: Vec<usize>,
+    right: Vec<usize>,
+    up: Vec<usize>,
+    down: Vec<usize>,
+
+    /// Column header of each node.
+    column: Vec<usize>,
+
+    /// Index into placements of the row each node is part of. Unused for the root and headers.
+    row: Vec<usize>,
+
+    /// Number of nodes left in each column, indexed by header.
+    size: Vec<usize>,
+
+    /// The placement each row stands for.
+    placements: Vec<Placement>,
+}
+
+impl Links {
+    /// Builds the matrix for a puzzle: one row per given, and one per candidate of each blank.
+    fn new(puzzle: Puzzle) -> Links {
+        let headers = CONSTRAINTS + 1;
+        let mut links = Links {
+            left: (0..headers)
+                .map(|node| (node + headers - 1) % headers)
+                .collect(),
+            right: (0..headers).map(|node| (node + 1) % headers).collect(),
+            up: (0..headers).collect(),
+            down: (0..headers).collect(),
+            column: (0..headers).collect(),
+            row: vec![0; headers],
+            size: vec![0; headers],
+            placements: Vec::new(),
+        };
+
+        let candidates = candidates(puzzle);
+        for row in 0..9 {
+            for col in 0..9 {
+                let digits = match puzzle[row][col] {
+                    0 => candidates[row][col],
+                    digit => 1 << (digit - 1),
+                };
+
+                for digit in (1..=9).filter(|digit| digits & (1 << (digit - 1)) != 0) {
+                    links.add_row((row, col), digit);
+                }
+            }
+        }
+
+        links
+    }
+
+    /// Adds the row for placing a digit in a cell.
+    fn add_row(&mut self, (row, col): GridPos, digit: u8) {
+        let digit_index = digit as usize - 1;
+        let square = cell((row, col)).square;
+        let constraints = [
+            row * 9 + col,
+            81 + row * 9 + digit_index,
+            2 * 81 + col * 9 + digit_index,
+            3 * 81 + square * 9 + digit_index,
+        ];
+
+        let first = self.column.len();
+        for (index, constraint) in constraints.into_iter().enumerate() {
+            let header = constraint + 1;
+            let node = first + index;
+
+            self.left
+                .push(if index == 0 { first + 3 } else { node - 1 });
+            self.right.push(if index == 3 { first } else { node + 1 });
+            self.up.push(self.up[header]);
+            self.down.push(header);
+            self.column.push(header);
+            self.row.push(self.placements.len());
+
+            let last = self.up[header];
+            self.down[last] = node;
+            self.up[header] = node;
+            self.size[header] += 1;
+        }
+
+        self.placements.push(((row, col), digit));
+    }
+
+    /// Removes a column, and every row that has a node in it, from the matrix.
+    fn cover(&mut self, header: usize) {
+        self.right[self.left[header]] = self.right[header];
+        self.left[self.right[header]] = self.left[header];
+
+        let mut row_node = self.down[header];
+        while row_node != header {
+            let mut node = self.right[row_node];
+            while node != row_node {
+                self.down[self.up[node]] = self.down[node];
+                self.up[self.down[node]] = self.up[node];
+                self.size[self.column[node]] -= 1;
+                node = self.right[node];
+            }
+
+            row_node = self.down[row_node];
+        }
+    }
+
+    /// Undoes [Links::cover], in exactly the reverse order.
+    fn uncover(&mut self, header: usize) {
+        let mut row_node = self.up[header];
+        while row_node != header {
+            let mut node = self.left[row_node];
+            while node != row_node {
+                self.size[self.column[node]] += 1;
+                self.down[self.up[node]] = node;
+                self.up[self.down[node]] = node;
+                node = self.left[node];
+            }
+
+            row_node = self.up[row_node];
+        }
+
+        self.right[self.left[header]] = header;
+        self.left[self.right[header]] = header;
+    }
+
+    /// Runs Algorithm X, always branching on the column with the fewest rows, and passes the
+    /// placements of each exact cover found to on_solution, which returns whether to carry on.
+    ///
+    /// Returns false if the search should stop.
+    fn search(
+        &mut self,
+        chosen: &mut Vec<Placement>,
+        on_solution: &mut dyn FnMut(&[Placement]) -> bool,
+    ) -> bool {
+        if self.right[0] == 0 {
+            return on_solution(chosen);
+        }
+
+        let mut header = self.right[0];
+        let mut best = header;
+        while header != 0 {
+            if self.size[header] < self.size[best] {
+                best = header;
+            }
+
+            header = self.right[header];
+        }
+
+        if self.size[best] == 0 {
+            return true;
+        }
+
+        self.cover(best);
+
+        let mut carry_on = true;
+        let mut row_node = self.down[best];
+        while row_node != best && carry_on {
+            chosen.push(self.placements[self.row[row_node]]);
+
+            let mut node = self.right[row_node];
+            while node != row_node {
+                self.cover(self.column[node]);
+                node = self.right[node];
+            }
+
+            carry_on = self.search(chosen, on_solution);
+
+            let mut node = self.left[row_node];
+            while node != row_node {
+                self.uncover(self.column[node]);
+                node = self.left[node];
+            }
+
+            chosen.pop();
+            row_node = self.down[row_node];
+        }
+
+        self.uncover(best);
+
+        carry_on
+    }
+}
@@ -0,0 +1,250 @@
+use super::{Puzzle, Solution, Unit};
+
+/// Finds up to limit solutions to a puzzle by encoding it as a boolean satisfiability problem in
+/// conjunctive normal form, and enumerating its models with a small DPLL solver.
+///
+/// There is one variable per digit per cell, true if the cell holds the digit.
+pub(super) fn solve_up_to(puzzle: Puzzle, limit: usize) -> Vec<Solution> {
+    let mut solutions = Vec::new();
+    if limit == 0 {
+        return solutions;
+    }
+
+    let mut solver = Dpll::new(encode(puzzle));
+    solver.search(&mut |model| {
+        let mut solution = [[0; 9]; 9];
+        for var in (0..VARS).filter(|&var| model[var] == Some(true)) {
+            solution[var / 81][var / 9 % 9] = (var % 9) as u8 + 1;
+        }
+
+        solutions.push(solution);
+        solutions.len() < limit
+    });
+
+    solutions
+}
+
+/// Number of variables: 9 digits for each of the 81 cells.
+const VARS: usize = 9 * 81;
+
+/// A variable or its negation, as variable * 2, plus 1 if negated.
+type Literal = usize;
+
+/// Gets the literal that a cell holds a digit.
+fn holds(row: usize, col: usize, digit: usize) -> Literal {
+    (row * 81 + col * 9 + digit - 1) * 2
+}
+
+fn negate(literal: Literal) -> Literal {
+    literal ^ 1
+}
+
+/// Gets the value of a literal under a partial assignment of values to variables.
+fn value(values: &[Option<bool>], literal: Literal) -> Option<bool> {
+    values[literal / 2].map(|value| value == (literal & 1 == 0))
+}
+
+/// Encodes the rules and the givens of a puzzle as clauses, each a disjunction of literals.
+fn encode(puzzle: Puzzle) -> Vec<Vec<Literal>> {
+    let mut clauses = Vec::new();
+
+    // Each cell holds exactly one digit.
+    for row in 0..9 {
+        for col in 0..9 {
+            let digits = (1..=9)
+                .map(|digit| holds(row, col, digit))
+                .collect::<Vec<_>>();
+            at_most_one(&digits, &mut clauses);
+            clauses.push(digits);
+        }
+    }
+
+    // Each unit holds each digit exactly once.
+    for unit in Unit::all() {
+        for digit in 1..=9 {
+            let cells = unit
+                .positions()
+                .into_iter()
+                .map(|(row, col)| holds(row, col, digit))
+                .collect::<Vec<_>>();
+            at_most_one(&cells, &mut clauses);
+            clauses.push(cells);
+        }
+    }
+
+    for (row, digits) in puzzle.iter().enumerate() {
+        for (col, &digit) in digits.iter().enumerate() {
+            if digit != 0 {
+                clauses.push(vec![holds(row, col, digit as usize)]);
+            }
+        }
+    }
+
+    clauses
+}
+
+/// Adds a clause for every pair of literals, so that at most one of them is true.
+fn at_most_one(literals: &[Literal], clauses: &mut Vec<Vec<Literal>>) {
+    for (index, &first) in literals.iter().enumerate() {
+        for &second in &literals[index + 1..] {
+            clauses.push(vec![negate(first), negate(second)]);
+        }
+    }
+}
+
+/// DPLL solver with unit propagation over two watched literals per clause.
+struct Dpll {
+    clauses: Vec<Vec<Literal>>,
+
+    /// Clauses of two or more literals, by each literal they watch. A clause watches its first two
+    /// literals, and is only looked at again when one of them becomes false.
+    watches: Vec<Vec<usize>>,
+
+    /// Value of each variable, if assigned.
+    values: Vec<Option<bool>>,
+
+    /// Literals made true so far, in order.
+    trail: Vec<Literal>,
+
+    /// How much of the trail has been propagated.
+    propagated: usize,
+
+    /// Whether a clause is unsatisfiable before any decisions are made.
+    conflict: bool,
+}
+
+impl Dpll {
+    fn new(clauses: Vec<Vec<Literal>>) -> Dpll {
+        let mut solver = Dpll {
+            watches: vec![Vec::new(); VARS * 2],
+            values: vec![None; VARS],
+            trail: Vec::new(),
+            propagated: 0,
+            conflict: false,
+            clauses: Vec::new(),
+        };
+
+        for (index, clause) in clauses.iter().enumerate() {
+            match clause[..] {
+                [] => solver.conflict = true,
+                [literal] => {
+                    if value(&solver.values, literal) == Some(false) {
+                        solver.conflict = true;
+                    } else if value(&solver.values, literal).is_none() {
+                        solver.assign(literal);
+                    }
+                }
+                [first, second, ..] => {
+                    solver.watches[first].push(index);
+                    solver.watches[second].push(index);
+                }
+            }
+        }
+        solver.clauses = clauses;
+
+        solver
+    }
+
+    /// Makes a literal true.
+    fn assign(&mut self, literal: Literal) {
+        self.values[literal / 2] = Some(literal & 1 == 0);
+        self.trail.push(literal);
+    }
+
+    /// Unassigns everything after the first length literals of the trail.
+    fn backtrack(&mut self, length: usize) {
+        for literal in self.trail.drain(length..) {
+            self.values[literal / 2] = None;
+        }
+        self.propagated = length;
+    }
+
+    /// Assigns every literal that is the last one left to satisfy a clause. Returns false if a
+    /// clause ends up with all its literals false.
+    fn propagate(&mut self) -> bool {
+        while self.propagated < self.trail.len() {
+            let falsified = negate(self.trail[self.propagated]);
+            self.propagated += 1;
+
+            let watching = std::mem::take(&mut self.watches[falsified]);
+            let mut kept = Vec::with_capacity(watching.len());
+            let mut ok = true;
+
+            for (position, &index) in watching.iter().enumerate() {
+                if !ok {
+                    kept.extend_from_slice(&watching[position..]);
+                    break;
+                }
+
+                let clause = &mut self.clauses[index];
+                if clause[0] == falsified {
+                    clause.swap(0, 1);
+                }
+
+                let other = clause[0];
+                if value(&self.values, other) == Some(true) {
+                    kept.push(index);
+                    continue;
+                }
+
+                let replacement = (2..clause.len())
+                    .find(|&position| value(&self.values, clause[position]) != Some(false));
+                if let Some(position) = replacement {
+                    clause.swap(1, position);
+                    self.watches[clause[1]].push(index);
+                    continue;
+                }
+
+                kept.push(index);
+                match value(&self.values, other) {
+                    Some(false) => ok = false,
+                    _ => self.assign(other),
+                }
+            }
+
+            self.watches[falsified].extend(kept);
+            if !ok {
+                return false;
+            }
+        }
+
+        true
+    }
+
+    /// Enumerates every model, passing each to on_model, which returns whether to carry on.
+    fn search(&mut self, on_model: &mut dyn FnMut(&[Option<bool>]) -> bool) {
+        if !self.conflict {
+            self.search_from(on_model);
+        }
+    }
+
+    /// Returns false if the search should stop.
+    fn search_from(&mut self, on_model: &mut dyn FnMut(&[Option<bool>]) -> bool) -> bool {
+        let length = self.trail.len();
+        if !self.propagate() {
+            self.backtrack(length);
+            return true;
+        }
+
+        let Some(var) = (0..VARS).find(|&var| self.values[var].is_none()) else {
+            let carry_on = on_model(&self.values);
+            self.backtrack(length);
+            return carry_on;
+        };
+
+        for literal in [var * 2, var * 2 + 1] {
+            let decided = self.trail.len();
+            self.assign(literal);
+
+            if !self.search_from(on_model) {
+                self.backtrack(length);
+                return false;
+            }
+
+            self.backtrack(decided);
+        }
+
+        self.backtrack(length);
+        true
+    }
+}
@@ -0,0 +1,80 @@
+use std::arch::x86_64::{
+    __m128i, _mm_add_epi16, _mm_cmpeq_epi16, _mm_loadu_si128, _mm_movemask_epi8, _mm_or_si128,
+    _mm_setzero_si128,
+};
+
+use super::{CELLS, Puzzle};
+
+/// Verifies whether a puzzle is valid -- all digits are in legal positions -- checking all nine
+/// units of a kind at once with SSE2, which every x86_64 CPU has.
+///
+/// Each cell is turned into a bitmask with bit (digit - 1) set, and each lane sums and ORs the
+/// masks of one unit. The masks of a unit are disjoint, i.e. its digits are unique, exactly when
+/// the sum equals the OR.
+pub(super) fn is_valid_puzzle(puzzle: Puzzle) -> bool {
+    // For each kind of unit (rows, cols, squares) and each of the 9 cells of a unit, the masks of
+    // that cell in every unit, padded to two vectors of 8 lanes.
+    let mut masks = [[[0u16; 16]; 9]; 3];
+    for info in &CELLS {
+        let mask = match puzzle[info.row][info.col] {
+            0 => 0,
+            digit => 1 << (digit - 1),
+        };
+
+        masks[0][info.col][info.row] = mask;
+        masks[1][info.row][info.col] = mask;
+        masks[2][info.row % 3 * 3 + info.col % 3][info.square] = mask;
+    }
+
+    // SAFETY: SSE2 is part of the x86_64 baseline, so it is always available.
+    masks.iter().all(|cells| unsafe { units_are_unique(cells) })
+}
+
+/// Checks the units of one kind, given the masks of each of their cells.
+#[target_feature(enable = "sse2")]
+fn units_are_unique(cells: &[[u16; 16]; 9]) -> bool {
+    let mut ors = [_mm_setzero_si128(); 2];
+    let mut sums = [_mm_setzero_si128(); 2];
+
+    for lanes in cells {
+        for half in 0..2 {
+            // SAFETY: lanes holds 16 u16s, so each half is 8 u16s -- 128 bits -- in bounds. The
+            // load does not need to be aligned.
+            let masks = unsafe { _mm_loadu_si128(lanes[half * 8..].as_ptr() as *const __m128i) };
+            ors[half] = _mm_or_si128(ors[half], masks);
+            sums[half] = _mm_add_epi16(sums[half], masks);
+        }
+    }
+
+    (0..2).all(|half| _mm_movemask_epi8(_mm_cmpeq_epi16(ors[half], sums[half])) == 0xffff)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::sudoku::{slices_are_valid, solve_random};
+
+    use super::*;
+
+    #[test]
+    fn check_is_valid_puzzle() {
+        for seed in 0..20 {
+            let solution = solve_random([[0; 9]; 9], seed).unwrap();
+            assert!(is_valid_puzzle(solution));
+
+            // Swapping two digits of a row breaks a col and maybe a square, but not the row.
+            let mut puzzle = solution;
+            let col = seed as usize % 8;
+            puzzle[seed as usize % 9].swap(col, col + 1);
+            assert!(!is_valid_puzzle(puzzle));
+
+            // Moving a digit down a col breaks the row it moves into, but not the col.
+            let mut puzzle = solution;
+            puzzle[0][0] = 0;
+            puzzle[1][0] = solution[0][0];
+            assert!(!is_valid_puzzle(puzzle));
+            assert!(!slices_are_valid(puzzle));
+        }
+
+        assert!(is_valid_puzzle([[0; 9]; 9]));
+    }
+}
@@ -0,0 +1,197 @@
+use anyhow::{Result, anyhow};
+
+use crate::{
+    i18n::{Message, tr},
+    sudoku::{DigitSet, Puzzle, Solution, candidates, cell},
+};
+
+/// Parses a Sukaku (pencil-mark puzzle): a grid of candidates instead of placed digits, given as
+/// 729 chars, 9 for each cell in reading order. The chars of a cell list its candidates, digits
+/// 1-9 for the candidates and . or 0 to pad, usually with each digit in its own slot, e.g.
+/// "1.3...7.." for 1, 3 and 7. Whitespace is ignored.
+///
+/// The result has bit (digit - 1) set for each candidate of each cell.
+pub fn parse_sukaku(input: &str) -> Result<[[u16; 9]; 9]> {
+    let chars = input
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect::<Vec<_>>();
+    if chars.len() != 729 {
+        return Err(anyhow!(tr(Message::InvalidSukaku)));
+    }
+
+    let mut candidates = [[0; 9]; 9];
+    for (index, &c) in chars.iter().enumerate() {
+        let cell = &mut candidates[index / 81][index / 9 % 9];
+        match c {
+            '1'..='9' => *cell |= 1 << (c as u8 - b'1'),
+            '0' | '.' => {}
+            _ => return Err(anyhow!(tr(Message::InvalidSukaku))),
+        }
+    }
+
+    Ok(candidates)
+}
+
+/// Converts a grid of candidates to the 729 char format of [parse_sukaku], with each digit in its
+/// own slot and . for the missing ones.
+pub fn sukaku_to_string(candidates: [[u16; 9]; 9]) -> String {
+    candidates
+        .iter()
+        .flatten()
+        .flat_map(|&set| {
+            (1..=9u8).map(move |digit| match set & (1 << (digit - 1)) {
+                0 => '.',
+                _ => char::from(b'0' + digit),
+            })
+        })
+        .collect()
+}
+
+/// Turns a puzzle into a grid of candidates: each given is its only candidate, and each blank has
+/// the digits that can legally go there.
+pub fn sukaku_from_puzzle(puzzle: Puzzle) -> [[u16; 9]; 9] {
+    let mut sukaku = candidates(puzzle);
+    for (row, digits) in puzzle.iter().enumerate() {
+        for (col, &digit) in digits.iter().enumerate() {
+            if digit != 0 {
+                sukaku[row][col] = 1 << (digit - 1);
+            }
+        }
+    }
+
+    sukaku
+}
+
+/// Finds up to limit solutions to a grid of candidates, where each cell holds one of its
+/// candidates, by backtracking. Solutions are in no particular order.
+pub fn solve_sukaku(candidates: [[u16; 9]; 9], limit: usize) -> Vec<Solution> {
+    let mut solutions = Vec::new();
+    if limit > 0 {
+        search(
+            candidates.map(|row| row.map(|set| set & 0x1ff)),
+            &mut [[0; 9]; 9],
+            limit,
+            &mut solutions,
+        );
+    }
+
+    solutions
+}
+
+/// Fills in the blank of grid with the fewest candidates left with each of them in turn, removing
+/// the digit from the candidates of its peers, until limit solutions are found.
+fn search(
+    candidates: [[DigitSet; 9]; 9],
+    grid: &mut Puzzle,
+    limit: usize,
+    solutions: &mut Vec<Solution>,
+) {
+    let blank = (0..81)
+        .map(|index| (index / 9, index % 9))
+        .filter(|&(row, col)| grid[row][col] == 0)
+        .min_by_key(|&(row, col)| candidates[row][col].count_ones());
+    let Some((row, col)) = blank else {
+        solutions.push(*grid);
+        return;
+    };
+
+    for digit in 1..=9 {
+        let bit = 1 << (digit - 1);
+        if candidates[row][col] & bit == 0 {
+            continue;
+        }
+
+        let mut next = candidates;
+        for &(peer_row, peer_col) in &cell((row, col)).peers {
+            next[peer_row][peer_col] &= !bit;
+        }
+        let peers_left = cell((row, col)).peers.iter().all(|&(peer_row, peer_col)| {
+            grid[peer_row][peer_col] != 0 || next[peer_row][peer_col] != 0
+        });
+        if !peers_left {
+            continue;
+        }
+
+        grid[row][col] = digit;
+        search(next, grid, limit, solutions);
+        grid[row][col] = 0;
+
+        if solutions.len() >= limit {
+            return;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        logic::{Board, LogicalOutcome, LogicalSolver},
+        sudoku::{solve_any, verify_solution},
+    };
+
+    use super::*;
+
+    const PUZZLE: Puzzle = [
+        [5, 3, 0, 0, 7, 0, 0, 0, 0],
+        [6, 0, 0, 1, 9, 5, 0, 0, 0],
+        [9, 8, 0, 0, 0, 0, 0, 6, 0],
+        [8, 0, 0, 0, 6, 0, 0, 0, 3],
+        [4, 0, 0, 8, 0, 3, 0, 0, 1],
+        [7, 0, 0, 0, 2, 0, 0, 0, 6],
+        [0, 6, 0, 0, 0, 0, 2, 8, 0],
+        [0, 0, 0, 4, 1, 9, 0, 0, 5],
+        [0, 0, 0, 0, 8, 0, 0, 7, 9],
+    ];
+
+    #[test]
+    fn check_parse_sukaku() {
+        let sukaku = sukaku_from_puzzle(PUZZLE);
+        assert_eq!(sukaku[0][0], 0b1_0000);
+        assert_eq!(sukaku[0][2], 0b1011);
+
+        let line = sukaku_to_string(sukaku);
+        assert_eq!(line.len(), 729);
+        assert!(line.starts_with("....5...."));
+        assert_eq!(&line[18..27], "12.4.....");
+        assert_eq!(parse_sukaku(&line).unwrap(), sukaku);
+
+        // Zeros for padding, any slot, and whitespace.
+        let mut other = line.replace('.', "0");
+        other.replace_range(18..27, "124000000");
+        other.insert(81, '\n');
+        assert_eq!(parse_sukaku(&other).unwrap(), sukaku);
+
+        assert!(parse_sukaku(&line[1..]).is_err());
+        assert!(parse_sukaku(&line.replacen('.', "x", 1)).is_err());
+    }
+
+    #[test]
+    fn check_solve_sukaku() {
+        let solution = solve_any(PUZZLE).unwrap();
+        let mut sukaku = sukaku_from_puzzle(PUZZLE);
+        assert_eq!(solve_sukaku(sukaku, 2), vec![solution]);
+
+        // Ruling out the solution's digit of a blank leaves no solution.
+        sukaku[0][2] &= !(1 << (solution[0][2] - 1));
+        assert!(solve_sukaku(sukaku, 2).is_empty());
+
+        let open = solve_sukaku([[0x1ff; 9]; 9], 3);
+        assert_eq!(open.len(), 3);
+        assert!(open.iter().all(|&grid| verify_solution([[0; 9]; 9], grid)));
+        assert!(solve_sukaku([[0x1ff; 9]; 9], 0).is_empty());
+    }
+
+    #[test]
+    fn check_logical_sukaku() {
+        let board = Board::from_candidates(sukaku_from_puzzle(PUZZLE)).unwrap();
+        assert_eq!(
+            LogicalSolver::new().try_solve_board(board),
+            LogicalOutcome::Solved(solve_any(PUZZLE).unwrap())
+        );
+
+        let mut sukaku = [[0x1ff; 9]; 9];
+        sukaku[4][4] = 0;
+        assert_eq!(Board::from_candidates(sukaku), None);
+    }
+}
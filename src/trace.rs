@@ -0,0 +1,234 @@
+use crate::{
+    logic::{Board, Link, LogicalSolver, Step, Technique},
+    sudoku::{GridPos, Puzzle, Unit},
+};
+
+/// Writes the trace of a logical solve as one JSON document, e.g. for an external visualizer to
+/// replay:
+///
+/// `{"puzzle": grid, "solved": bool, "grid": grid, "steps": [step, ...]}`
+///
+/// where each grid is 9 rows of 9 digits, 0 for a blank, and "grid" holds the digits placed by the
+/// end.
+/// Each step is written as in [trace_ndjson]. Returns None if the puzzle is invalid.
+pub fn trace_json(puzzle: Puzzle, solver: &LogicalSolver) -> Option<String> {
+    let (steps, board) = trace(puzzle, solver)?;
+
+    Some(format!(
+        "{{\"puzzle\":{},\"solved\":{},\"grid\":{},\"steps\":[{}]}}",
+        grid(puzzle),
+        board.is_solved(),
+        grid(board.grid()),
+        steps.join(",")
+    ))
+}
+
+/// Writes the trace of a logical solve as newline-delimited JSON, one step per line, e.g. for a
+/// notebook to load as a table:
+///
+/// `{"index": 0, "technique": "HiddenSingle", "units": [{"row": 3}], "cells": [[3, 5]],
+/// "links": [{"from": [[0, 0], 3], "to": [[0, 4], 3], "strong": true}],
+/// "eliminations": [[[1, 4], 3]], "placement": [[3, 5], 7] or null,
+/// "grid_before": grid, "candidates_before": candidates, "candidates_after": candidates}`
+///
+/// Cells are [row, col], counting from 0, and candidates are 9 rows of 9 lists of digits, empty for
+/// filled cells. Each line holds the state before the step, so it can be replayed on its own.
+/// Returns None if the puzzle is invalid.
+pub fn trace_ndjson(puzzle: Puzzle, solver: &LogicalSolver) -> Option<String> {
+    let (steps, _) = trace(puzzle, solver)?;
+
+    Some(steps.into_iter().map(|step| step + "\n").collect())
+}
+
+/// Solves a puzzle, writing each step as a JSON object. Also returns the final board.
+fn trace(puzzle: Puzzle, solver: &LogicalSolver) -> Option<(Vec<String>, Board)> {
+    let mut board = Board::new(puzzle)?;
+
+    let mut steps = Vec::new();
+    for (index, step) in solver.solve(puzzle)?.iter().enumerate() {
+        let before = board;
+        board.apply(step);
+        steps.push(step_json(index, step, &before, &board));
+    }
+
+    Some((steps, board))
+}
+
+fn step_json(index: usize, step: &Step, before: &Board, after: &Board) -> String {
+    let placement = match step.placement {
+        Some(placement) => candidate(placement),
+        None => "null".to_string(),
+    };
+
+    format!(
+        "{{\"index\":{index},\"technique\":{},\"units\":{},\"cells\":{},\"links\":{},\
+         \"eliminations\":{},\"placement\":{placement},\"grid_before\":{},\
+         \"candidates_before\":{},\"candidates_after\":{}}}",
+        technique(step.technique),
+        array(step.units.iter().map(|&unit| unit_json(unit))),
+        array(step.cells.iter().map(|&pos| cell(pos))),
+        array(step.links.iter().map(link)),
+        array(
+            step.eliminations
+                .iter()
+                .map(|&elimination| candidate(elimination))
+        ),
+        grid(before.grid()),
+        candidates(before),
+        candidates(after),
+    )
+}
+
+/// Writes items that are already JSON as an array.
+fn array(items: impl Iterator<Item = String>) -> String {
+    format!("[{}]", items.collect::<Vec<_>>().join(","))
+}
+
+fn grid(grid: Puzzle) -> String {
+    array(grid.iter().map(|row| array(row.iter().map(u8::to_string))))
+}
+
+fn candidates(board: &Board) -> String {
+    array((0..9).map(|row| {
+        array((0..9).map(|col| {
+            let set = board.candidates((row, col));
+            array(
+                (1..=9u8)
+                    .filter(|digit| set & (1 << (digit - 1)) != 0)
+                    .map(|digit| digit.to_string()),
+            )
+        }))
+    }))
+}
+
+/// Writes a technique as a string: its variant name, or the name of a custom technique.
+fn technique(technique: Technique) -> String {
+    match technique {
+        Technique::Custom(name) => string(name),
+        technique => format!("\"{technique:?}\""),
+    }
+}
+
+/// Writes text as a JSON string, escaping quotes, backslashes and control characters.
+fn string(text: &str) -> String {
+    let mut json = String::from('"');
+    for char in text.chars() {
+        match char {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            char if char.is_control() => json.push_str(&format!("\\u{:04x}", char as u32)),
+            char => json.push(char),
+        }
+    }
+    json.push('"');
+
+    json
+}
+
+fn cell((row, col): GridPos) -> String {
+    format!("[{row},{col}]")
+}
+
+/// Writes a digit in a cell as [[row, col], digit].
+fn candidate((pos, digit): (GridPos, u8)) -> String {
+    format!("[{},{digit}]", cell(pos))
+}
+
+fn link(link: &Link) -> String {
+    format!(
+        "{{\"from\":{},\"to\":{},\"strong\":{}}}",
+        candidate(link.from),
+        candidate(link.to),
+        link.strong
+    )
+}
+
+fn unit_json(unit: Unit) -> String {
+    match unit {
+        Unit::Row(row) => format!("{{\"row\":{row}}}"),
+        Unit::Col(col) => format!("{{\"col\":{col}}}"),
+        Unit::Square(square) => format!("{{\"square\":{square}}}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PUZZLE: Puzzle = [
+        [5, 3, 0, 0, 7, 0, 0, 0, 0],
+        [6, 0, 0, 1, 9, 5, 0, 0, 0],
+        [9, 8, 0, 0, 0, 0, 0, 6, 0],
+        [8, 0, 0, 0, 6, 0, 0, 0, 3],
+        [4, 0, 0, 8, 0, 3, 0, 0, 1],
+        [7, 0, 0, 0, 2, 0, 0, 0, 6],
+        [0, 6, 0, 0, 0, 0, 2, 8, 0],
+        [0, 0, 0, 4, 1, 9, 0, 0, 5],
+        [0, 0, 0, 0, 8, 0, 0, 7, 9],
+    ];
+
+    #[test]
+    fn check_trace_ndjson() {
+        let solver = LogicalSolver::new();
+        let trace = trace_ndjson(PUZZLE, &solver).unwrap();
+        let lines = trace.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 51);
+        assert!(lines[0].starts_with("{\"index\":0,\"technique\":\""));
+        assert!(lines[50].starts_with("{\"index\":50,"));
+        assert!(lines.iter().all(|line| line.ends_with("]]]}")));
+        assert!(lines[0].contains("\"grid_before\":[[5,3,0,0,7,0,0,0,0],"));
+        // r0c2 can be 1, 2 or 4 at first.
+        assert!(lines[0].contains("\"candidates_before\":[[[],[],[1,2,4],"));
+
+        let mut invalid = PUZZLE;
+        invalid[0][2] = 5;
+        assert_eq!(trace_ndjson(invalid, &solver), None);
+    }
+
+    #[test]
+    fn check_trace_json() {
+        let solver = LogicalSolver::new();
+        let trace = trace_json(PUZZLE, &solver).unwrap();
+        assert!(trace.starts_with("{\"puzzle\":[[5,3,0,0,7,0,0,0,0],"));
+        assert!(trace.contains("\"solved\":true,\"grid\":[[5,3,4,6,7,8,9,1,2],"));
+        assert!(trace.ends_with("]]]}]}"));
+        assert_eq!(trace.matches("\"index\":").count(), 51);
+
+        // Gets stuck with singles only.
+        let singles = LogicalSolver::with_techniques(&[Technique::NakedSingle]);
+        let trace = trace_json([[0; 9]; 9], &singles).unwrap();
+        assert!(trace.contains("\"solved\":false,"));
+        assert!(trace.ends_with("\"steps\":[]}"));
+    }
+
+    #[test]
+    fn check_string() {
+        assert_eq!(string("oracle"), "\"oracle\"");
+        assert_eq!(string("a \"b\" \\ c\n"), "\"a \\\"b\\\" \\\\ c\\u000a\"");
+        assert_eq!(technique(Technique::Custom("x\"y")), "\"x\\\"y\"");
+        assert_eq!(technique(Technique::XWing), "\"XWing\"");
+    }
+
+    #[test]
+    fn check_step_json() {
+        let board = Board::new(PUZZLE).unwrap();
+        let step = Step {
+            technique: Technique::XChain,
+            units: vec![Unit::Row(0), Unit::Square(8)],
+            cells: vec![(0, 2)],
+            links: vec![Link {
+                from: ((0, 2), 1),
+                to: ((0, 3), 1),
+                strong: true,
+            }],
+            eliminations: vec![((0, 2), 1)],
+            placement: None,
+        };
+        let json = step_json(3, &step, &board, &board);
+        assert!(json.starts_with(
+            "{\"index\":3,\"technique\":\"XChain\",\"units\":[{\"row\":0},{\"square\":8}],\
+             \"cells\":[[0,2]],\"links\":[{\"from\":[[0,2],1],\"to\":[[0,3],1],\"strong\":true}],\
+             \"eliminations\":[[[0,2],1]],\"placement\":null,\"grid_before\":"
+        ));
+    }
+}
@@ -0,0 +1,435 @@
+use std::{array, fmt, str::FromStr};
+
+use anyhow::{Error, anyhow};
+
+use crate::{
+    i18n::{Message, tr},
+    sudoku::Puzzle,
+};
+
+/// A rearrangement of a grid that keeps valid grids valid, made up of a transpose, a permutation of
+/// the rows that keeps the rows of each band together, a similar permutation of the cols, and a
+/// relabeling of the digits, applied in that order.
+///
+/// Build one from the basic operations and combine them with [Transformation::then]. Every
+/// transformation can be undone with its [Transformation::inverse]. Converts to and from a string
+/// with [fmt::Display] and [FromStr], e.g. for storing it next to a puzzle.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Transformation {
+    transpose: bool,
+
+    /// Row of the transposed grid that ends up in each row.
+    rows: [usize; 9],
+
+    /// Col of the transposed grid that ends up in each col.
+    cols: [usize; 9],
+
+    /// New digit for each digit, indexed by digit - 1.
+    digits: [u8; 9],
+}
+
+const IDENTITY: [usize; 9] = [0, 1, 2, 3, 4, 5, 6, 7, 8];
+
+impl Transformation {
+    /// Leaves the grid as it is.
+    pub fn identity() -> Transformation {
+        Transformation {
+            transpose: false,
+            rows: IDENTITY,
+            cols: IDENTITY,
+            digits: [1, 2, 3, 4, 5, 6, 7, 8, 9],
+        }
+    }
+
+    /// Rotates the grid by 90 degrees clockwise.
+    pub fn rotate90() -> Transformation {
+        Transformation {
+            transpose: true,
+            cols: IDENTITY.map(|col| 8 - col),
+            ..Transformation::identity()
+        }
+    }
+
+    /// Mirrors the grid left to right.
+    pub fn mirror() -> Transformation {
+        Transformation {
+            cols: IDENTITY.map(|col| 8 - col),
+            ..Transformation::identity()
+        }
+    }
+
+    /// Swaps the rows and cols of the grid.
+    pub fn transpose() -> Transformation {
+        Transformation {
+            transpose: true,
+            ..Transformation::identity()
+        }
+    }
+
+    /// Relabels the digits, turning each digit into digits[digit - 1]. Returns None if digits is
+    /// not a permutation of 1 to 9.
+    pub fn permute_digits(digits: [u8; 9]) -> Option<Transformation> {
+        is_permutation(digits.map(|digit| digit.wrapping_sub(1) as usize)).then_some(
+            Transformation {
+                digits,
+                ..Transformation::identity()
+            },
+        )
+    }
+
+    /// Swaps two rows, 0 to 2, within a band, 0 to 2. Returns None if any of them is out of range.
+    pub fn swap_rows(band: usize, first: usize, second: usize) -> Option<Transformation> {
+        (band < 3 && first < 3 && second < 3).then(|| Transformation {
+            rows: swapped(band * 3 + first, band * 3 + second),
+            ..Transformation::identity()
+        })
+    }
+
+    /// Swaps two cols, 0 to 2, within a stack, 0 to 2. Returns None if any of them is out of range.
+    pub fn swap_cols(stack: usize, first: usize, second: usize) -> Option<Transformation> {
+        (stack < 3 && first < 3 && second < 3).then(|| Transformation {
+            cols: swapped(stack * 3 + first, stack * 3 + second),
+            ..Transformation::identity()
+        })
+    }
+
+    /// Swaps two bands, 0 to 2. Returns None if either is out of range.
+    pub fn swap_bands(first: usize, second: usize) -> Option<Transformation> {
+        (first < 3 && second < 3).then(|| Transformation {
+            rows: swapped_triples(first, second),
+            ..Transformation::identity()
+        })
+    }
+
+    /// Swaps two stacks, 0 to 2. Returns None if either is out of range.
+    pub fn swap_stacks(first: usize, second: usize) -> Option<Transformation> {
+        (first < 3 && second < 3).then(|| Transformation {
+            cols: swapped_triples(first, second),
+            ..Transformation::identity()
+        })
+    }
+
+    /// Builds a transformation from its parts: whether to transpose, the row and col of the
+    /// transposed grid that end up in each row and col, and the new digit for each digit. Returns
+    /// None unless the rows and cols keep the bands and stacks together, and the digits are a
+    /// permutation of 1 to 9.
+    pub(crate) fn new(
+        transpose: bool,
+        rows: [usize; 9],
+        cols: [usize; 9],
+        digits: [u8; 9],
+    ) -> Option<Transformation> {
+        let valid = keeps_bands(rows)
+            && keeps_bands(cols)
+            && is_permutation(digits.map(|digit| digit.wrapping_sub(1) as usize));
+
+        valid.then_some(Transformation {
+            transpose,
+            rows,
+            cols,
+            digits,
+        })
+    }
+
+    /// Applies the transformation to a puzzle. Blanks stay blank.
+    pub fn apply(&self, puzzle: Puzzle) -> Puzzle {
+        array::from_fn(|row| {
+            array::from_fn(|col| {
+                let (from_row, from_col) = match self.transpose {
+                    false => (self.rows[row], self.cols[col]),
+                    true => (self.cols[col], self.rows[row]),
+                };
+
+                match puzzle[from_row][from_col] {
+                    0 => 0,
+                    digit => self.digits[digit as usize - 1],
+                }
+            })
+        })
+    }
+
+    /// Combines two transformations into one that applies this one, then next.
+    pub fn then(&self, next: &Transformation) -> Transformation {
+        let (transpose, rows, cols) = match next.transpose {
+            false => (
+                self.transpose,
+                next.rows.map(|row| self.rows[row]),
+                next.cols.map(|col| self.cols[col]),
+            ),
+            true => (
+                !self.transpose,
+                next.rows.map(|row| self.cols[row]),
+                next.cols.map(|col| self.rows[col]),
+            ),
+        };
+
+        Transformation {
+            transpose,
+            rows,
+            cols,
+            digits: self.digits.map(|digit| next.digits[digit as usize - 1]),
+        }
+    }
+
+    /// Gets the transformation that undoes this one.
+    pub fn inverse(&self) -> Transformation {
+        let rows = invert(self.rows);
+        let cols = invert(self.cols);
+        let mut digits = [0; 9];
+        for (index, &digit) in self.digits.iter().enumerate() {
+            digits[digit as usize - 1] = index as u8 + 1;
+        }
+
+        let (rows, cols) = match self.transpose {
+            false => (rows, cols),
+            true => (cols, rows),
+        };
+
+        Transformation {
+            transpose: self.transpose,
+            rows,
+            cols,
+            digits,
+        }
+    }
+}
+
+impl Default for Transformation {
+    fn default() -> Self {
+        Transformation::identity()
+    }
+}
+
+/// Formats as 4 fields separated by colons: 1 if transposed (else 0), the rows, the cols, and the
+/// digits, e.g. `0:012345678:876543210:123456789` for [Transformation::mirror].
+impl fmt::Display for Transformation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let join = |values: &[usize]| values.iter().map(usize::to_string).collect::<String>();
+
+        write!(
+            f,
+            "{}:{}:{}:{}",
+            self.transpose as u8,
+            join(&self.rows),
+            join(&self.cols),
+            join(&self.digits.map(usize::from))
+        )
+    }
+}
+
+impl FromStr for Transformation {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || anyhow!(tr(Message::InvalidTransformation));
+
+        let fields = s.trim().split(':').collect::<Vec<_>>();
+        let [transpose, rows, cols, digits] = fields[..] else {
+            return Err(invalid());
+        };
+
+        let transpose = match transpose {
+            "0" => false,
+            "1" => true,
+            _ => return Err(invalid()),
+        };
+        let parse = |field: &str| -> Option<[u8; 9]> {
+            let values = field
+                .chars()
+                .map(|c| c.to_digit(10).map(|digit| digit as u8))
+                .collect::<Option<Vec<_>>>()?;
+            values.try_into().ok()
+        };
+
+        let rows = parse(rows).ok_or_else(invalid)?.map(usize::from);
+        let cols = parse(cols).ok_or_else(invalid)?.map(usize::from);
+        let digits = parse(digits).ok_or_else(invalid)?;
+
+        Transformation::new(transpose, rows, cols, digits).ok_or_else(invalid)
+    }
+}
+
+/// Checks whether values are a permutation of 0 to 8.
+fn is_permutation(values: [usize; 9]) -> bool {
+    let mut seen = [false; 9];
+    values
+        .into_iter()
+        .all(|value| value < 9 && !std::mem::replace(&mut seen[value], true))
+}
+
+/// Checks whether an order of rows (or cols) is a permutation that keeps the rows of each band
+/// together.
+fn keeps_bands(lines: [usize; 9]) -> bool {
+    is_permutation(lines)
+        && lines
+            .chunks(3)
+            .all(|band| band.iter().all(|line| line / 3 == band[0] / 3))
+}
+
+/// Gets the order that swaps two rows (or cols).
+fn swapped(first: usize, second: usize) -> [usize; 9] {
+    let mut lines = IDENTITY;
+    lines.swap(first, second);
+    lines
+}
+
+/// Gets the order that swaps two bands (or stacks).
+fn swapped_triples(first: usize, second: usize) -> [usize; 9] {
+    let mut bands = [0, 1, 2];
+    bands.swap(first, second);
+    array::from_fn(|line| bands[line / 3] * 3 + line % 3)
+}
+
+/// Gets the inverse of a permutation.
+fn invert(lines: [usize; 9]) -> [usize; 9] {
+    let mut inverse = [0; 9];
+    for (index, &line) in lines.iter().enumerate() {
+        inverse[line] = index;
+    }
+    inverse
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::sudoku::{find_conflicts, solve_any};
+
+    use super::*;
+
+    const PUZZLE: Puzzle = [
+        [5, 3, 0, 0, 7, 0, 0, 0, 0],
+        [6, 0, 0, 1, 9, 5, 0, 0, 0],
+        [9, 8, 0, 0, 0, 0, 0, 6, 0],
+        [8, 0, 0, 0, 6, 0, 0, 0, 3],
+        [4, 0, 0, 8, 0, 3, 0, 0, 1],
+        [7, 0, 0, 0, 2, 0, 0, 0, 6],
+        [0, 6, 0, 0, 0, 0, 2, 8, 0],
+        [0, 0, 0, 4, 1, 9, 0, 0, 5],
+        [0, 0, 0, 0, 8, 0, 0, 7, 9],
+    ];
+
+    fn all_kinds() -> Vec<Transformation> {
+        vec![
+            Transformation::rotate90(),
+            Transformation::mirror(),
+            Transformation::transpose(),
+            Transformation::permute_digits([9, 8, 7, 6, 5, 4, 3, 2, 1]).unwrap(),
+            Transformation::swap_rows(1, 0, 2).unwrap(),
+            Transformation::swap_cols(2, 1, 2).unwrap(),
+            Transformation::swap_bands(0, 2).unwrap(),
+            Transformation::swap_stacks(0, 1).unwrap(),
+        ]
+    }
+
+    #[test]
+    fn check_basic_operations() {
+        let rotated = Transformation::rotate90().apply(PUZZLE);
+        assert_eq!(rotated[0], [0, 0, 0, 7, 4, 8, 9, 6, 5]);
+        assert_eq!(rotated[8], [9, 5, 0, 6, 1, 3, 0, 0, 0]);
+        assert_eq!(
+            Transformation::mirror().apply(PUZZLE)[0],
+            [0, 0, 0, 0, 7, 0, 0, 3, 5]
+        );
+        assert_eq!(
+            Transformation::transpose().apply(PUZZLE)[0],
+            [5, 6, 9, 8, 4, 7, 0, 0, 0]
+        );
+        assert_eq!(
+            Transformation::swap_rows(0, 0, 1).unwrap().apply(PUZZLE)[0],
+            PUZZLE[1]
+        );
+        assert_eq!(
+            Transformation::swap_bands(0, 2).unwrap().apply(PUZZLE)[0],
+            PUZZLE[6]
+        );
+        assert_eq!(
+            Transformation::swap_stacks(0, 1).unwrap().apply(PUZZLE)[1][..3],
+            [1, 9, 5]
+        );
+        assert_eq!(
+            Transformation::swap_cols(0, 0, 1).unwrap().apply(PUZZLE)[0][..2],
+            [3, 5]
+        );
+
+        // Rows and cols must stay within their band or stack.
+        assert_eq!(Transformation::swap_rows(0, 0, 4), None);
+        assert_eq!(Transformation::swap_rows(3, 0, 1), None);
+        assert_eq!(Transformation::swap_cols(0, 3, 1), None);
+        assert_eq!(Transformation::swap_bands(0, 3), None);
+        assert_eq!(Transformation::swap_stacks(4, 1), None);
+
+        let relabeled = Transformation::permute_digits([2, 3, 4, 5, 6, 7, 8, 9, 1]).unwrap();
+        assert_eq!(relabeled.apply(PUZZLE)[0], [6, 4, 0, 0, 8, 0, 0, 0, 0]);
+        assert_eq!(Transformation::permute_digits([1; 9]), None);
+        assert_eq!(
+            Transformation::permute_digits([0, 1, 2, 3, 4, 5, 6, 7, 8]),
+            None
+        );
+
+        let solution = solve_any(PUZZLE).unwrap();
+        for transformation in all_kinds() {
+            assert!(find_conflicts(transformation.apply(solution)).is_empty());
+        }
+    }
+
+    #[test]
+    fn check_then_and_inverse() {
+        let kinds = all_kinds();
+        for first in &kinds {
+            assert_eq!(first.inverse().apply(first.apply(PUZZLE)), PUZZLE);
+            assert_eq!(first.then(&first.inverse()), Transformation::identity());
+
+            for second in &kinds {
+                let combined = first.then(second);
+                assert_eq!(combined.apply(PUZZLE), second.apply(first.apply(PUZZLE)));
+                assert_eq!(combined.inverse().apply(combined.apply(PUZZLE)), PUZZLE);
+            }
+        }
+
+        let rotate90 = Transformation::rotate90();
+        let full_turn = rotate90.then(&rotate90).then(&rotate90).then(&rotate90);
+        assert_eq!(full_turn, Transformation::identity());
+    }
+
+    #[test]
+    fn check_to_and_from_string() {
+        assert_eq!(
+            Transformation::mirror().to_string(),
+            "0:012345678:876543210:123456789"
+        );
+
+        let kinds = all_kinds();
+        let combined = kinds
+            .iter()
+            .fold(Transformation::identity(), |all, next| all.then(next));
+        for transformation in kinds.into_iter().chain([combined]) {
+            let parsed = transformation
+                .to_string()
+                .parse::<Transformation>()
+                .unwrap();
+            assert_eq!(parsed, transformation);
+        }
+
+        // Swapping rows of different bands.
+        assert!(
+            "0:312045678:012345678:123456789"
+                .parse::<Transformation>()
+                .is_err()
+        );
+        assert!(
+            "0:012345678:012345678:113456789"
+                .parse::<Transformation>()
+                .is_err()
+        );
+        assert!(
+            "2:012345678:012345678:123456789"
+                .parse::<Transformation>()
+                .is_err()
+        );
+        assert!(
+            "0:01234567:012345678:123456789"
+                .parse::<Transformation>()
+                .is_err()
+        );
+        assert!("".parse::<Transformation>().is_err());
+    }
+}